@@ -6,12 +6,15 @@
 //! * Optionally: Add [`TnuaAvian3dSensorShape`] to the sensor entities. This means the entity of
 //!   the characters controlled by Tnua, but also other things like the entity generated by
 //!   `TnuaCrouchEnforcer`, that can be affected with a closure.
+//! * Alternatively: Add [`TnuaAvian3dAutoSensorShape`] to derive the sensor shape from the entity's
+//!   own collider.
 use avian3d::{
     dynamics::rigid_body::mass_properties::components::GlobalAngularInertia, prelude::*,
     schedule::PhysicsStepSet,
 };
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
 use bevy::prelude::*;
+use bevy::utils::Parallel;
 use bevy_tnua_physics_integration_layer::math::AdjustPrecision;
 use bevy_tnua_physics_integration_layer::math::AsF32;
 use bevy_tnua_physics_integration_layer::math::Float;
@@ -19,9 +22,11 @@ use bevy_tnua_physics_integration_layer::math::Vector3;
 
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostPlatform;
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostSensor;
+use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostSurface;
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaToggle;
 use bevy_tnua_physics_integration_layer::data_for_backends::{
-    TnuaMotor, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker,
+    TnuaColliderDimensions, TnuaFeetCollider, TnuaMotor, TnuaMotorFilter, TnuaMotorPushLimit,
+    TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker,
 };
 use bevy_tnua_physics_integration_layer::subservient_sensors::TnuaSubservientSensor;
 use bevy_tnua_physics_integration_layer::TnuaPipelineStages;
@@ -55,10 +60,18 @@ impl Plugin for TnuaAvian3dPlugin {
             self.schedule,
             (
                 update_rigid_body_trackers_system,
+                update_collider_dimensions_system,
+                update_feet_collider_dimensions_system,
                 update_proximity_sensors_system,
             )
                 .in_set(TnuaPipelineStages::Sensors),
         );
+        app.add_systems(
+            self.schedule,
+            update_auto_sensor_shapes_system
+                .in_set(TnuaPipelineStages::Sensors)
+                .before(update_proximity_sensors_system),
+        );
         app.add_systems(
             self.schedule,
             apply_motors_system.in_set(TnuaPipelineStages::Motors),
@@ -70,6 +83,118 @@ impl Plugin for TnuaAvian3dPlugin {
 #[derive(Component)]
 pub struct TnuaAvian3dSensorShape(pub Collider);
 
+/// Add this component to derive [`TnuaAvian3dSensorShape`] from the entity's own [`Collider`].
+///
+/// The sensor shape will be a flat cylinder, with the radius of the collider (measured from its
+/// bounding box on the X and Z axes) multiplied by the given factor. A factor slightly smaller than
+/// `1.0` (e.g. `0.9`) prevents the sensor from hitting walls the character touches sideways, while
+/// still keeping the character from dropping off ledges too early.
+///
+/// The sensor shape is updated whenever the collider or this component change. This assumes the
+/// sensor is cast along the collider's Y axis.
+#[derive(Component)]
+pub struct TnuaAvian3dAutoSensorShape(pub Float);
+
+fn update_auto_sensor_shapes_system(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &Collider, &TnuaAvian3dAutoSensorShape),
+        Or<(Changed<Collider>, Changed<TnuaAvian3dAutoSensorShape>)>,
+    >,
+) {
+    for (entity, collider, TnuaAvian3dAutoSensorShape(scale)) in query.iter() {
+        let aabb = collider.aabb(Vector3::ZERO, Rotation::default());
+        let size = aabb.max - aabb.min;
+        let radius = 0.5 * scale * size.x.min(size.z);
+        commands
+            .entity(entity)
+            .insert(TnuaAvian3dSensorShape(Collider::cylinder(radius, 0.0)));
+    }
+}
+
+fn update_collider_dimensions_system(
+    mut query: Query<
+        (Entity, &GlobalTransform, &mut TnuaColliderDimensions),
+        Without<TnuaFeetCollider>,
+    >,
+    colliders_query: Query<(&Collider, &GlobalTransform)>,
+    children_query: Query<&Children>,
+    collider_parents_query: Query<&ColliderParent>,
+) {
+    for (owner_entity, owner_transform, mut dimensions) in query.iter_mut() {
+        // The colliders may be on child entities (e.g. with character models loaded from glTF).
+        let owner_collider_entities = std::iter::once(owner_entity).chain(
+            children_query
+                .iter_descendants(owner_entity)
+                .filter(|entity| {
+                    collider_parents_query
+                        .get(*entity)
+                        .is_ok_and(|collider_parent| collider_parent.get() == owner_entity)
+                }),
+        );
+        let Some((min_y, max_y)) = owner_collider_entities
+            .filter_map(|entity| {
+                let (collider, collider_transform) = colliders_query.get(entity).ok()?;
+                let aabb = if entity == owner_entity {
+                    collider.aabb(Vector3::ZERO, Rotation::default())
+                } else {
+                    let relative_transform = collider_transform.reparented_to(owner_transform);
+                    collider.aabb(
+                        relative_transform.translation.adjust_precision(),
+                        relative_transform.rotation.adjust_precision(),
+                    )
+                };
+                Some((aabb.min.y, aabb.max.y))
+            })
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+        else {
+            continue;
+        };
+        let bottom_offset = -min_y;
+        let half_height = 0.5 * (max_y - min_y);
+        // Changes to child colliders cannot be detected from the character entity, so this runs
+        // every frame - but should only mark the dimensions as changed when they actually change.
+        if dimensions.bottom_offset != bottom_offset || dimensions.half_height != half_height {
+            *dimensions = TnuaColliderDimensions {
+                bottom_offset,
+                half_height,
+            };
+        }
+    }
+}
+
+fn update_feet_collider_dimensions_system(
+    mut query: Query<(
+        &GlobalTransform,
+        &TnuaFeetCollider,
+        &mut TnuaColliderDimensions,
+    )>,
+    feet_query: Query<(&Collider, &GlobalTransform)>,
+) {
+    for (owner_transform, TnuaFeetCollider(feet_entity), mut dimensions) in query.iter_mut() {
+        let Ok((collider, feet_transform)) = feet_query.get(*feet_entity) else {
+            continue;
+        };
+        // The feet collider may be offset from the origin of the character entity.
+        let offset = feet_transform
+            .reparented_to(owner_transform)
+            .translation
+            .y
+            .adjust_precision();
+        let aabb = collider.aabb(Vector3::ZERO, Rotation::default());
+        let bottom_offset = -(offset + aabb.min.y);
+        let half_height = 0.5 * (aabb.max.y - aabb.min.y);
+        // Changes to the feet collider cannot be detected from the character entity, so this runs
+        // every frame - but should only mark the dimensions as changed when they actually change.
+        if dimensions.bottom_offset != bottom_offset || dimensions.half_height != half_height {
+            *dimensions = TnuaColliderDimensions {
+                bottom_offset,
+                half_height,
+            };
+        }
+    }
+}
+
 fn update_rigid_body_trackers_system(
     gravity: Res<Gravity>,
     mut query: Query<(
@@ -112,12 +237,17 @@ fn update_proximity_sensors_system(
         Option<&TnuaToggle>,
     )>,
     collision_layers_entity: Query<&CollisionLayers>,
+    feet_colliders_query: Query<&TnuaFeetCollider>,
+    children_query: Query<&Children>,
+    collider_parents_query: Query<&ColliderParent>,
     other_object_query: Query<(
         Option<(&GlobalTransform, &LinearVelocity, &AngularVelocity)>,
         Option<&CollisionLayers>,
         Has<TnuaGhostPlatform>,
+        Option<&TnuaGhostSurface>,
         Has<Sensor>,
     )>,
+    owner_collider_entities_buffers: Local<Parallel<Vec<Entity>>>,
 ) {
     query.par_iter_mut().for_each(
         |(
@@ -135,6 +265,11 @@ fn update_proximity_sensors_system(
                 TnuaToggle::Enabled => {}
             }
 
+            if sensor.skip_next_cast {
+                sensor.skip_next_cast = false;
+                return;
+            }
+
             // TODO: is there any point in doing these transformations as f64 when that feature
             // flag is active?
             let cast_origin = transform
@@ -155,9 +290,39 @@ fn update_proximity_sensors_system(
                 owner_entity
             };
 
-            let collision_layers = collision_layers_entity.get(owner_entity).ok();
+            // The colliders may be on child entities (e.g. with character models loaded from glTF).
+            let mut owner_collider_entities = owner_collider_entities_buffers.borrow_local_mut();
+            owner_collider_entities.clear();
+            owner_collider_entities.push(owner_entity);
+            owner_collider_entities.extend(children_query.iter_descendants(owner_entity).filter(
+                |entity| {
+                    collider_parents_query
+                        .get(*entity)
+                        .is_ok_and(|collider_parent| collider_parent.get() == owner_entity)
+                },
+            ));
+
+            let feet_collider_entity = feet_colliders_query
+                .get(owner_entity)
+                .ok()
+                .map(|TnuaFeetCollider(entity)| *entity);
+            // The feet collider goes first, so that it's the one whose collision layers are used.
+            if let Some(feet_collider_entity) = feet_collider_entity {
+                owner_collider_entities.retain(|entity| *entity != feet_collider_entity);
+                owner_collider_entities.insert(0, feet_collider_entity);
+            }
+
+            let collision_layers = if let Some(feet_collider_entity) = feet_collider_entity {
+                collision_layers_entity.get(feet_collider_entity).ok()
+            } else {
+                owner_collider_entities
+                    .iter()
+                    .find_map(|entity| collision_layers_entity.get(*entity).ok())
+            };
 
+            let max_hits = sensor.max_hits.max(1);
             let mut final_sensor_output = None;
+            let mut additional_outputs = Vec::new();
             if let Some(ghost_sensor) = ghost_sensor.as_mut() {
                 ghost_sensor.0.clear();
             }
@@ -170,19 +335,21 @@ fn update_proximity_sensors_system(
                 } = cast_result;
 
                 // This fixes https://github.com/idanarye/bevy-tnua/issues/14
-                if let Some(contacts) = collisions.get(owner_entity, entity) {
-                    let same_order = owner_entity == contacts.entity1;
-                    for manifold in contacts.manifolds.iter() {
-                        if !manifold.contacts.is_empty() {
-                            let manifold_normal = if same_order {
-                                manifold.normal2.adjust_precision()
-                            } else {
-                                manifold.normal1.adjust_precision()
-                            };
-                            if sensor.intersection_match_prevention_cutoff
-                                < manifold_normal.dot(cast_direction.adjust_precision())
-                            {
-                                return true;
+                for owner_collider_entity in owner_collider_entities.iter() {
+                    if let Some(contacts) = collisions.get(*owner_collider_entity, entity) {
+                        let same_order = *owner_collider_entity == contacts.entity1;
+                        for manifold in contacts.manifolds.iter() {
+                            if !manifold.contacts.is_empty() {
+                                let manifold_normal = if same_order {
+                                    manifold.normal2.adjust_precision()
+                                } else {
+                                    manifold.normal1.adjust_precision()
+                                };
+                                if sensor.intersection_match_prevention_cutoff
+                                    < manifold_normal.dot(cast_direction.adjust_precision())
+                                {
+                                    return true;
+                                }
                             }
                         }
                     }
@@ -194,12 +361,16 @@ fn update_proximity_sensors_system(
                 let Ok((
                     entity_kinematic_data,
                     entity_collision_layers,
-                    entity_is_ghost,
+                    entity_is_ghost_platform,
+                    entity_ghost_surface,
                     entity_is_sensor,
                 )) = other_object_query.get(entity)
                 else {
                     return false;
                 };
+                let entity_is_ghost = entity_is_ghost_platform
+                    || entity_ghost_surface
+                        .is_some_and(|ghost_surface| ghost_surface.is_passable_in(cast_direction));
 
                 let entity_linvel;
                 let entity_angvel;
@@ -238,19 +409,29 @@ fn update_proximity_sensors_system(
                 };
 
                 if entity_is_ghost {
-                    if let Some(ghost_sensor) = ghost_sensor.as_mut() {
-                        ghost_sensor.0.push(sensor_output);
+                    if final_sensor_output.is_none() {
+                        if let Some(ghost_sensor) = ghost_sensor.as_mut() {
+                            ghost_sensor.0.push(sensor_output);
+                        }
                     }
                     true
-                } else if entity_is_sensor || excluded_by_collision_layers() {
+                } else if entity_is_sensor
+                    || (entity_ghost_surface.is_none() && excluded_by_collision_layers())
+                {
+                    // A ghost surface that is not passable in the cast direction is sensed like a
+                    // regular solid collider, even if its collision layers exclude the character.
                     true
-                } else {
+                } else if final_sensor_output.is_none() {
                     final_sensor_output = Some(sensor_output);
-                    false
+                    1 < max_hits
+                } else {
+                    additional_outputs.push(sensor_output);
+                    1 + additional_outputs.len() < max_hits
                 }
             };
 
-            let query_filter = SpatialQueryFilter::from_excluded_entities([owner_entity]);
+            let query_filter =
+                SpatialQueryFilter::from_excluded_entities(owner_collider_entities.iter().copied());
             if let Some(TnuaAvian3dSensorShape(shape)) = shape {
                 let (_, owner_rotation, _) = transform.to_scale_rotation_translation();
                 let owner_rotation = Quat::from_axis_angle(
@@ -298,14 +479,20 @@ fn update_proximity_sensors_system(
                 );
             }
             sensor.output = final_sensor_output;
+            additional_outputs.sort_by(|a, b| a.proximity.total_cmp(&b.proximity));
+            sensor.additional_outputs = additional_outputs;
         },
     );
 }
 
 #[allow(clippy::type_complexity)]
 fn apply_motors_system(
+    time: Res<Time>,
+    collisions: Res<Collisions>,
     mut query: Query<(
+        Entity,
         &TnuaMotor,
+        &mut Position,
         &mut LinearVelocity,
         &mut AngularVelocity,
         &ComputedMass,
@@ -313,10 +500,20 @@ fn apply_motors_system(
         &mut ExternalForce,
         &mut ExternalTorque,
         Option<&TnuaToggle>,
+        Option<&TnuaMotorPushLimit>,
+        Option<&TnuaMotorFilter>,
     )>,
+    rigid_bodies_query: Query<&RigidBody>,
+    rotations_query: Query<&Rotation>,
+    children_query: Query<&Children>,
+    collider_parents_query: Query<&ColliderParent>,
+    mut owner_collider_entities: Local<Vec<Entity>>,
 ) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
     for (
+        entity,
         motor,
+        mut position,
         mut linare_velocity,
         mut angular_velocity,
         mass,
@@ -324,6 +521,8 @@ fn apply_motors_system(
         mut external_force,
         mut external_torque,
         tnua_toggle,
+        push_limit,
+        motor_filter,
     ) in query.iter_mut()
     {
         match tnua_toggle.copied().unwrap_or_default() {
@@ -333,21 +532,73 @@ fn apply_motors_system(
             }
             TnuaToggle::Enabled => {}
         }
-        if motor.lin.boost.is_finite() {
-            linare_velocity.0 += motor.lin.boost;
-        }
-        if motor.lin.acceleration.is_finite() {
-            external_force.set_force(motor.lin.acceleration * mass.value());
+        let mut lin = motor.lin.clone();
+        if let Some(push_limit) = push_limit {
+            // The colliders may be on child entities (e.g. with character models loaded from glTF).
+            owner_collider_entities.clear();
+            owner_collider_entities.push(entity);
+            owner_collider_entities.extend(children_query.iter_descendants(entity).filter(
+                |child| {
+                    collider_parents_query
+                        .get(*child)
+                        .is_ok_and(|collider_parent| collider_parent.get() == entity)
+                },
+            ));
+            for owner_collider_entity in owner_collider_entities.iter() {
+                for contacts in collisions.collisions_with_entity(*owner_collider_entity) {
+                    let same_order = *owner_collider_entity == contacts.entity1;
+                    let other_entity = if same_order {
+                        contacts.entity2
+                    } else {
+                        contacts.entity1
+                    };
+                    if !rigid_bodies_query
+                        .get(other_entity)
+                        .is_ok_and(RigidBody::is_dynamic)
+                    {
+                        continue;
+                    }
+                    let Ok(other_rotation) = rotations_query.get(other_entity) else {
+                        continue;
+                    };
+                    for manifold in contacts.manifolds.iter() {
+                        if manifold.contacts.is_empty() {
+                            continue;
+                        }
+                        // Like in the sensor, this normal points from the other body to the
+                        // character. The pushed body may be rotated, so it must be in world space.
+                        let manifold_normal = if same_order {
+                            manifold.global_normal2(other_rotation)
+                        } else {
+                            manifold.global_normal1(other_rotation)
+                        };
+                        let push_direction = -manifold_normal.adjust_precision();
+                        push_limit.limit_vel_change(
+                            &mut lin,
+                            push_direction,
+                            mass.value(),
+                            frame_duration,
+                        );
+                    }
+                }
+            }
         }
-        if motor.ang.boost.is_finite() {
-            angular_velocity.0 += motor.ang.boost;
+        let mut ang = motor.ang.clone();
+        if let Some(motor_filter) = motor_filter {
+            motor_filter.apply(&mut lin, &mut ang);
         }
-        if motor.ang.acceleration.is_finite() {
-            external_torque.set_torque(
-                // NOTE: I did not actually verify that this is the correct formula. Nothing uses
-                // angular acceleration yet - only angular impulses.
-                inertia.value() * motor.ang.acceleration,
-            );
+        lin.quarantine_non_finite(entity, "linear");
+        ang.quarantine_non_finite(entity, "angular");
+        if let Some(teleport) = motor.teleport {
+            position.0 = teleport;
         }
+        linare_velocity.0 += lin.boost;
+        external_force.set_force(lin.acceleration * mass.value());
+        angular_velocity.0 += ang.boost;
+        external_torque.set_torque(
+            // NOTE: I did not actually verify that this is the correct formula. Nothing uses
+            // angular acceleration yet - only angular impulses.
+            inertia.value() * ang.acceleration,
+        );
     }
 }
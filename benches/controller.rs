@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use bevy_tnua::math::{Float, Vector3};
+use bevy_tnua::prelude::*;
+use bevy_tnua::{TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker};
+
+/// Builds an app with `count` characters standing on the ground, without a physics backend.
+///
+/// The sensors and trackers are filled with constant data, so this only measures Tnua's logic.
+fn create_app(count: usize) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TnuaControllerPlugin::default()));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / 60.0,
+    )));
+    app.add_systems(Update, feed_controllers.in_set(TnuaUserControlsSystemSet));
+
+    for i in 0..count {
+        let ground = app.world_mut().spawn_empty().id();
+        app.world_mut().spawn((
+            TnuaController::default(),
+            TnuaRigidBodyTracker {
+                translation: Vector3::new(i as Float, 1.5, 0.0),
+                gravity: -9.81 * Vector3::Y,
+                ..Default::default()
+            },
+            TnuaProximitySensor {
+                output: Some(TnuaProximitySensorOutput {
+                    entity: ground,
+                    proximity: 1.5,
+                    normal: Dir3::Y,
+                    entity_linvel: Default::default(),
+                    entity_angvel: Default::default(),
+                }),
+                ..Default::default()
+            },
+        ));
+    }
+
+    app.finish();
+    app.cleanup();
+    // The first update has a zero time delta, which makes the controller skip its logic.
+    app.update();
+    app
+}
+
+fn feed_controllers(mut query: Query<&mut TnuaController>, mut frame: Local<usize>) {
+    *frame += 1;
+    for mut controller in query.iter_mut() {
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: Vector3::X,
+            desired_forward: Some(Dir3::X),
+            float_height: 1.5,
+            ..Default::default()
+        });
+        // Jump every half a second, so that the actions machinery will also be exercised.
+        if *frame % 30 < 15 {
+            controller.action(TnuaBuiltinJump {
+                height: 4.0,
+                ..Default::default()
+            });
+        }
+    }
+}
+
+fn controller_logic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("controller_logic");
+    for count in [1, 100, 800] {
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            let mut app = create_app(count);
+            b.iter(|| app.update());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, controller_logic);
+criterion_main!(benches);
@@ -134,6 +134,8 @@ fn main() {
         apply_platformer_controls.in_set(TnuaUserControlsSystemSet),
     );
     app.add_plugins(LevelMechanicsPlugin);
+    #[cfg(feature = "touch-controls")]
+    app.add_plugins(tnua_demos_crate::touch_controls::TnuaTouchControlsPlugin);
     #[cfg(feature = "rapier2d")]
     {
         app.add_systems(Startup, |mut cfg: Single<&mut RapierConfiguration>| {
@@ -144,6 +144,8 @@ fn main() {
     app.add_systems(Update, animation_patcher_system);
     app.add_systems(Update, animate_platformer_character);
     app.add_plugins(LevelMechanicsPlugin);
+    #[cfg(feature = "touch-controls")]
+    app.add_plugins(tnua_demos_crate::touch_controls::TnuaTouchControlsPlugin);
     app.run();
 }
 
@@ -64,9 +64,11 @@ pub fn animate_platformer_character(
                         TnuaBuiltinJumpState::SlowDownTooFastSlopeJump { .. } => {
                             AnimationState::Jumping
                         }
-                        TnuaBuiltinJumpState::MaintainingJump => AnimationState::Jumping,
-                        TnuaBuiltinJumpState::StoppedMaintainingJump => AnimationState::Jumping,
-                        TnuaBuiltinJumpState::FallSection => AnimationState::Falling,
+                        TnuaBuiltinJumpState::MaintainingJump { .. } => AnimationState::Jumping,
+                        TnuaBuiltinJumpState::StoppedMaintainingJump { .. } => {
+                            AnimationState::Jumping
+                        }
+                        TnuaBuiltinJumpState::FallSection { .. } => AnimationState::Falling,
                     }
                 }
                 Some(TnuaBuiltinCrouch::NAME) => {
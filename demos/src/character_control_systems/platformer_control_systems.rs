@@ -11,6 +11,8 @@ use bevy_tnua::math::{AdjustPrecision, AsF32, Float, Vector3};
 use bevy_tnua::prelude::*;
 use bevy_tnua::{TnuaGhostSensor, TnuaProximitySensor};
 
+#[cfg(feature = "touch-controls")]
+use crate::touch_controls::TouchControlsInput;
 use crate::ui::tuning::UiTunable;
 
 use super::Dimensionality;
@@ -20,6 +22,7 @@ use super::Dimensionality;
 pub fn apply_platformer_controls(
     #[cfg(feature = "egui")] mut egui_context: EguiContexts,
     keyboard: Res<ButtonInput<KeyCode>>,
+    #[cfg(feature = "touch-controls")] touch_controls: Res<TouchControlsInput>,
     mut query: Query<(
         &CharacterMotionConfigForPlatformerDemo,
         // This is the main component used for interacting with Tnua. It is used for both issuing
@@ -93,6 +96,14 @@ pub fn apply_platformer_controls(
             direction += Vector3::X;
         }
 
+        #[cfg(feature = "touch-controls")]
+        {
+            direction.x += touch_controls.joystick.x.adjust_precision();
+            if config.dimensionality == Dimensionality::Dim3 {
+                direction.z -= touch_controls.joystick.y.adjust_precision();
+            }
+        }
+
         direction = direction.clamp_length_max(1.0);
 
         if let Some(forward_from_camera) = forward_from_camera {
@@ -109,6 +120,8 @@ pub fn apply_platformer_controls(
             Dimensionality::Dim3 => keyboard.any_pressed([KeyCode::Space]),
         };
         let dash = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        #[cfg(feature = "touch-controls")]
+        let (jump, dash) = (jump || touch_controls.jump, dash || touch_controls.dash);
 
         let turn_in_place = forward_from_camera.is_none()
             && keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
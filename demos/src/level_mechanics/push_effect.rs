@@ -32,11 +32,15 @@ fn apply_push_effect(
     for (entity, push_effect, mut controller, config) in query.iter_mut() {
         match push_effect {
             PushEffect::Impulse(impulse) => {
-                controller.action(TnuaBuiltinKnockback {
-                    shove: *impulse,
-                    force_forward: Dir3::new(-impulse.reject_from(Vector3::Y).f32()).ok(),
-                    ..config.knockback
-                });
+                if let Some((_, state)) = controller.concrete_action_mut::<TnuaBuiltinKnockback>() {
+                    state.refresh(*impulse);
+                } else {
+                    controller.action(TnuaBuiltinKnockback {
+                        shove: *impulse,
+                        force_forward: Dir3::new(-impulse.reject_from(Vector3::Y).f32()).ok(),
+                        ..config.knockback
+                    });
+                }
                 commands.entity(entity).remove::<PushEffect>();
             }
         }
@@ -3,6 +3,8 @@ pub mod character_animating_systems;
 pub mod character_control_systems;
 pub mod level_mechanics;
 pub mod levels_setup;
+#[cfg(feature = "touch-controls")]
+pub mod touch_controls;
 pub mod ui;
 pub mod util;
 
@@ -0,0 +1,163 @@
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// Renders a virtual joystick and jump/dash buttons, and reads them from touches (or from the
+/// mouse, for trying them on desktop) into the [`TouchControlsInput`] resource.
+///
+/// The plugin does not feed the controller by itself - the control system of the game should
+/// read [`TouchControlsInput`] and feed it just like it does with the keyboard.
+pub struct TnuaTouchControlsPlugin;
+
+impl Plugin for TnuaTouchControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchControlsInput>();
+        app.add_systems(Startup, spawn_touch_controls);
+        app.add_systems(PreUpdate, update_touch_controls.after(InputSystem));
+    }
+}
+
+/// The state of the touch controls.
+#[derive(Resource, Default, Debug)]
+pub struct TouchControlsInput {
+    /// The direction of the joystick, with length between 0 and 1. Up is positive Y.
+    pub joystick: Vec2,
+    pub jump: bool,
+    pub dash: bool,
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum TouchControl {
+    Joystick,
+    Jump,
+    Dash,
+}
+
+#[derive(Component)]
+struct JoystickKnob;
+
+const JOYSTICK_SIZE: f32 = 160.0;
+const KNOB_SIZE: f32 = 64.0;
+const BUTTON_SIZE: f32 = 96.0;
+const IDLE_ALPHA: f32 = 0.25;
+const PRESSED_ALPHA: f32 = 0.5;
+
+fn spawn_touch_controls(mut commands: Commands) {
+    commands
+        .spawn((
+            TouchControl::Joystick,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(32.0),
+                bottom: Val::Px(32.0),
+                width: Val::Px(JOYSTICK_SIZE),
+                height: Val::Px(JOYSTICK_SIZE),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            BorderRadius::MAX,
+            BackgroundColor(Color::WHITE.with_alpha(IDLE_ALPHA)),
+        ))
+        .with_child((
+            JoystickKnob,
+            Node {
+                width: Val::Px(KNOB_SIZE),
+                height: Val::Px(KNOB_SIZE),
+                ..Default::default()
+            },
+            BorderRadius::MAX,
+            BackgroundColor(Color::WHITE.with_alpha(PRESSED_ALPHA)),
+        ));
+
+    for (control, label, right, bottom) in [
+        (TouchControl::Jump, "Jump", 32.0, 32.0),
+        (TouchControl::Dash, "Dash", 32.0 + BUTTON_SIZE + 16.0, 32.0),
+    ] {
+        commands
+            .spawn((
+                control,
+                Node {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(right),
+                    bottom: Val::Px(bottom),
+                    width: Val::Px(BUTTON_SIZE),
+                    height: Val::Px(BUTTON_SIZE),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                BorderRadius::MAX,
+                BackgroundColor(Color::WHITE.with_alpha(IDLE_ALPHA)),
+            ))
+            .with_child((Text::new(label), TextColor(Color::BLACK)));
+    }
+}
+
+fn update_touch_controls(
+    touches: Res<Touches>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut controls_query: Query<(
+        &TouchControl,
+        &ComputedNode,
+        &GlobalTransform,
+        &mut BackgroundColor,
+    )>,
+    mut knob_query: Query<&mut Node, With<JoystickKnob>>,
+    mut input: ResMut<TouchControlsInput>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    // UI nodes are laid out in physical pixels, while touches and the cursor are in logical
+    // pixels.
+    let mut pointers = touches
+        .iter()
+        .map(|touch| touch.position())
+        .collect::<Vec<_>>();
+    if mouse.pressed(MouseButton::Left) {
+        pointers.extend(window.cursor_position());
+    }
+    for pointer in pointers.iter_mut() {
+        *pointer *= window.scale_factor();
+    }
+
+    *input = Default::default();
+    for (control, computed_node, global_transform, mut background_color) in
+        controls_query.iter_mut()
+    {
+        let center = global_transform.translation().truncate();
+        let radius = 0.5 * computed_node.size().x;
+        let touching = pointers
+            .iter()
+            .filter(|pointer| pointer.distance(center) <= radius)
+            .min_by(|a, b| a.distance(center).total_cmp(&b.distance(center)));
+        background_color.0 = Color::WHITE.with_alpha(if touching.is_some() {
+            PRESSED_ALPHA
+        } else {
+            IDLE_ALPHA
+        });
+        match control {
+            TouchControl::Joystick => {
+                let offset = touching.map_or(Vec2::ZERO, |pointer| {
+                    ((*pointer - center) / radius).clamp_length_max(1.0)
+                });
+                // Screen coordinates grow downward, but the joystick's up should be positive.
+                input.joystick = Vec2::new(offset.x, -offset.y);
+                for mut knob in knob_query.iter_mut() {
+                    let knob_offset = 0.5 * (JOYSTICK_SIZE - KNOB_SIZE) * offset;
+                    knob.left = Val::Px(knob_offset.x);
+                    knob.top = Val::Px(knob_offset.y);
+                }
+            }
+            TouchControl::Jump => {
+                input.jump = touching.is_some();
+            }
+            TouchControl::Dash => {
+                input.dash = touching.is_some();
+            }
+        }
+    }
+}
@@ -105,6 +105,18 @@ impl UiTunable for TnuaBuiltinWalk {
         ui.add(egui::Slider::new(&mut self.cling_distance, 0.0..=10.0).text("Cling Distance"));
         ui.add(egui::Slider::new(&mut self.spring_strengh, 0.0..=4000.0).text("Spring Strengh"));
         ui.add(egui::Slider::new(&mut self.spring_dampening, 0.0..=1.9).text("Spring Dampening"));
+        ui.add(
+            egui::Slider::new(&mut self.forward_speed_multiplier, 0.0..=2.0)
+                .text("Forward Speed Multiplier"),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.backward_speed_multiplier, 0.0..=2.0)
+                .text("Backward Speed Multiplier"),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.strafe_speed_multiplier, 0.0..=2.0)
+                .text("Strafe Speed Multiplier"),
+        );
         slider_or_infinity(ui, "Acceleration", &mut self.acceleration, 0.0..=200.0);
         slider_or_infinity(
             ui,
@@ -112,13 +124,45 @@ impl UiTunable for TnuaBuiltinWalk {
             &mut self.air_acceleration,
             0.0..=200.0,
         );
+        slider_or_none(ui, "Deceleration", &mut self.deceleration, 0.0..=200.0);
+        slider_or_none(
+            ui,
+            "Air Deceleration",
+            &mut self.air_deceleration,
+            0.0..=200.0,
+        );
+        slider_or_none(
+            ui,
+            "Over Speed Decay",
+            &mut self.over_speed_decay,
+            0.0..=200.0,
+        );
+        slider_or_none(ui, "Friction", &mut self.friction, 0.0..=20.0);
+        ui.add(egui::Slider::new(&mut self.surface_grip, 0.0..=1.0).text("Surface Grip"));
+        slider_or_none(
+            ui,
+            "Platform Anchoring",
+            &mut self.platform_anchoring,
+            0.0..=50.0,
+        );
+        ui.checkbox(
+            &mut self.rotating_platform_compensation,
+            "Rotating Platform Compensation",
+        );
 
         ui.add(egui::Slider::new(&mut self.coyote_time, 0.0..=1.0).text("Coyote Time"));
+        ui.add(
+            egui::Slider::new(&mut self.ground_persistence, 0.0..=0.2).text("Ground Persistence"),
+        );
 
         ui.add(
             egui::Slider::new(&mut self.free_fall_extra_gravity, 0.0..=100.0)
                 .text("Free Fall Extra Gravity"),
         );
+        ui.add(
+            egui::Slider::new(&mut self.fall_gravity_multiplier, 0.0..=5.0)
+                .text("Fall Gravity Multiplier"),
+        );
 
         slider_or_infinity(
             ui,
@@ -139,11 +183,38 @@ impl UiTunable for TnuaBuiltinWalk {
             &mut self.turning_angvel,
             0.0..=70.0,
         );
+        slider_or_none(
+            ui,
+            "Air Turning Angular Velocity",
+            &mut self.air_turning_angvel,
+            0.0..=70.0,
+        );
+        ui.add(
+            egui::Slider::new(&mut self.turning_snap_angle, 0.0..=float_consts::PI)
+                .text("Turning Snap Angle (in radians)"),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.turning_reverse_multiplier, 0.0..=5.0)
+                .text("Turning Reverse Multiplier"),
+        );
 
         ui.add(
             egui::Slider::new(&mut self.max_slope, 0.0..=float_consts::FRAC_PI_2)
                 .text("Max Slope (in radians)"),
         );
+        slider_or_none(
+            ui,
+            "Soft Max Slope (in radians)",
+            &mut self.soft_max_slope,
+            0.0..=float_consts::FRAC_PI_2,
+        );
+        ui.add(
+            egui::Slider::new(&mut self.soft_slope_exponent, 0.1..=5.0).text("Soft Slope Exponent"),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.slip_acceleration, 0.0..=100.0).text("Slip Acceleration"),
+        );
+        slider_or_infinity(ui, "Max Slip Speed", &mut self.max_slip_speed, 0.0..=50.0);
     }
 }
 
@@ -151,6 +222,30 @@ impl UiTunable for TnuaBuiltinJump {
     #[cfg(feature = "egui")]
     fn tune(&mut self, ui: &mut egui::Ui) {
         ui.add(egui::Slider::new(&mut self.height, 0.0..=10.0).text("Jump Height"));
+        slider_or_none(
+            ui,
+            "Air Jump Height",
+            &mut self.air_overrides.height,
+            0.0..=10.0,
+        );
+        slider_or_none(
+            ui,
+            "Air Jump Takeoff Extra Gravity",
+            &mut self.air_overrides.takeoff_extra_gravity,
+            0.0..=100.0,
+        );
+        slider_or_none(
+            ui,
+            "Air Jump Shorten Extra Gravity",
+            &mut self.air_overrides.shorten_extra_gravity,
+            0.0..=100.0,
+        );
+        slider_or_none(
+            ui,
+            "Air Jump Fall Extra Gravity",
+            &mut self.air_overrides.fall_extra_gravity,
+            0.0..=50.0,
+        );
         ui.add(
             egui::Slider::new(&mut self.input_buffer_time, 0.0..=1.0)
                 .text("Jump Input Buffer Time"),
@@ -216,6 +311,7 @@ impl UiTunable for TnuaBuiltinDash {
     #[cfg(feature = "egui")]
     fn tune(&mut self, ui: &mut egui::Ui) {
         ui.add(egui::Slider::new(&mut self.speed, 0.0..=200.0).text("Dash Speed"));
+        ui.checkbox(&mut self.conform_to_ground, "Conform to Ground");
         slider_or_infinity(
             ui,
             "Brake to Speed After Dash",
@@ -257,5 +353,22 @@ impl UiTunable for TnuaBuiltinKnockback {
             &mut self.air_acceleration_limit,
             0.0..=20.0,
         );
+        ui.add(
+            egui::Slider::new(&mut self.launch_gravity_multiplier, 0.0..=4.0)
+                .text("Launch Gravity Multiplier"),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.float_gravity_multiplier, 0.0..=4.0)
+                .text("Float Gravity Multiplier"),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.fall_gravity_multiplier, 0.0..=4.0)
+                .text("Fall Gravity Multiplier"),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.float_velocity_threshold, 0.0..=10.0)
+                .text("Float Velocity Threshold"),
+        );
+        ui.checkbox(&mut self.persist_while_airborne, "Persist While Airborne");
     }
 }
@@ -240,9 +240,9 @@ fn handle_animating(
                 TnuaBuiltinJumpState::NoJump => return,
                 TnuaBuiltinJumpState::StartingJump { .. } => AnimationState::Jumping,
                 TnuaBuiltinJumpState::SlowDownTooFastSlopeJump { .. } => AnimationState::Jumping,
-                TnuaBuiltinJumpState::MaintainingJump => AnimationState::Jumping,
-                TnuaBuiltinJumpState::StoppedMaintainingJump => AnimationState::Jumping,
-                TnuaBuiltinJumpState::FallSection => AnimationState::Falling,
+                TnuaBuiltinJumpState::MaintainingJump { .. } => AnimationState::Jumping,
+                TnuaBuiltinJumpState::StoppedMaintainingJump { .. } => AnimationState::Jumping,
+                TnuaBuiltinJumpState::FallSection { .. } => AnimationState::Falling,
             }
         }
         // Tnua should only have the `action_name` of the actions you feed to it. If it has
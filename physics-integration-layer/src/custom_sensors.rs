@@ -0,0 +1,167 @@
+use std::marker::PhantomData;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+
+use crate::data_for_backends::{
+    TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker, TnuaToggle,
+};
+use crate::math::{Float, Vector3};
+use crate::{TnuaPipelineStages, TnuaSystemSet};
+
+/// A description of a cast a [`TnuaCustomSensor`] wants to perform.
+#[derive(Debug, Clone)]
+pub struct TnuaCustomSensorCast {
+    /// The cast origin in the entity's coord system.
+    pub origin: Vector3,
+    /// The direction in world coord system (unmodified by the entity's transform)
+    pub direction: Dir3,
+    /// The maximum distance of the cast.
+    pub range: Float,
+    /// The maximum number of hits to consider. Since [`TnuaCustomSensor::filter`] is applied
+    /// after the cast, this should be more than 1 when the filter is expected to reject hits that
+    /// are closer than the desired one.
+    pub max_hits: usize,
+}
+
+/// A component that can use the physics backend to cast rays or shapes, without any
+/// backend-specific code.
+///
+/// Implement this for a component, register it with [`TnuaCustomSensorPlugin`], and on every frame
+/// the physics backend will perform the cast described by [`cast`](Self::cast) and pass its result
+/// to [`set_output`](Self::set_output) during [`TnuaPipelineStages::Sensors`] - so the result will
+/// be available to systems in [`TnuaPipelineStages::Logic`].
+///
+/// The cast is done by the same backend code that handles Tnua's own sensors, using a
+/// [`TnuaProximitySensor`] that the plugin places on the same entity. This means:
+///
+/// * The custom sensor must not be placed on the character entity, because that entity already has
+///   the [`TnuaProximitySensor`] of the character's own ground sensor, and the plugin would take it
+///   over. Place it instead on a child entity of the character, with a
+///   [`TnuaSubservientSensor`](crate::subservient_sensors::TnuaSubservientSensor) that points to
+///   the character so that the character will be excluded from the cast (the way
+///   `TnuaCeilingSensor` does it). Custom sensors on an entity that has a
+///   [`TnuaRigidBodyTracker`] are ignored, and an error is logged.
+/// * The sensor entity itself is excluded from the cast.
+/// * To cast a shape instead of a ray, add the physics backend's sensor shape component (e.g.
+///   `TnuaAvian3dSensorShape`) to the sensor entity.
+/// * The collision layers/groups of the owner entity, ghost platforms and [`TnuaToggle`] are
+///   handled the same way they are for Tnua's own sensors.
+pub trait TnuaCustomSensor: Component {
+    /// The cast to perform in the current frame, or `None` to skip the cast.
+    fn cast(&self) -> Option<TnuaCustomSensorCast>;
+
+    /// Decide if a hit should be considered. Hits are checked by proximity, and the first hit this
+    /// method accepts is passed to [`set_output`](Self::set_output).
+    fn filter(&self, _output: &TnuaProximitySensorOutput) -> bool {
+        true
+    }
+
+    /// Receive the result of the cast.
+    fn set_output(&mut self, output: Option<TnuaProximitySensorOutput>);
+}
+
+/// Add this plugin to make a [`TnuaCustomSensor`] work.
+///
+/// Must be added once for every type of custom sensor, with the same schedule as the physics
+/// backend's plugin.
+pub struct TnuaCustomSensorPlugin<S: TnuaCustomSensor> {
+    schedule: InternedScheduleLabel,
+    _phantom: PhantomData<fn() -> S>,
+}
+
+impl<S: TnuaCustomSensor> TnuaCustomSensorPlugin<S> {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: TnuaCustomSensor> Default for TnuaCustomSensorPlugin<S> {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl<S: TnuaCustomSensor> Plugin for TnuaCustomSensorPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.register_required_components::<S, TnuaProximitySensor>();
+        app.add_systems(
+            self.schedule,
+            (
+                check_custom_sensors_placement_system::<S>.in_set(TnuaSystemSet),
+                prepare_custom_sensors_system::<S>
+                    .in_set(TnuaSystemSet)
+                    .before(TnuaPipelineStages::Sensors),
+                read_custom_sensors_system::<S>.in_set(TnuaPipelineStages::SubservientSensors),
+            ),
+        );
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn check_custom_sensors_placement_system<S: TnuaCustomSensor>(
+    query: Query<
+        Entity,
+        (
+            With<S>,
+            With<TnuaRigidBodyTracker>,
+            Or<(Added<S>, Added<TnuaRigidBodyTracker>)>,
+        ),
+    >,
+) {
+    for entity in query.iter() {
+        error!(
+            "Custom sensor {} on {entity} is ignored because it is placed on a character entity - \
+             it should be placed on a separate entity with a `TnuaSubservientSensor`",
+            std::any::type_name::<S>(),
+        );
+    }
+}
+
+fn prepare_custom_sensors_system<S: TnuaCustomSensor>(
+    mut query: Query<
+        (&S, &mut TnuaProximitySensor, Option<&TnuaToggle>),
+        Without<TnuaRigidBodyTracker>,
+    >,
+) {
+    for (custom_sensor, mut sensor, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if let Some(cast) = custom_sensor.cast() {
+            sensor.cast_origin = cast.origin;
+            sensor.cast_direction = cast.direction;
+            sensor.cast_range = cast.range;
+            sensor.max_hits = cast.max_hits;
+        } else {
+            sensor.skip_next_cast = true;
+            sensor.output = None;
+            sensor.additional_outputs.clear();
+        }
+    }
+}
+
+fn read_custom_sensors_system<S: TnuaCustomSensor>(
+    mut query: Query<
+        (&mut S, &TnuaProximitySensor, Option<&TnuaToggle>),
+        Without<TnuaRigidBodyTracker>,
+    >,
+) {
+    for (mut custom_sensor, sensor, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let output = sensor
+            .all_outputs()
+            .find(|output| custom_sensor.filter(output))
+            .cloned();
+        custom_sensor.set_output(output);
+    }
+}
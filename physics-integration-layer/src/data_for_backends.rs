@@ -1,6 +1,6 @@
 use std::ops::{Add, AddAssign};
 
-use crate::math::{Float, Quaternion, Vector3};
+use crate::math::{AdjustPrecision, Float, Quaternion, Vector3};
 use bevy::prelude::*;
 
 /// Allows disabling Tnua for a specific entity.
@@ -57,6 +57,51 @@ impl Default for TnuaRigidBodyTracker {
     }
 }
 
+/// The dimensions of the character's collider, as measured by the physics backend.
+///
+/// This component is not mandatory - add it to a character entity to make the physics backend
+/// fill it during [`TnuaPipelineStages::Sensors`](crate::TnuaPipelineStages::Sensors) whenever the
+/// collider changes. The dimensions are measured along the entity's local Y axis (which is the
+/// default up direction) from the bounding box of all the character's colliders - the one on the
+/// character entity and the ones on its descendants.
+///
+/// Its main use is to set the float height relative to the collider instead of as an absolute
+/// number (see [`float_height_above_bottom`](Self::float_height_above_bottom)).
+#[derive(Component, Debug, Default, Clone)]
+pub struct TnuaColliderDimensions {
+    /// The distance from the entity's origin to the bottom of the collider.
+    pub bottom_offset: Float,
+
+    /// Half the height of the collider.
+    pub half_height: Float,
+}
+
+impl TnuaColliderDimensions {
+    /// The float height that will keep the bottom of the collider `distance` above the ground.
+    ///
+    /// Until the physics backend measures the collider, this is just `distance` - so the character
+    /// may sink into the ground for a frame.
+    pub fn float_height_above_bottom(&self, distance: Float) -> Float {
+        self.bottom_offset + distance
+    }
+}
+
+/// Marks which collider of a character assembled from multiple colliders represents its feet.
+///
+/// Add this component to the character entity (the one with the [`TnuaProximitySensor`]). By
+/// default, the physics backend measures [`TnuaColliderDimensions`] from all the colliders on the
+/// character entity and its descendants, and takes the collision layers/groups the proximity sensor uses from the
+/// first collider it finds on the character entity or its descendants. When a character has
+/// additional colliders - hitboxes, for example - this can pick the wrong one. With this
+/// component, the backend uses the collider on the given entity for these instead. The feet
+/// collider is always excluded from the proximity sensor's casts - even if it is not a descendant
+/// of the character entity.
+///
+/// The dimensions of the feet collider are measured relative to the origin of the character
+/// entity, so the feet collider may be offset from it.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TnuaFeetCollider(pub Entity);
+
 /// Distance from another collider in a certain direction, and information on that collider.
 ///
 /// The physics backend is responsible for updating this component from the physics engine during
@@ -91,6 +136,35 @@ pub struct TnuaProximitySensor {
     /// Positive dot products should not happen (hitting the ceiling?), but it's trivial to
     /// consider them as invalid.
     pub intersection_match_prevention_cutoff: Float,
+
+    /// The maximum number of tangible hits the backend should report.
+    ///
+    /// The nearest hit is always reported in [`output`](Self::output). When this is more than 1,
+    /// the backend will also report up to `max_hits - 1` additional hits (on different entities)
+    /// in [`additional_outputs`](Self::additional_outputs). Ghost platforms (see
+    /// [`TnuaGhostSensor`]) do not count as tangible hits.
+    pub max_hits: usize,
+
+    /// Tangible hits beyond [`output`](Self::output), sorted by proximity.
+    ///
+    /// Only filled when [`max_hits`](Self::max_hits) is more than 1.
+    pub additional_outputs: Vec<TnuaProximitySensorOutput>,
+
+    /// When set, the backend will not cast the sensor on the next frame - leaving the previous
+    /// [`output`](Self::output) and [`additional_outputs`](Self::additional_outputs) as they are -
+    /// and will reset this flag back to `false`.
+    ///
+    /// Used for skipping the (relatively expensive) casts when their results are not expected to
+    /// change.
+    pub skip_next_cast: bool,
+}
+
+impl TnuaProximitySensor {
+    /// Iterate over all the tangible hits, sorted by proximity - starting with
+    /// [`output`](Self::output) followed by the [`additional_outputs`](Self::additional_outputs).
+    pub fn all_outputs(&self) -> impl Iterator<Item = &TnuaProximitySensorOutput> {
+        self.output.iter().chain(self.additional_outputs.iter())
+    }
 }
 
 impl Default for TnuaProximitySensor {
@@ -101,6 +175,9 @@ impl Default for TnuaProximitySensor {
             cast_range: 0.0,
             output: None,
             intersection_match_prevention_cutoff: -0.5,
+            max_hits: 1,
+            additional_outputs: Vec::new(),
+            skip_next_cast: false,
         }
     }
 }
@@ -165,6 +242,29 @@ impl TnuaVelChange {
     pub fn calc_boost(&self, frame_duration: Float) -> Vector3 {
         self.acceleration * frame_duration + self.boost
     }
+
+    /// Zero the acceleration and/or the boost if they are not finite, logging an error.
+    ///
+    /// Physics backends should call this right before applying the velocity change, so that a NaN
+    /// or an infinity (e.g. from bad user input) will not get fed to the physics engine and
+    /// teleport the character to infinity. The `entity` and the `description` (e.g. `"linear"`)
+    /// are only used for the error message.
+    pub fn quarantine_non_finite(&mut self, entity: Entity, description: &str) {
+        if !self.acceleration.is_finite() {
+            error!(
+                "Non-finite {description} acceleration {} in the motor of {entity}",
+                self.acceleration
+            );
+            self.acceleration = Vector3::ZERO;
+        }
+        if !self.boost.is_finite() {
+            error!(
+                "Non-finite {description} boost {} in the motor of {entity}",
+                self.boost
+            );
+            self.boost = Vector3::ZERO;
+        }
+    }
 }
 
 impl Default for TnuaVelChange {
@@ -208,6 +308,129 @@ pub struct TnuaMotor {
     /// rotation axis multiplied by the rotation speed in radians per second. Can be extracted from
     /// a quaternion using [`Quaternion::xyz`].
     pub ang: TnuaVelChange,
+
+    /// Move the rigid body to this position in the current frame.
+    ///
+    /// The velocity change is still applied on top of the teleportation.
+    pub teleport: Option<Vector3>,
+}
+
+/// Limits the force the [`TnuaMotor`] may apply to dynamic bodies the character is pushing.
+///
+/// Without this component, the motor applies whatever velocity change the controller requests -
+/// which means that when the character walks into a light dynamic body, it pushes it as hard as it
+/// needs to keep its own speed. When this component is present, the physics backend uses
+/// [`limit_vel_change`](Self::limit_vel_change) to limit the part of the linear velocity change
+/// that goes into each dynamic body the character is in contact with.
+#[derive(Component, Debug)]
+pub struct TnuaMotorPushLimit {
+    /// The maximum force the character may apply to a body it pushes.
+    pub max_push_force: Float,
+}
+
+impl TnuaMotorPushLimit {
+    /// Limit a velocity change in the direction of a pushed body.
+    ///
+    /// * `push_direction` - the normal of the contact with the pushed body, pointing from the
+    ///   character into that body.
+    /// * `mass` - the mass of the character.
+    pub fn limit_vel_change(
+        &self,
+        vel_change: &mut TnuaVelChange,
+        push_direction: Vector3,
+        mass: Float,
+        frame_duration: Float,
+    ) {
+        let max_boost = self.max_push_force * frame_duration / mass;
+
+        let boost_into = vel_change.boost.dot(push_direction);
+        if max_boost < boost_into {
+            vel_change.boost -= (boost_into - max_boost) * push_direction;
+        }
+
+        if 0.0 < frame_duration {
+            let max_acceleration =
+                (max_boost - vel_change.boost.dot(push_direction).max(0.0)) / frame_duration;
+            let acceleration_into = vel_change.acceleration.dot(push_direction);
+            if max_acceleration < acceleration_into {
+                vel_change.acceleration -= (acceleration_into - max_acceleration) * push_direction;
+            }
+        }
+    }
+}
+
+/// Post-processes the velocity changes of the [`TnuaMotor`] before they are applied.
+///
+/// When this component is present, the physics backend calls [`apply`](Self::apply) on the linear
+/// and angular velocity changes right before writing them to the physics engine - after the
+/// [`TnuaMotorPushLimit`] was applied. The [`TnuaMotor`] component itself is not modified, so it
+/// still shows what the controller wanted to do.
+///
+/// For example, to stop a character from moving horizontally during a cutscene while still
+/// letting it stand on the ground:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua_physics_integration_layer::data_for_backends::TnuaMotorFilter;
+/// # let mut commands: Commands = panic!();
+/// # let character_entity: Entity = panic!();
+/// commands
+///     .entity(character_entity)
+///     .insert(TnuaMotorFilter::KeepLinearAlong(Dir3::Y));
+/// ```
+#[derive(Component)]
+pub enum TnuaMotorFilter {
+    /// Do not apply any velocity change (linear or angular).
+    Freeze,
+    /// Only apply the part of the linear velocity change that is along the given axis.
+    KeepLinearAlong(Dir3),
+    /// Do not apply the part of the linear velocity change that is along the given axis.
+    CancelLinearAlong(Dir3),
+    /// Limit the magnitudes of the linear boost and acceleration.
+    ClampLinear {
+        max_boost: Float,
+        max_acceleration: Float,
+    },
+    /// Modify the linear and angular velocity changes with a custom function.
+    #[allow(clippy::type_complexity)]
+    Custom(Box<dyn Send + Sync + Fn(&mut TnuaVelChange, &mut TnuaVelChange)>),
+}
+
+impl TnuaMotorFilter {
+    /// Create a [`Custom`](Self::Custom) filter.
+    pub fn custom(
+        filter: impl 'static + Send + Sync + Fn(&mut TnuaVelChange, &mut TnuaVelChange),
+    ) -> Self {
+        Self::Custom(Box::new(filter))
+    }
+
+    /// Apply the filter to the linear and angular velocity changes of the motor.
+    pub fn apply(&self, lin: &mut TnuaVelChange, ang: &mut TnuaVelChange) {
+        match self {
+            TnuaMotorFilter::Freeze => {
+                *lin = TnuaVelChange::ZERO;
+                *ang = TnuaVelChange::ZERO;
+            }
+            TnuaMotorFilter::KeepLinearAlong(axis) => {
+                let axis = axis.adjust_precision();
+                lin.acceleration = lin.acceleration.project_onto_normalized(axis);
+                lin.boost = lin.boost.project_onto_normalized(axis);
+            }
+            TnuaMotorFilter::CancelLinearAlong(axis) => {
+                lin.cancel_on_axis(axis.adjust_precision());
+            }
+            TnuaMotorFilter::ClampLinear {
+                max_boost,
+                max_acceleration,
+            } => {
+                lin.acceleration = lin.acceleration.clamp_length_max(*max_acceleration);
+                lin.boost = lin.boost.clamp_length_max(*max_boost);
+            }
+            TnuaMotorFilter::Custom(filter) => {
+                filter(lin, ang);
+            }
+        }
+    }
 }
 
 /// An addon for [`TnuaProximitySensor`] that allows it to detect [`TnuaGhostPlatform`] colliders.
@@ -239,3 +462,28 @@ impl TnuaGhostSensor {
 /// See `TnuaSimpleFallThroughPlatformsHelper`.
 #[derive(Component, Default, Debug)]
 pub struct TnuaGhostPlatform;
+
+/// A directional version of [`TnuaGhostPlatform`], for one-way surfaces of any orientation (e.g.
+/// one-way walls or side gates).
+///
+/// When a sensor is cast through the surface from its [`passable_from`](Self::passable_from)
+/// side, the surface is handled like a [`TnuaGhostPlatform`]. When a sensor is cast from the other
+/// side, the surface is sensed like a regular solid collider - even if its solver groups exclude
+/// the character's collider.
+///
+/// Like with [`TnuaGhostPlatform`], making the character physically pass through the surface is
+/// up to the game (e.g. by changing the solver groups according to the side the character
+/// approaches from).
+#[derive(Component, Debug)]
+pub struct TnuaGhostSurface {
+    /// The direction, in world space, from which the surface can be passed through. For example,
+    /// `Dir3::NEG_Y` is a one-way floor that can be passed through from below.
+    pub passable_from: Dir3,
+}
+
+impl TnuaGhostSurface {
+    /// Check if a cast in the given direction passes through the surface.
+    pub fn is_passable_in(&self, cast_direction: Dir3) -> bool {
+        self.passable_from.dot(*cast_direction) < 0.0
+    }
+}
@@ -27,11 +27,23 @@
 //!       [`TnuaSubservientSensor`](subservient_sensors::TnuaSubservientSensor) component, the
 //!       "owner entity" is defined as the `owner_entity` field from that component and not the
 //!       entity the sensor component is attached to.
+//!     * If the owner entity has the
+//!       [`TnuaFeetCollider`](data_for_backends::TnuaFeetCollider) component, the sensor should
+//!       use the collision layers/groups of the collider it points to, and ignore that collider
+//!       as well.
 //!     * The detection should be done with a ray cast, unless the sensor is configured to cast a
 //!       shape instead. Such configuration is done with component, defined by the integration
 //!       crate, that specifies the shape to cast in a way the integration crate can pass on to the
 //!       physics engine. The name of that component should be
 //!       `Tnua<physics-engine-name>SensorShape`.
+//!     * Colliders the cast starts inside of should not be reported as hits at the cast origin.
+//!       Shape casts should ignore penetrations at the origin (Avian's
+//!       `ignore_origin_penetration`, Rapier's `stop_at_penetration: false`) and continue to find
+//!       the next surface.
+//!     * Hits on colliders the owner entity is in contact with should be discarded if the normal
+//!       of that contact is not opposite enough to the cast direction, as determined by
+//!       [`intersection_match_prevention_cutoff`](data_for_backends::TnuaProximitySensor::intersection_match_prevention_cutoff).
+//!       This prevents the sensor from treating walls the character squeezed into as ground.
 //!
 //!   The integration crate may update all these components in one system or multiple systems as it
 //!   sees fit.
@@ -62,6 +74,7 @@
 //! should go in that bundle.
 use bevy::prelude::*;
 
+pub mod custom_sensors;
 pub mod data_for_backends;
 pub mod math;
 pub mod subservient_sensors;
@@ -70,6 +83,10 @@ pub mod subservient_sensors;
 ///
 /// The physics backends' plugins are responsible for preventing this entire system set from
 /// running when the physics backend itself is paused.
+///
+/// Systems that keep time-dependent state (timers, smoothing, etc.) should be placed in this
+/// system set and advance that state by the schedule's [`Time`] - never by real time - so that the
+/// state freezes while the physics is paused.
 #[derive(SystemSet, Clone, PartialEq, Eq, Debug, Hash)]
 pub struct TnuaSystemSet;
 
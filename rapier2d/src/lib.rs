@@ -7,18 +7,25 @@
 //! * Optionally: Add [`TnuaRapier2dSensorShape`] to the sensor entities. This means the entity of
 //!   the characters controlled by Tnua, but also other things like the entity generated by
 //!   `TnuaCrouchEnforcer`, that can be affected with a closure.
+//! * Alternatively: Add [`TnuaRapier2dAutoSensorShape`] to derive the sensor shape from the entity's
+//!   own collider.
+//! * Optionally: Add [`TnuaRapier2dColliderCastOrigin`] to characters whose collider is offset
+//!   from the rigid body's origin.
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
 use bevy::prelude::*;
 use bevy::utils::HashSet;
+use bevy::utils::Parallel;
 use bevy_rapier2d::prelude::*;
 use bevy_rapier2d::rapier;
 use bevy_rapier2d::rapier::prelude::InteractionGroups;
 
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostPlatform;
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostSensor;
+use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostSurface;
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaToggle;
 use bevy_tnua_physics_integration_layer::data_for_backends::{
-    TnuaMotor, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker,
+    TnuaColliderDimensions, TnuaFeetCollider, TnuaMotor, TnuaMotorFilter, TnuaMotorPushLimit,
+    TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker,
 };
 use bevy_tnua_physics_integration_layer::subservient_sensors::TnuaSubservientSensor;
 use bevy_tnua_physics_integration_layer::TnuaPipelineStages;
@@ -60,10 +67,18 @@ impl Plugin for TnuaRapier2dPlugin {
             self.schedule,
             (
                 update_rigid_body_trackers_system,
+                update_collider_dimensions_system,
+                update_feet_collider_dimensions_system,
                 update_proximity_sensors_system,
             )
                 .in_set(TnuaPipelineStages::Sensors),
         );
+        app.add_systems(
+            self.schedule,
+            update_auto_sensor_shapes_system
+                .in_set(TnuaPipelineStages::Sensors)
+                .before(update_proximity_sensors_system),
+        );
         app.add_systems(
             self.schedule,
             apply_motors_system.in_set(TnuaPipelineStages::Motors),
@@ -86,6 +101,129 @@ pub struct TnuaRapier2dIOBundle {
 #[derive(Component)]
 pub struct TnuaRapier2dSensorShape(pub Collider);
 
+/// Add this component to derive [`TnuaRapier2dSensorShape`] from the entity's own [`Collider`].
+///
+/// The sensor shape will be a flat rectangle, with the width of the collider (measured from its
+/// bounding box) multiplied by the given factor. A factor slightly smaller than `1.0` (e.g. `0.9`)
+/// prevents the sensor from hitting walls the character touches sideways, while still keeping the
+/// character from dropping off ledges too early.
+///
+/// The sensor shape is updated whenever the collider or this component change. This assumes the
+/// sensor is cast along the collider's Y axis.
+#[derive(Component)]
+pub struct TnuaRapier2dAutoSensorShape(pub f32);
+
+/// Add this component to make the [`TnuaProximitySensor`] cast from the center of the collider
+/// instead of from the entity's origin.
+///
+/// By default, [`cast_origin`](TnuaProximitySensor::cast_origin) is relative to the entity's
+/// [`GlobalTransform`]. When the character's collider is offset from the rigid body (e.g. it's
+/// placed on a child entity with a translation) this makes the float height measured from the wrong
+/// place. With this component, the cast origin is offset by the difference between the center of
+/// the collider's world bounding box and the entity's translation.
+///
+/// This component should be placed on the character entity itself, not on subservient sensors. If
+/// the character has multiple colliders, the first one found is used.
+#[derive(Component)]
+pub struct TnuaRapier2dColliderCastOrigin;
+
+fn update_auto_sensor_shapes_system(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &Collider, &TnuaRapier2dAutoSensorShape),
+        Or<(Changed<Collider>, Changed<TnuaRapier2dAutoSensorShape>)>,
+    >,
+) {
+    for (entity, collider, TnuaRapier2dAutoSensorShape(scale)) in query.iter() {
+        let half_extents = collider.raw.compute_local_aabb().half_extents();
+        commands
+            .entity(entity)
+            .insert(TnuaRapier2dSensorShape(Collider::cuboid(
+                scale * half_extents.x,
+                0.0,
+            )));
+    }
+}
+
+fn update_collider_dimensions_system(
+    mut query: Query<
+        (Entity, &GlobalTransform, &mut TnuaColliderDimensions),
+        Without<TnuaFeetCollider>,
+    >,
+    colliders_query: Query<(&Collider, &GlobalTransform)>,
+    children_query: Query<&Children>,
+    child_entities_query: Query<(Has<Collider>, Has<RigidBody>)>,
+    mut owner_collider_entities: Local<Vec<Entity>>,
+) {
+    for (owner_entity, owner_transform, mut dimensions) in query.iter_mut() {
+        // The colliders may be on child entities (e.g. with character models loaded from glTF).
+        owner_collider_entities.clear();
+        owner_collider_entities.push(owner_entity);
+        collect_child_colliders(
+            owner_entity,
+            &children_query,
+            &child_entities_query,
+            &mut owner_collider_entities,
+        );
+        let Some((min_y, max_y)) = owner_collider_entities
+            .iter()
+            .filter_map(|entity| {
+                let (collider, collider_transform) = colliders_query.get(*entity).ok()?;
+                let aabb = if *entity == owner_entity {
+                    collider.raw.compute_local_aabb()
+                } else {
+                    let relative_transform = collider_transform.reparented_to(owner_transform);
+                    collider
+                        .raw
+                        .compute_aabb(&bevy_rapier2d::utils::transform_to_iso(&relative_transform))
+                };
+                Some((aabb.mins.y, aabb.maxs.y))
+            })
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+        else {
+            continue;
+        };
+        let bottom_offset = -min_y;
+        let half_height = 0.5 * (max_y - min_y);
+        // Changes to child colliders cannot be detected from the character entity, so this runs
+        // every frame - but should only mark the dimensions as changed when they actually change.
+        if dimensions.bottom_offset != bottom_offset || dimensions.half_height != half_height {
+            *dimensions = TnuaColliderDimensions {
+                bottom_offset,
+                half_height,
+            };
+        }
+    }
+}
+
+fn update_feet_collider_dimensions_system(
+    mut query: Query<(
+        &GlobalTransform,
+        &TnuaFeetCollider,
+        &mut TnuaColliderDimensions,
+    )>,
+    feet_query: Query<(&Collider, &GlobalTransform)>,
+) {
+    for (owner_transform, TnuaFeetCollider(feet_entity), mut dimensions) in query.iter_mut() {
+        let Ok((collider, feet_transform)) = feet_query.get(*feet_entity) else {
+            continue;
+        };
+        // The feet collider may be offset from the origin of the character entity.
+        let offset = feet_transform.reparented_to(owner_transform).translation.y;
+        let aabb = collider.raw.compute_local_aabb();
+        let bottom_offset = -(offset + aabb.mins.y);
+        let half_height = aabb.half_extents().y;
+        // Changes to the feet collider cannot be detected from the character entity, so this runs
+        // every frame - but should only mark the dimensions as changed when they actually change.
+        if dimensions.bottom_offset != bottom_offset || dimensions.half_height != half_height {
+            *dimensions = TnuaColliderDimensions {
+                bottom_offset,
+                half_height,
+            };
+        }
+    }
+}
+
 fn update_rigid_body_trackers_system(
     rapier_config: Single<&RapierConfiguration>,
     mut query: Query<(
@@ -121,6 +259,38 @@ fn get_collider(
     //if let Some(owner_collider) = rapier_context.entity2collider().get(&owner_entity).and_then(|handle| rapier_context.colliders.get(*handle)) {
 }
 
+/// Collect the colliders on the descendants of `entity` that belong to its rigid body.
+///
+/// Descendants with their own [`RigidBody`] (and everything below them) are skipped, since their
+/// colliders belong to that rigid body.
+fn collect_child_colliders(
+    entity: Entity,
+    children_query: &Query<&Children>,
+    child_entities_query: &Query<(Has<Collider>, Has<RigidBody>)>,
+    collider_entities: &mut Vec<Entity>,
+) {
+    let Ok(children) = children_query.get(entity) else {
+        return;
+    };
+    for child in children.iter() {
+        let Ok((has_collider, has_rigid_body)) = child_entities_query.get(*child) else {
+            continue;
+        };
+        if has_rigid_body {
+            continue;
+        }
+        if has_collider {
+            collider_entities.push(*child);
+        }
+        collect_child_colliders(
+            *child,
+            children_query,
+            child_entities_query,
+            collider_entities,
+        );
+    }
+}
+
 #[allow(clippy::type_complexity)]
 fn update_proximity_sensors_system(
     rapier_context_query: RapierContextAccess,
@@ -130,12 +300,20 @@ fn update_proximity_sensors_system(
         &GlobalTransform,
         &mut TnuaProximitySensor,
         Option<&TnuaRapier2dSensorShape>,
+        Has<TnuaRapier2dColliderCastOrigin>,
         Option<&mut TnuaGhostSensor>,
         Option<&TnuaSubservientSensor>,
         Option<&TnuaToggle>,
     )>,
-    ghost_platforms_query: Query<(), With<TnuaGhostPlatform>>,
+    ghost_platforms_query: Query<
+        Option<&TnuaGhostSurface>,
+        Or<(With<TnuaGhostPlatform>, With<TnuaGhostSurface>)>,
+    >,
+    feet_colliders_query: Query<&TnuaFeetCollider>,
+    children_query: Query<&Children>,
+    child_entities_query: Query<(Has<Collider>, Has<RigidBody>)>,
     other_object_query_query: Query<(&GlobalTransform, &Velocity)>,
+    owner_collider_entities_buffers: Local<Parallel<Vec<Entity>>>,
 ) {
     query.par_iter_mut().for_each(
         |(
@@ -144,6 +322,7 @@ fn update_proximity_sensors_system(
             transform,
             mut sensor,
             shape,
+            cast_from_collider,
             mut ghost_sensor,
             subservient,
             tnua_toggle,
@@ -154,12 +333,17 @@ fn update_proximity_sensors_system(
                 TnuaToggle::Enabled => {}
             }
 
+            if sensor.skip_next_cast {
+                sensor.skip_next_cast = false;
+                return;
+            }
+
             let Some(rapier_context) = rapier_context_query.try_context(rapier_context_entity_link)
             else {
                 return;
             };
 
-            let cast_origin = transform.transform_point(sensor.cast_origin);
+            let mut cast_origin = transform.transform_point(sensor.cast_origin);
             let cast_direction = sensor.cast_direction;
 
             struct CastResult {
@@ -180,13 +364,48 @@ fn update_proximity_sensors_system(
             let mut query_filter = QueryFilter::new().exclude_rigid_body(owner_entity);
             let owner_solver_groups: InteractionGroups;
 
-            if let Some(owner_collider) = get_collider(rapier_context, owner_entity) {
+            // The colliders may be on child entities (e.g. with character models loaded from glTF).
+            // The rigid body exclusion already covers them, but their collision groups and contacts
+            // are still needed.
+            let mut owner_collider_entities = owner_collider_entities_buffers.borrow_local_mut();
+            owner_collider_entities.clear();
+            owner_collider_entities.push(owner_entity);
+            collect_child_colliders(
+                owner_entity,
+                &children_query,
+                &child_entities_query,
+                &mut owner_collider_entities,
+            );
+
+            let feet_collider_entity = feet_colliders_query
+                .get(owner_entity)
+                .ok()
+                .map(|TnuaFeetCollider(entity)| *entity);
+            if let Some(feet_collider_entity) = feet_collider_entity {
+                // The feet collider goes first, so that it's the one whose collision groups are
+                // used.
+                owner_collider_entities.retain(|entity| *entity != feet_collider_entity);
+                owner_collider_entities.insert(0, feet_collider_entity);
+                // The feet collider may be on another rigid body, which the rigid body exclusion
+                // does not cover.
+                query_filter = query_filter.exclude_collider(feet_collider_entity);
+            }
+
+            if let Some(owner_collider) = owner_collider_entities
+                .iter()
+                .find_map(|entity| get_collider(rapier_context, *entity))
+            {
                 let collision_groups = owner_collider.collision_groups();
                 query_filter.groups = Some(CollisionGroups {
                     memberships: Group::from_bits_truncate(collision_groups.memberships.bits()),
                     filters: Group::from_bits_truncate(collision_groups.filter.bits()),
                 });
                 owner_solver_groups = owner_collider.solver_groups();
+                if cast_from_collider {
+                    let aabb_center = owner_collider.compute_aabb().center();
+                    cast_origin +=
+                        Vec3::new(aabb_center.x, aabb_center.y, 0.0) - transform.translation();
+                }
             } else {
                 owner_solver_groups = InteractionGroups::all();
             }
@@ -195,18 +414,37 @@ fn update_proximity_sensors_system(
 
             let has_ghost_sensor = ghost_sensor.is_some();
 
+            // `None` for regular entities, `Some(true)` for ghost platforms (and ghost surfaces
+            // that are passable in the cast direction), and `Some(false)` for ghost surfaces that
+            // block the cast.
+            let ghost_status = |entity: Entity| -> Option<bool> {
+                match ghost_platforms_query.get(entity) {
+                    Ok(None) => Some(true),
+                    Ok(Some(ghost_surface)) => Some(ghost_surface.is_passable_in(cast_direction)),
+                    Err(_) => None,
+                }
+            };
+
             let do_cast = |cast_range_skip: f32,
-                           already_visited_ghost_entities: &HashSet<Entity>|
+                           already_visited_ghost_entities: &HashSet<Entity>,
+                           already_hit_entities: &HashSet<Entity>|
              -> Option<CastResult> {
                 let predicate = |other_entity: Entity| {
+                    if already_hit_entities.contains(&other_entity) {
+                        return false;
+                    }
                     if let Some(other_collider) = get_collider(rapier_context, other_entity) {
                         if !other_collider.solver_groups().test(owner_solver_groups) {
-                            if has_ghost_sensor && ghost_platforms_query.contains(other_entity) {
-                                if already_visited_ghost_entities.contains(&other_entity) {
+                            match ghost_status(other_entity) {
+                                Some(true) if has_ghost_sensor => {
+                                    if already_visited_ghost_entities.contains(&other_entity) {
+                                        return false;
+                                    }
+                                }
+                                Some(false) => {}
+                                _ => {
                                     return false;
                                 }
-                            } else {
-                                return false;
                             }
                         }
                         if other_collider.is_sensor() {
@@ -215,19 +453,23 @@ fn update_proximity_sensors_system(
                     }
 
                     // This fixes https://github.com/idanarye/bevy-tnua/issues/14
-                    if let Some(contact) = rapier_context.contact_pair(owner_entity, other_entity) {
-                        let same_order = owner_entity == contact.collider1();
-                        for manifold in contact.manifolds() {
-                            if 0 < manifold.num_points() {
-                                let manifold_normal = if same_order {
-                                    manifold.local_n2()
-                                } else {
-                                    manifold.local_n1()
-                                };
-                                if sensor.intersection_match_prevention_cutoff
-                                    < manifold_normal.dot(cast_direction.truncate())
-                                {
-                                    return false;
+                    for owner_collider_entity in owner_collider_entities.iter() {
+                        if let Some(contact) =
+                            rapier_context.contact_pair(*owner_collider_entity, other_entity)
+                        {
+                            let same_order = *owner_collider_entity == contact.collider1();
+                            for manifold in contact.manifolds() {
+                                if 0 < manifold.num_points() {
+                                    let manifold_normal = if same_order {
+                                        manifold.local_n2()
+                                    } else {
+                                        manifold.local_n1()
+                                    };
+                                    if sensor.intersection_match_prevention_cutoff
+                                        < manifold_normal.dot(cast_direction.truncate())
+                                    {
+                                        return false;
+                                    }
                                 }
                             }
                         }
@@ -285,68 +527,109 @@ fn update_proximity_sensors_system(
             if let Some(ghost_sensor) = ghost_sensor.as_mut() {
                 ghost_sensor.0.clear();
             }
-            sensor.output = 'sensor_output: loop {
-                if let Some(CastResult {
+            let max_hits = sensor.max_hits.max(1);
+            let mut already_hit_entities = HashSet::<Entity>::default();
+            let mut final_sensor_output = None;
+            let mut additional_outputs = Vec::new();
+            while let Some(CastResult {
+                entity,
+                proximity,
+                intersection_point,
+                normal,
+            }) = do_cast(
+                cast_range_skip,
+                &already_visited_ghost_entities,
+                &already_hit_entities,
+            ) {
+                let entity_linvel;
+                let entity_angvel;
+                if let Ok((entity_transform, entity_velocity)) =
+                    other_object_query_query.get(entity)
+                {
+                    entity_angvel = Vec3::new(0.0, 0.0, entity_velocity.angvel);
+                    entity_linvel = entity_velocity.linvel.extend(0.0)
+                        + if 0.0 < entity_velocity.angvel.abs() {
+                            let relative_point =
+                                intersection_point - entity_transform.translation().truncate();
+                            // NOTE: no need to project relative_point on the rotation plane, it will not
+                            // affect the cross product.
+                            entity_angvel.cross(relative_point.extend(0.0))
+                        } else {
+                            Vec3::ZERO
+                        };
+                } else {
+                    entity_angvel = Vec3::ZERO;
+                    entity_linvel = Vec3::ZERO;
+                }
+                let sensor_output = TnuaProximitySensorOutput {
                     entity,
                     proximity,
-                    intersection_point,
                     normal,
-                }) = do_cast(cast_range_skip, &already_visited_ghost_entities)
-                {
-                    let entity_linvel;
-                    let entity_angvel;
-                    if let Ok((entity_transform, entity_velocity)) =
-                        other_object_query_query.get(entity)
-                    {
-                        entity_angvel = Vec3::new(0.0, 0.0, entity_velocity.angvel);
-                        entity_linvel = entity_velocity.linvel.extend(0.0)
-                            + if 0.0 < entity_velocity.angvel.abs() {
-                                let relative_point =
-                                    intersection_point - entity_transform.translation().truncate();
-                                // NOTE: no need to project relative_point on the rotation plane, it will not
-                                // affect the cross product.
-                                entity_angvel.cross(relative_point.extend(0.0))
-                            } else {
-                                Vec3::ZERO
-                            };
-                    } else {
-                        entity_angvel = Vec3::ZERO;
-                        entity_linvel = Vec3::ZERO;
-                    }
-                    let sensor_output = TnuaProximitySensorOutput {
-                        entity,
-                        proximity,
-                        normal,
-                        entity_linvel,
-                        entity_angvel,
-                    };
-                    if ghost_platforms_query.contains(entity) {
-                        cast_range_skip = proximity;
-                        already_visited_ghost_entities.insert(entity);
+                    entity_linvel,
+                    entity_angvel,
+                };
+                if ghost_status(entity) == Some(true) {
+                    cast_range_skip = proximity;
+                    already_visited_ghost_entities.insert(entity);
+                    if final_sensor_output.is_none() {
                         if let Some(ghost_sensor) = ghost_sensor.as_mut() {
                             ghost_sensor.0.push(sensor_output);
                         }
-                    } else {
-                        break 'sensor_output Some(sensor_output);
                     }
                 } else {
-                    break 'sensor_output None;
+                    if final_sensor_output.is_none() {
+                        final_sensor_output = Some(sensor_output);
+                    } else {
+                        additional_outputs.push(sensor_output);
+                    }
+                    if max_hits <= 1 + additional_outputs.len() {
+                        break;
+                    }
+                    already_hit_entities.insert(entity);
                 }
-            };
+            }
+            sensor.output = final_sensor_output;
+            sensor.additional_outputs = additional_outputs;
         },
     );
 }
 
+#[allow(clippy::type_complexity)]
 fn apply_motors_system(
+    time: Res<Time>,
+    rapier_context_query: RapierContextAccess,
     mut query: Query<(
+        Entity,
+        &RapierContextEntityLink,
         &TnuaMotor,
+        &mut Transform,
         &mut Velocity,
         &ReadMassProperties,
         &mut ExternalForce,
         Option<&TnuaToggle>,
+        Option<&TnuaMotorPushLimit>,
+        Option<&TnuaMotorFilter>,
+        Option<&LockedAxes>,
     )>,
+    rigid_bodies_query: Query<&RigidBody>,
+    children_query: Query<&Children>,
+    child_entities_query: Query<(Has<Collider>, Has<RigidBody>)>,
+    mut owner_collider_entities: Local<Vec<Entity>>,
 ) {
-    for (motor, mut velocity, mass_properties, mut external_force, tnua_toggle) in query.iter_mut()
+    let frame_duration = time.delta_secs();
+    for (
+        entity,
+        rapier_context_entity_link,
+        motor,
+        mut transform,
+        mut velocity,
+        mass_properties,
+        mut external_force,
+        tnua_toggle,
+        push_limit,
+        motor_filter,
+        locked_axes,
+    ) in query.iter_mut()
     {
         match tnua_toggle.copied().unwrap_or_default() {
             TnuaToggle::Disabled | TnuaToggle::SenseOnly => {
@@ -355,18 +638,72 @@ fn apply_motors_system(
             }
             TnuaToggle::Enabled => {}
         }
-        if motor.lin.boost.is_finite() {
-            velocity.linvel += motor.lin.boost.truncate();
+        let mut lin = motor.lin.clone();
+        if let Some((push_limit, rapier_context)) =
+            push_limit.zip(rapier_context_query.try_context(rapier_context_entity_link))
+        {
+            // The colliders may be on child entities (e.g. with character models loaded from glTF).
+            owner_collider_entities.clear();
+            owner_collider_entities.push(entity);
+            collect_child_colliders(
+                entity,
+                &children_query,
+                &child_entities_query,
+                &mut owner_collider_entities,
+            );
+            for owner_collider_entity in owner_collider_entities.iter() {
+                for contact in rapier_context.contact_pairs_with(*owner_collider_entity) {
+                    let same_order = *owner_collider_entity == contact.collider1();
+                    let other_entity = if same_order {
+                        contact.collider2()
+                    } else {
+                        contact.collider1()
+                    };
+                    if !matches!(rigid_bodies_query.get(other_entity), Ok(RigidBody::Dynamic)) {
+                        continue;
+                    }
+                    for manifold in contact.manifolds() {
+                        if manifold.num_points() == 0 {
+                            continue;
+                        }
+                        // Like in the sensor, this normal points from the other body to the
+                        // character. The world-space normal points from the first collider to the
+                        // second.
+                        let manifold_normal = if same_order {
+                            -manifold.normal()
+                        } else {
+                            manifold.normal()
+                        };
+                        let push_direction = -manifold_normal.extend(0.0);
+                        push_limit.limit_vel_change(
+                            &mut lin,
+                            push_direction,
+                            mass_properties.get().mass,
+                            frame_duration,
+                        );
+                    }
+                }
+            }
         }
-        if motor.lin.acceleration.is_finite() {
-            external_force.force = motor.lin.acceleration.truncate() * mass_properties.get().mass;
+        let mut ang = motor.ang.clone();
+        if let Some(motor_filter) = motor_filter {
+            motor_filter.apply(&mut lin, &mut ang);
         }
-        if motor.ang.boost.is_finite() {
-            velocity.angvel += motor.ang.boost.z;
+        lin.quarantine_non_finite(entity, "linear");
+        ang.quarantine_non_finite(entity, "angular");
+        if let Some(teleport) = motor.teleport {
+            transform.translation = teleport.truncate().extend(transform.translation.z);
         }
-        if motor.ang.acceleration.is_finite() {
-            external_force.torque =
-                motor.ang.acceleration.z * mass_properties.get().principal_inertia;
+        velocity.linvel += lin.boost.truncate();
+        external_force.force = lin.acceleration.truncate() * mass_properties.get().mass;
+        // In 2D the angular velocity is a scalar around the Z axis. The angular motor is still
+        // calculated in 3D, but whatever it does around the X and Y axes cannot be applied.
+        if locked_axes.is_some_and(|locked_axes| locked_axes.contains(LockedAxes::ROTATION_LOCKED))
+        {
+            external_force.torque = 0.0;
+        } else {
+            velocity.angvel += ang.boost.z;
+            external_force.torque = ang.acceleration.z * mass_properties.get().principal_inertia;
         }
     }
 }
@@ -0,0 +1,152 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
+
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{TnuaProximitySensor, TnuaProximitySensorOutput};
+
+/// A proximity sensor an [action](crate::TnuaAction) needs, in addition to the character's main
+/// proximity sensor.
+///
+/// Actions declare these in [`TnuaAction::sensors`](crate::TnuaAction::sensors), and the
+/// controller creates them as subservient sensor entities while the action is active. Their
+/// outputs can be read in the action via [`TnuaActionContext::sensors`](crate::TnuaActionContext).
+#[derive(Debug, Clone)]
+pub struct TnuaActionSensorSpec {
+    /// Used for identifying the sensor when reading its output with
+    /// [`TnuaActionSensors::output`]. Must be unique among the sensors of the same action.
+    pub name: &'static str,
+
+    /// The origin of the cast, relative to the character.
+    pub cast_origin: Vector3,
+
+    /// The direction of the cast, in world space.
+    pub cast_direction: Dir3,
+
+    /// The maximum distance of the cast.
+    pub cast_range: Float,
+
+    /// The radius of the shape the action would prefer to cast, if any.
+    ///
+    /// Tnua cannot create backend-specific cast shapes by itself. This is passed to the
+    /// [`TnuaActionSensorsSetup`], which can use it to add the shape component to the sensor
+    /// entity. Without a [`TnuaActionSensorsSetup`] the sensor will cast a ray.
+    pub shape_hint: Option<Float>,
+}
+
+/// The sensors declared by the current action of a [`TnuaController`](crate::prelude::TnuaController).
+///
+/// This component is automatically added to entities with a
+/// [`TnuaController`](crate::prelude::TnuaController), and it is managed by the controller - the
+/// only reason to access it from game code is to read the outputs of the sensors.
+#[derive(Component, Default)]
+pub struct TnuaActionSensors {
+    pub(crate) requested: Vec<TnuaActionSensorSpec>,
+    entities: HashMap<&'static str, Entity>,
+    outputs: HashMap<&'static str, TnuaProximitySensorOutput>,
+}
+
+impl TnuaActionSensors {
+    /// The output of the sensor with the given name, if that sensor exists and detected something.
+    ///
+    /// Note that a sensor only exists from the frame after the action first declared it, so its
+    /// output will be `None` in the action's first frame.
+    pub fn output(&self, name: &str) -> Option<&TnuaProximitySensorOutput> {
+        self.outputs.get(name)
+    }
+}
+
+/// Customizes the sensor entities created for the actions' [sensors](TnuaActionSensorSpec).
+///
+/// Place this on the character entity (the one with the
+/// [`TnuaController`](crate::prelude::TnuaController)). The function is called with the command
+/// that creates each sensor entity, and can add things to it - mostly cast-shape components based
+/// on the [`shape_hint`](TnuaActionSensorSpec::shape_hint).
+#[derive(Component)]
+pub struct TnuaActionSensorsSetup {
+    #[allow(clippy::type_complexity)]
+    setup: Box<dyn Send + Sync + Fn(&TnuaActionSensorSpec, &mut EntityCommands)>,
+}
+
+impl TnuaActionSensorsSetup {
+    pub fn new(
+        setup: impl 'static + Send + Sync + Fn(&TnuaActionSensorSpec, &mut EntityCommands),
+    ) -> Self {
+        Self {
+            setup: Box::new(setup),
+        }
+    }
+}
+
+pub(crate) fn read_action_sensors_system(
+    mut query: Query<&mut TnuaActionSensors>,
+    sensors_query: Query<&TnuaProximitySensor, With<TnuaSubservientSensor>>,
+) {
+    for mut action_sensors in query.iter_mut() {
+        let action_sensors = action_sensors.as_mut();
+        action_sensors.outputs.clear();
+        for (name, sensor_entity) in action_sensors.entities.iter() {
+            if let Some(output) = sensors_query
+                .get(*sensor_entity)
+                .ok()
+                .and_then(|sensor| sensor.output.clone())
+            {
+                action_sensors.outputs.insert(name, output);
+            }
+        }
+    }
+}
+
+pub(crate) fn update_action_sensors_system(
+    mut query: Query<(
+        Entity,
+        &mut TnuaActionSensors,
+        Option<&TnuaActionSensorsSetup>,
+    )>,
+    mut sensors_query: Query<&mut TnuaProximitySensor, With<TnuaSubservientSensor>>,
+    mut commands: Commands,
+) {
+    for (owner_entity, mut action_sensors, setup) in query.iter_mut() {
+        let action_sensors = action_sensors.as_mut();
+        action_sensors.entities.retain(|name, sensor_entity| {
+            if action_sensors
+                .requested
+                .iter()
+                .any(|spec| spec.name == *name)
+            {
+                true
+            } else {
+                commands.entity(*sensor_entity).despawn_recursive();
+                false
+            }
+        });
+        for spec in action_sensors.requested.iter() {
+            if let Some(mut sensor) = action_sensors
+                .entities
+                .get(spec.name)
+                .and_then(|sensor_entity| sensors_query.get_mut(*sensor_entity).ok())
+            {
+                sensor.cast_origin = spec.cast_origin;
+                sensor.cast_direction = spec.cast_direction;
+                sensor.cast_range = spec.cast_range;
+            } else {
+                let mut cmd = commands.spawn((
+                    Transform::default(),
+                    TnuaSubservientSensor { owner_entity },
+                    TnuaProximitySensor {
+                        cast_origin: spec.cast_origin,
+                        cast_direction: spec.cast_direction,
+                        cast_range: spec.cast_range,
+                        ..Default::default()
+                    },
+                ));
+                cmd.set_parent(owner_entity);
+                if let Some(setup) = setup {
+                    (setup.setup)(spec, &mut cmd);
+                }
+                action_sensors.entities.insert(spec.name, cmd.id());
+            }
+        }
+    }
+}
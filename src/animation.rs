@@ -0,0 +1,246 @@
+//! Integration with Bevy's [animation graphs](AnimationGraph).
+//!
+//! Only available with the `animation` feature.
+//!
+//! Instead of writing a system that reads the [`TnuaAnimatingStateDirective`] and works the
+//! [`AnimationPlayer`] manually, place a [`TnuaAnimationGraphMapping`] that maps the animation
+//! states to [`AnimationNodeIndex`]es on the character entity (together with the
+//! [`TnuaAnimatingState`]), and add [`TnuaAnimationGraphPlugin`] for the state type. The system
+//! that decides which animation to play still needs to feed the [`TnuaAnimatingState`] every
+//! frame - the plugin will take care of starting the animations, blending between them, and
+//! updating their speed.
+//!
+//! [`TnuaAnimatingStateDirective`]: crate::TnuaAnimatingStateDirective
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use bevy::animation::transition::AnimationTransitions;
+use bevy::app::Animation;
+use bevy::prelude::*;
+
+use crate::TnuaAnimatingState;
+
+/// Must be added once for every animation state type that is used with a
+/// [`TnuaAnimationGraphMapping`].
+///
+/// The animations are driven in [`PostUpdate`], right before Bevy's animation systems, so the
+/// [`TnuaAnimatingState`] can be updated in any schedule that runs before it.
+pub struct TnuaAnimationGraphPlugin<State> {
+    _phantom: PhantomData<fn() -> State>,
+}
+
+impl<State> Default for TnuaAnimationGraphPlugin<State> {
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// A plugin required for making a [`TnuaAnimationGraphMapping`] work.
+impl<State: 'static + Send + Sync> Plugin for TnuaAnimationGraphPlugin<State> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            drive_animation_graph_system::<State>.before(Animation),
+        );
+    }
+}
+
+/// How to play an animation node of the [`AnimationGraph`].
+pub struct TnuaAnimationNode<State> {
+    node: AnimationNodeIndex,
+    blend_time: Duration,
+    repeat: bool,
+    #[allow(clippy::type_complexity)]
+    speed: Option<Box<dyn Send + Sync + Fn(&State) -> f32>>,
+}
+
+impl<State> TnuaAnimationNode<State> {
+    /// Play the node on repeat, without blending it with the previous animation.
+    pub fn new(node: AnimationNodeIndex) -> Self {
+        Self {
+            node,
+            blend_time: Duration::ZERO,
+            repeat: true,
+            speed: None,
+        }
+    }
+
+    /// Blend the previous animation into this one over the given duration.
+    pub fn with_blend_time(self, blend_time: Duration) -> Self {
+        Self { blend_time, ..self }
+    }
+
+    /// Play the animation only once instead of repeating it.
+    pub fn once(self) -> Self {
+        Self {
+            repeat: false,
+            ..self
+        }
+    }
+
+    /// Set the speed of the animation from the state.
+    ///
+    /// The speed is updated every frame - even when the animation itself does not change - so
+    /// that it can follow the parameters of the state (e.g. the running speed).
+    pub fn with_speed(self, speed: impl 'static + Send + Sync + Fn(&State) -> f32) -> Self {
+        Self {
+            speed: Some(Box::new(speed)),
+            ..self
+        }
+    }
+}
+
+/// Maps the animation states of a [`TnuaAnimatingState`] to animation nodes.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::TnuaAnimatingState;
+/// # use bevy_tnua::animation::{TnuaAnimationGraphMapping, TnuaAnimationNode};
+/// # use bevy_tnua::math::Float;
+/// # let mut commands: Commands = panic!();
+/// # let (standing_node, running_node, jumping_node): (AnimationNodeIndex, AnimationNodeIndex, AnimationNodeIndex) = panic!();
+/// # let player_entity: Entity = panic!();
+/// enum AnimationState {
+///     Standing,
+///     Running(Float),
+///     Jumping,
+/// }
+///
+/// commands.spawn((
+///     TnuaAnimatingState::<AnimationState>::default(),
+///     TnuaAnimationGraphMapping::new()
+///         .with_player_entity(player_entity)
+///         .map(
+///             |state| matches!(state, AnimationState::Standing),
+///             TnuaAnimationNode::new(standing_node).with_blend_time(Duration::from_millis(200)),
+///         )
+///         .map(
+///             |state| matches!(state, AnimationState::Running(_)),
+///             TnuaAnimationNode::new(running_node)
+///                 .with_blend_time(Duration::from_millis(100))
+///                 .with_speed(|state| match state {
+///                     AnimationState::Running(speed) => 0.1 * *speed as f32,
+///                     _ => 1.0,
+///                 }),
+///         )
+///         .map(
+///             |state| matches!(state, AnimationState::Jumping),
+///             TnuaAnimationNode::new(jumping_node).once(),
+///         ),
+/// ));
+/// ```
+#[derive(Component)]
+pub struct TnuaAnimationGraphMapping<State> {
+    player_entity: Option<Entity>,
+    #[allow(clippy::type_complexity)]
+    entries: Vec<(
+        Box<dyn Send + Sync + Fn(&State) -> bool>,
+        TnuaAnimationNode<State>,
+    )>,
+    playing_entry: Option<usize>,
+}
+
+impl<State> Default for TnuaAnimationGraphMapping<State> {
+    fn default() -> Self {
+        Self {
+            player_entity: None,
+            entries: Vec::new(),
+            playing_entry: None,
+        }
+    }
+}
+
+impl<State> TnuaAnimationGraphMapping<State> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the entity of the [`AnimationPlayer`].
+    ///
+    /// If not set, the [`AnimationPlayer`] is expected to be on the character entity itself.
+    /// Note that when the model is loaded from a glTF scene, the [`AnimationPlayer`] is usually on
+    /// one of the descendants of the entity the scene was spawned on.
+    pub fn with_player_entity(self, player_entity: Entity) -> Self {
+        Self {
+            player_entity: Some(player_entity),
+            ..self
+        }
+    }
+
+    /// Set the entity of the [`AnimationPlayer`] after the mapping was already placed on the
+    /// character.
+    pub fn set_player_entity(&mut self, player_entity: Entity) {
+        self.player_entity = Some(player_entity);
+        self.playing_entry = None;
+    }
+
+    /// Play the `node` when the `predicate` matches the state.
+    ///
+    /// The predicates are checked in the order they were added, and the first one that matches
+    /// decides the node. A new animation is started only when a different predicate matches - so
+    /// predicates that match a variant of an `enum` regardless of its payload (e.g. with
+    /// [`matches!`]) behave like [`update_by_discriminant`](TnuaAnimatingState::update_by_discriminant).
+    pub fn map(
+        mut self,
+        predicate: impl 'static + Send + Sync + Fn(&State) -> bool,
+        node: TnuaAnimationNode<State>,
+    ) -> Self {
+        self.entries.push((Box::new(predicate), node));
+        self
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn drive_animation_graph_system<State: 'static + Send + Sync>(
+    mut query: Query<(
+        Entity,
+        &TnuaAnimatingState<State>,
+        &mut TnuaAnimationGraphMapping<State>,
+    )>,
+    mut players_query: Query<(&mut AnimationPlayer, Option<&mut AnimationTransitions>)>,
+    mut commands: Commands,
+) {
+    for (entity, animating_state, mut mapping) in query.iter_mut() {
+        let Some(state) = animating_state.get() else {
+            continue;
+        };
+        let player_entity = mapping.player_entity.unwrap_or(entity);
+        let Ok((mut player, transitions)) = players_query.get_mut(player_entity) else {
+            continue;
+        };
+        let Some(mut transitions) = transitions else {
+            commands
+                .entity(player_entity)
+                .insert(AnimationTransitions::new());
+            continue;
+        };
+        let Some(entry_index) = mapping
+            .entries
+            .iter()
+            .position(|(predicate, _)| predicate(state))
+        else {
+            continue;
+        };
+        let (_, node) = &mapping.entries[entry_index];
+
+        let active_animation = if mapping.playing_entry == Some(entry_index) {
+            let Some(active_animation) = player.animation_mut(node.node) else {
+                continue;
+            };
+            active_animation
+        } else {
+            let active_animation = transitions.play(&mut player, node.node, node.blend_time);
+            if node.repeat {
+                active_animation.repeat();
+            }
+            active_animation
+        };
+        if let Some(speed) = node.speed.as_ref() {
+            active_animation.set_speed(speed(state));
+        }
+        mapping.playing_entry = Some(entry_index);
+    }
+}
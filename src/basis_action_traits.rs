@@ -2,8 +2,10 @@ use bevy::prelude::*;
 use bevy::time::Stopwatch;
 use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
 
-use std::{any::Any, time::Duration};
+use std::{any::Any, cell::RefCell, time::Duration};
 
+use crate::action_sensors::{TnuaActionSensorSpec, TnuaActionSensors};
+use crate::controller::TnuaWalkableFilter;
 use crate::{TnuaMotor, TnuaProximitySensor, TnuaRigidBodyTracker};
 
 /// Various data passed to [`TnuaBasis::apply`].
@@ -19,6 +21,21 @@ pub struct TnuaBasisContext<'a> {
 
     /// The direction considered as "up".
     pub up_direction: Dir3,
+
+    /// Additional restrictions on which surfaces the character can stand on, if the character
+    /// entity has the [`TnuaWalkableFilter`] component.
+    pub walkable_filter: Option<&'a TnuaWalkableFilter>,
+}
+
+impl TnuaBasisContext<'_> {
+    /// Checks if the surface the proximity sensor detected can be stood on, according to the
+    /// [`walkable_filter`](Self::walkable_filter).
+    ///
+    /// Note that this does not check the slope of the surface - only the filter.
+    pub fn is_walkable(&self, entity: Entity, normal: Dir3) -> bool {
+        self.walkable_filter
+            .is_none_or(|walkable_filter| walkable_filter.is_walkable(entity, normal))
+    }
 }
 
 /// The main movement command of a character.
@@ -91,6 +108,15 @@ pub trait TnuaBasis: 'static + Send + Sync {
     /// This is a query method, used by the action to determine what the basis thinks.
     fn is_airborne(&self, state: &Self::State) -> bool;
 
+    /// Information about the ground the character stands on.
+    ///
+    /// This is a query method, used by actions that need to adapt to the ground (e.g. to move
+    /// along a slope). Returns `None` when the character is not standing on anything - or when the
+    /// basis does not track the ground at all, which is the default.
+    fn ground_details(&self, _state: &Self::State) -> Option<TnuaGroundDetails> {
+        None
+    }
+
     /// If the basis is at coyote time - finish the coyote time.
     ///
     /// This will be called automatically by Tnua, if the controller runs an action that  [violated
@@ -130,8 +156,14 @@ pub trait DynamicBasis: Send + Sync + Any + 'static {
     /// Dynamically invokes [`TnuaBasis::is_airborne`].
     fn is_airborne(&self) -> bool;
 
+    /// Dynamically invokes [`TnuaBasis::ground_details`].
+    fn ground_details(&self) -> Option<TnuaGroundDetails>;
+
     #[doc(hidden)]
     fn violate_coyote_time(&mut self);
+
+    #[doc(hidden)]
+    fn reset_state(&mut self);
 }
 
 pub(crate) struct BoxableBasis<B: TnuaBasis> {
@@ -185,9 +217,31 @@ impl<B: TnuaBasis> DynamicBasis for BoxableBasis<B> {
         self.input.is_airborne(&self.state)
     }
 
+    fn ground_details(&self) -> Option<TnuaGroundDetails> {
+        self.input.ground_details(&self.state)
+    }
+
     fn violate_coyote_time(&mut self) {
         self.input.violate_coyote_time(&mut self.state)
     }
+
+    fn reset_state(&mut self) {
+        self.state = Default::default();
+    }
+}
+
+/// Information about the ground a character stands on, as reported by
+/// [`TnuaBasis::ground_details`].
+#[derive(Debug, Clone, Copy)]
+pub struct TnuaGroundDetails {
+    /// The entity the character stands on.
+    pub entity: Entity,
+    /// The normal of the ground at the contact point.
+    pub normal: Dir3,
+    /// The point where the proximity sensor hits the ground.
+    pub contact_point: Vector3,
+    /// The angle, in radians, between the ground's normal and the up direction.
+    pub slope_angle: Float,
 }
 
 /// Various data passed to [`TnuaAction::apply`].
@@ -204,8 +258,26 @@ pub struct TnuaActionContext<'a> {
     /// The direction considered as "up".
     pub up_direction: Dir3,
 
+    /// Additional restrictions on which surfaces the character can stand on, if the character
+    /// entity has the [`TnuaWalkableFilter`] component.
+    pub walkable_filter: Option<&'a TnuaWalkableFilter>,
+
     /// An accessor to the currently active basis.
     pub basis: &'a dyn DynamicBasis,
+
+    /// The outputs of the sensors declared by the action in [`TnuaAction::sensors`].
+    pub sensors: &'a TnuaActionSensors,
+
+    /// For how long (in seconds) the action was charged before it was released.
+    ///
+    /// This is only set for actions that were initiated after returning
+    /// [`TnuaActionInitiationDirective::Charge`] from [`TnuaAction::initiation_decision`], and it
+    /// remains set for as long as that action is active. It is always `None` when passed to
+    /// [`TnuaAction::initiation_decision`] itself - use the `being_fed_for` stopwatch there.
+    pub charge_duration: Option<Float>,
+
+    /// Where the events emitted with [`emit`](Self::emit) are collected.
+    pub events: &'a TnuaActionEventQueue,
 }
 
 impl<'a> TnuaActionContext<'a> {
@@ -227,12 +299,72 @@ impl<'a> TnuaActionContext<'a> {
             tracker: self.tracker,
             proximity_sensor: self.proximity_sensor,
             up_direction: self.up_direction,
+            walkable_filter: self.walkable_filter,
         }
     }
 
     pub fn frame_duration_as_duration(&self) -> Duration {
         Duration::from_secs_f64(self.frame_duration.into())
     }
+
+    /// Information about the ground the character stands on, as reported by the basis.
+    ///
+    /// See [`TnuaBasis::ground_details`].
+    pub fn ground_details(&self) -> Option<TnuaGroundDetails> {
+        self.basis.ground_details()
+    }
+
+    /// Emit a semantic event about the action, to be sent as a
+    /// [`TnuaActionEvent`](crate::controller::TnuaActionEvent) after the controller system runs.
+    pub fn emit(&self, event: TnuaActionEventKind) {
+        self.events.push(event);
+    }
+}
+
+/// Semantic events that actions [emit](TnuaActionContext::emit) on their phase transitions.
+///
+/// Only the actions themselves know when they move from one phase to another, so these events are
+/// the place to trigger sounds and visual effects from - instead of polling the action states.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TnuaActionEventKind {
+    /// The character took off at the start of a jump.
+    JumpTakeoff,
+    /// The character reached the top of a jump and started falling.
+    JumpApex,
+    /// The character hit a ceiling during a jump and started falling.
+    ///
+    /// Only emitted when the jump's [`hit_ceiling`](crate::builtins::TnuaBuiltinJump::hit_ceiling)
+    /// is set.
+    JumpHitCeiling,
+    /// The character landed at the end of a jump.
+    LandImpact {
+        /// The velocity of the character, relative to the ground, at the moment of the impact.
+        velocity: Vector3,
+    },
+    /// A dash started.
+    DashStarted {
+        /// The direction of the dash.
+        direction: Dir3,
+    },
+    /// An event emitted by a user-defined action.
+    Custom(&'static str),
+}
+
+/// Collects the events [emitted](TnuaActionContext::emit) by an action until the controller
+/// sends them.
+#[derive(Default, Debug)]
+pub struct TnuaActionEventQueue(RefCell<Vec<TnuaActionEventKind>>);
+
+impl TnuaActionEventQueue {
+    /// Add an event to the queue.
+    pub fn push(&self, event: TnuaActionEventKind) {
+        self.0.borrow_mut().push(event);
+    }
+
+    /// Remove all the events from the queue.
+    pub fn take(&self) -> Vec<TnuaActionEventKind> {
+        self.0.take()
+    }
 }
 
 /// Input for [`TnuaAction::apply`] that informs it about the long-term feeding of the input.
@@ -352,6 +484,22 @@ pub enum TnuaActionInitiationDirective {
 
     /// The action can start this frame.
     Allow,
+
+    /// The action is being charged, and will start only after the input is released.
+    ///
+    /// As long as the input is still fed, [`TnuaAction::initiation_decision`] will be checked
+    /// again every frame (so it can still change its decision). On the first frame the input is
+    /// no longer fed, the action will start as if [`Allow`](Self::Allow) was returned, and the
+    /// time it was charged for will be available in [`TnuaActionContext::charge_duration`].
+    ///
+    /// If the action cannot start when it is released (e.g. because of a
+    /// [`TnuaLandingLag`](crate::controller::TnuaLandingLag) or the
+    /// [`TnuaActionTransitionRules`](crate::controller::TnuaActionTransitionRules)), it waits -
+    /// keeping the duration it was charged for - until it can.
+    ///
+    /// Note that this means the action will get
+    /// [`NoLongerFed`](TnuaActionLifecycleStatus::NoLongerFed) starting from its second frame.
+    Charge,
 }
 
 /// A character movement command for performing special actions.
@@ -413,6 +561,17 @@ pub trait TnuaAction: 'static + Send + Sync {
         0.0
     }
 
+    /// Additional proximity sensors the action needs while it is active.
+    ///
+    /// Like [`proximity_sensor_cast_range`](Self::proximity_sensor_cast_range), this is queried
+    /// after every [`apply`](Self::apply). The controller will create a subservient sensor entity
+    /// for each of the returned sensors, update it while it keeps being returned, and remove it
+    /// when it is no longer returned or when the action ends. Their outputs are available in
+    /// [`TnuaActionContext::sensors`].
+    fn sensors(&self, _state: &Self::State, _up_direction: Dir3) -> Vec<TnuaActionSensorSpec> {
+        Vec::new()
+    }
+
     /// Decides whether the action can start.
     ///
     /// The difference between rejecting the action here with
@@ -440,6 +599,7 @@ pub trait DynamicAction: Send + Sync + Any + 'static {
         motor: &mut TnuaMotor,
     ) -> TnuaActionLifecycleDirective;
     fn proximity_sensor_cast_range(&self) -> Float;
+    fn sensors(&self, up_direction: Dir3) -> Vec<TnuaActionSensorSpec>;
     fn initiation_decision(
         &self,
         ctx: TnuaActionContext,
@@ -485,6 +645,10 @@ impl<A: TnuaAction> DynamicAction for BoxableAction<A> {
         self.input.proximity_sensor_cast_range()
     }
 
+    fn sensors(&self, up_direction: Dir3) -> Vec<TnuaActionSensorSpec> {
+        self.input.sensors(&self.state, up_direction)
+    }
+
     fn initiation_decision(
         &self,
         ctx: TnuaActionContext,
@@ -1,10 +1,10 @@
 use crate::math::{AdjustPrecision, AsF32, Float, Vector3};
 use bevy::prelude::*;
 
-use crate::util::rotation_arc_around_axis;
+use crate::util::{rotation_arc_around_axis, ChargeCurve};
 use crate::{
-    prelude::*, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
-    TnuaActionLifecycleStatus, TnuaMotor,
+    prelude::*, TnuaActionContext, TnuaActionEventKind, TnuaActionInitiationDirective,
+    TnuaActionLifecycleDirective, TnuaActionLifecycleStatus, TnuaMotor,
 };
 
 /// The basic dash [action](TnuaAction).
@@ -27,6 +27,16 @@ pub struct TnuaBuiltinDash {
     /// Allow this action to start even if the character is not touching ground nor in coyote time.
     pub allow_in_air: bool,
 
+    /// Move along the ground the character dashes on, instead of moving in a straight line.
+    ///
+    /// When set, on every frame the character is on the ground the dash direction is projected
+    /// onto the plane of the ground, so that the dash goes up and down slopes instead of launching
+    /// the character off ramps or slamming it into upslopes.
+    ///
+    /// The ground is taken from the basis' [`ground_details`](crate::TnuaBasis::ground_details),
+    /// so this has no effect with bases that do not report it.
+    pub conform_to_ground: bool,
+
     /// The speed the character will move in during the dash.
     pub speed: Float,
 
@@ -45,6 +55,14 @@ pub struct TnuaBuiltinDash {
     /// possible (typically when a character is still in the air and about the land) and the dash
     /// action would still get registered and be executed once the dash is possible.
     pub input_buffer_time: Float,
+
+    /// Make this a charged dash, where the speed depends on how long the dash was held.
+    ///
+    /// When set, the dash does not start when it is first fed. Instead, it
+    /// [charges](TnuaActionInitiationDirective::Charge) for as long as it is fed and starts once
+    /// it is released, with its [`speed`](Self::speed) multiplied by the curve's
+    /// [`factor`](ChargeCurve::factor) for the charge duration.
+    pub charge_speed_curve: Option<ChargeCurve>,
 }
 
 impl Default for TnuaBuiltinDash {
@@ -53,11 +71,13 @@ impl Default for TnuaBuiltinDash {
             displacement: Vector3::ZERO,
             desired_forward: None,
             allow_in_air: false,
+            conform_to_ground: false,
             speed: 80.0,
             brake_to_speed: 20.0,
             acceleration: 400.0,
             brake_acceleration: 200.0,
             input_buffer_time: 0.2,
+            charge_speed_curve: None,
         }
     }
 }
@@ -76,7 +96,11 @@ impl TnuaAction for TnuaBuiltinDash {
             TnuaActionInitiationDirective::Reject
         } else if self.allow_in_air || !ctx.basis.is_airborne() {
             // Either not airborne, or air jumps are allowed
-            TnuaActionInitiationDirective::Allow
+            if self.charge_speed_curve.is_some() {
+                TnuaActionInitiationDirective::Charge
+            } else {
+                TnuaActionInitiationDirective::Allow
+            }
         } else if (being_fed_for.elapsed().as_secs_f64() as Float) < self.input_buffer_time {
             TnuaActionInitiationDirective::Delay
         } else {
@@ -91,6 +115,13 @@ impl TnuaAction for TnuaBuiltinDash {
         _lifecycle_status: TnuaActionLifecycleStatus,
         motor: &mut TnuaMotor,
     ) -> TnuaActionLifecycleDirective {
+        let speed = match &self.charge_speed_curve {
+            Some(charge_speed_curve) => {
+                self.speed * charge_speed_curve.factor(ctx.charge_duration.unwrap_or(0.0))
+            }
+            None => self.speed,
+        };
+
         // TODO: Once `std::mem::variant_count` gets stabilized, use that instead.
         for _ in 0..3 {
             return match state {
@@ -99,6 +130,7 @@ impl TnuaAction for TnuaBuiltinDash {
                         // Probably unneeded because of the `initiation_decision`, but still
                         return TnuaActionLifecycleDirective::Finished;
                     };
+                    ctx.emit(TnuaActionEventKind::DashStarted { direction });
                     *state = TnuaBuiltinDashState::During {
                         direction,
                         destination: ctx.tracker.translation + self.displacement,
@@ -123,17 +155,24 @@ impl TnuaAction for TnuaBuiltinDash {
                         continue;
                     }
 
-                    let current_speed = direction.adjust_precision().dot(ctx.tracker.velocity);
+                    let move_direction = match ctx.ground_details() {
+                        Some(ground) if self.conform_to_ground => {
+                            Dir3::new(direction.reject_from(*ground.normal)).unwrap_or(*direction)
+                        }
+                        _ => *direction,
+                    };
+
+                    let current_speed = move_direction.adjust_precision().dot(ctx.tracker.velocity);
                     if current_speed < *consider_blocked_if_speed_is_less_than {
                         return TnuaActionLifecycleDirective::Finished;
                     }
 
                     motor.lin = Default::default();
                     motor.lin.acceleration = -ctx.tracker.gravity;
-                    motor.lin.boost = (direction.adjust_precision() * self.speed
+                    motor.lin.boost = (move_direction.adjust_precision() * speed
                         - ctx.tracker.velocity)
                         .clamp_length_max(ctx.frame_duration * self.acceleration);
-                    let expected_speed = direction
+                    let expected_speed = move_direction
                         .adjust_precision()
                         .dot(ctx.tracker.velocity + motor.lin.boost);
                     *consider_blocked_if_speed_is_less_than = if current_speed < expected_speed {
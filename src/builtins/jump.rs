@@ -1,10 +1,11 @@
 use crate::math::{AdjustPrecision, Float, Vector3};
 use bevy::prelude::*;
 
-use crate::util::SegmentedJumpInitialVelocityCalculator;
+use crate::builtins::TnuaBuiltinWalk;
+use crate::util::{ChargeCurve, SegmentedJumpInitialVelocityCalculator};
 use crate::{
-    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
-    TnuaActionLifecycleStatus,
+    TnuaAction, TnuaActionContext, TnuaActionEventKind, TnuaActionInitiationDirective,
+    TnuaActionLifecycleDirective, TnuaActionLifecycleStatus, TnuaConfigWarning,
 };
 
 /// The basic jump [action](TnuaAction).
@@ -31,6 +32,14 @@ pub struct TnuaBuiltinJump {
     /// and the basis' [`displacement`](crate::TnuaBasis::displacement).
     pub height: Float,
 
+    /// Parameters to use instead of the ones in this struct when the jump starts while the
+    /// character is airborne (an air jump, e.g. a double jump).
+    ///
+    /// Jumps during coyote time are considered ground jumps.
+    ///
+    /// This only has effect if [`allow_in_air`](Self::allow_in_air) is set.
+    pub air_overrides: TnuaBuiltinJumpAirOverrides,
+
     /// Allow this action to start even if the character is not touching ground nor in coyote time.
     pub allow_in_air: bool,
 
@@ -58,7 +67,8 @@ pub struct TnuaBuiltinJump {
 
     /// Extra gravity for falling down after reaching the top of the jump.
     ///
-    /// **NOTE**: This force will be added to the normal gravity.
+    /// **NOTE**: This force will be added to the normal gravity, after it was multiplied by the
+    /// walk basis' [`fall_gravity_multiplier`](TnuaBuiltinWalk::fall_gravity_multiplier).
     pub fall_extra_gravity: Float,
 
     /// Extra gravity for shortening a jump when the player releases the jump button.
@@ -99,12 +109,51 @@ pub struct TnuaBuiltinJump {
     /// possible (typically when a character is still in the air and about the land) and the jump
     /// action would still get registered and be executed once the jump is possible.
     pub input_buffer_time: Float,
+
+    /// Set to `true` when the character hits a ceiling.
+    ///
+    /// While the character is still going up, this will cut its upward velocity and move the jump
+    /// straight to its fall section (marked as [`bonked`](TnuaBuiltinJumpState::FallSection)).
+    /// Use [`TnuaCeilingSensor::sensing`](crate::control_helpers::TnuaCeilingSensor::sensing) to
+    /// set it.
+    pub hit_ceiling: bool,
+
+    /// Make this a charged jump, where the height depends on how long the jump was held.
+    ///
+    /// When set, the jump does not start when it is first fed. Instead, it
+    /// [charges](TnuaActionInitiationDirective::Charge) for as long as it is fed and starts once
+    /// it is released, with its [`height`](Self::height) (or
+    /// [`air_overrides`](Self::air_overrides)' height) multiplied by the curve's
+    /// [`factor`](ChargeCurve::factor) for the charge duration. Since releasing the jump is what
+    /// starts it, a charged jump is never shortened by
+    /// [`shorten_extra_gravity`](Self::shorten_extra_gravity).
+    pub charge_height_curve: Option<ChargeCurve>,
+}
+
+/// Overrides for [`TnuaBuiltinJump`] parameters when it is used as an air jump.
+///
+/// See [`TnuaBuiltinJump::air_overrides`]. Fields that are `None` use the value from the
+/// [`TnuaBuiltinJump`] itself.
+#[derive(Clone, Default, Debug)]
+pub struct TnuaBuiltinJumpAirOverrides {
+    /// Overrides [`TnuaBuiltinJump::height`].
+    pub height: Option<Float>,
+
+    /// Overrides [`TnuaBuiltinJump::takeoff_extra_gravity`].
+    pub takeoff_extra_gravity: Option<Float>,
+
+    /// Overrides [`TnuaBuiltinJump::shorten_extra_gravity`].
+    pub shorten_extra_gravity: Option<Float>,
+
+    /// Overrides [`TnuaBuiltinJump::fall_extra_gravity`].
+    pub fall_extra_gravity: Option<Float>,
 }
 
 impl Default for TnuaBuiltinJump {
     fn default() -> Self {
         Self {
             height: 0.0,
+            air_overrides: Default::default(),
             allow_in_air: false,
             upslope_extra_gravity: 30.0,
             takeoff_extra_gravity: 30.0,
@@ -115,6 +164,8 @@ impl Default for TnuaBuiltinJump {
             peak_prevention_extra_gravity: 20.0,
             reschedule_cooldown: None,
             input_buffer_time: 0.2,
+            hit_ceiling: false,
+            charge_height_curve: None,
         }
     }
 }
@@ -131,7 +182,11 @@ impl TnuaAction for TnuaBuiltinJump {
     ) -> crate::basis_action_traits::TnuaActionInitiationDirective {
         if self.allow_in_air || !ctx.basis.is_airborne() {
             // Either not airborne, or air jumps are allowed
-            TnuaActionInitiationDirective::Allow
+            if self.charge_height_curve.is_some() {
+                TnuaActionInitiationDirective::Charge
+            } else {
+                TnuaActionInitiationDirective::Allow
+            }
         } else if (being_fed_for.elapsed().as_secs_f64() as Float) < self.input_buffer_time {
             TnuaActionInitiationDirective::Delay
         } else {
@@ -148,8 +203,27 @@ impl TnuaAction for TnuaBuiltinJump {
     ) -> TnuaActionLifecycleDirective {
         let up = ctx.up_direction.adjust_precision();
 
+        // A charged jump was started by releasing it, so it should not be shortened for not being
+        // fed.
+        let lifecycle_status = match (lifecycle_status, ctx.charge_duration) {
+            (TnuaActionLifecycleStatus::NoLongerFed, Some(_)) => {
+                TnuaActionLifecycleStatus::StillFed
+            }
+            _ => lifecycle_status,
+        };
+
         if lifecycle_status.just_started() {
-            let mut calculator = SegmentedJumpInitialVelocityCalculator::new(self.height);
+            let air_jump = ctx.basis.is_airborne();
+            let this = if air_jump {
+                &self.with_air_overrides()
+            } else {
+                self
+            };
+            let mut height = this.height;
+            if let Some(charge_height_curve) = &self.charge_height_curve {
+                height *= charge_height_curve.factor(ctx.charge_duration.unwrap_or(0.0));
+            }
+            let mut calculator = SegmentedJumpInitialVelocityCalculator::new(height);
             let gravity = ctx.tracker.gravity.dot(-up);
             let kinetic_energy = calculator
                 .add_segment(
@@ -157,22 +231,48 @@ impl TnuaAction for TnuaBuiltinJump {
                     self.peak_prevention_at_upward_velocity,
                 )
                 .add_segment(gravity, self.takeoff_above_velocity)
-                .add_final_segment(gravity + self.takeoff_extra_gravity)
+                .add_final_segment(gravity + this.takeoff_extra_gravity)
                 .kinetic_energy()
                 .expect("`add_final_segment` should have covered remaining height");
             *state = TnuaBuiltinJumpState::StartingJump {
                 desired_energy: kinetic_energy,
+                air_jump,
             };
         }
 
+        let air_jump = state.is_air_jump();
+        let this = if air_jump {
+            &self.with_air_overrides()
+        } else {
+            self
+        };
+
         let effective_velocity = ctx.basis.effective_velocity();
 
+        let mut cut_upward_velocity = 0.0;
+        if self.hit_ceiling
+            && matches!(
+                state,
+                TnuaBuiltinJumpState::StartingJump { .. }
+                    | TnuaBuiltinJumpState::SlowDownTooFastSlopeJump { .. }
+                    | TnuaBuiltinJumpState::MaintainingJump { .. }
+                    | TnuaBuiltinJumpState::StoppedMaintainingJump { .. }
+            )
+        {
+            cut_upward_velocity = effective_velocity.dot(up).max(0.0);
+            *state = TnuaBuiltinJumpState::FallSection {
+                bonked: true,
+                air_jump,
+            };
+            ctx.emit(TnuaActionEventKind::JumpHitCeiling);
+        }
+
         // TODO: Once `std::mem::variant_count` gets stabilized, use that instead. The idea is to
         // allow jumping through multiple states but failing if we get into loop.
         for _ in 0..7 {
             return match state {
                 TnuaBuiltinJumpState::NoJump => panic!(),
-                TnuaBuiltinJumpState::StartingJump { desired_energy } => {
+                TnuaBuiltinJumpState::StartingJump { desired_energy, .. } => {
                     let extra_height = if let Some(displacement) = ctx.basis.displacement() {
                         displacement.dot(up)
                     } else if !self.allow_in_air && ctx.basis.is_airborne() {
@@ -195,9 +295,11 @@ impl TnuaAction for TnuaBuiltinJump {
                     motor.lin.cancel_on_axis(up);
                     motor.lin.boost += (desired_upward_velocity - relative_velocity) * up;
                     if 0.0 <= extra_height {
+                        ctx.emit(TnuaActionEventKind::JumpTakeoff);
                         *state = TnuaBuiltinJumpState::SlowDownTooFastSlopeJump {
                             desired_energy: *desired_energy,
                             zero_potential_energy_at: ctx.tracker.translation - extra_height * up,
+                            air_jump,
                         };
                     }
                     self.directive_simple_or_reschedule(lifecycle_status)
@@ -205,13 +307,18 @@ impl TnuaAction for TnuaBuiltinJump {
                 TnuaBuiltinJumpState::SlowDownTooFastSlopeJump {
                     desired_energy,
                     zero_potential_energy_at,
+                    ..
                 } => {
                     let upward_velocity = up.dot(effective_velocity);
                     if upward_velocity <= ctx.basis.vertical_velocity() {
-                        *state = TnuaBuiltinJumpState::FallSection;
+                        *state = TnuaBuiltinJumpState::FallSection {
+                            bonked: false,
+                            air_jump,
+                        };
+                        ctx.emit(TnuaActionEventKind::JumpApex);
                         continue;
                     } else if !lifecycle_status.is_active() {
-                        *state = TnuaBuiltinJumpState::StoppedMaintainingJump;
+                        *state = TnuaBuiltinJumpState::StoppedMaintainingJump { air_jump };
                         continue;
                     }
                     let relative_velocity = effective_velocity.dot(up);
@@ -225,29 +332,33 @@ impl TnuaAction for TnuaBuiltinJump {
                             desired_kinetic_energy,
                         );
                     if relative_velocity <= desired_upward_velocity {
-                        *state = TnuaBuiltinJumpState::MaintainingJump;
+                        *state = TnuaBuiltinJumpState::MaintainingJump { air_jump };
                         continue;
                     } else {
                         let mut extra_gravity = self.upslope_extra_gravity;
                         if self.takeoff_above_velocity <= relative_velocity {
-                            extra_gravity += self.takeoff_extra_gravity;
+                            extra_gravity += this.takeoff_extra_gravity;
                         }
                         motor.lin.cancel_on_axis(up);
                         motor.lin.acceleration = -extra_gravity * up;
                         self.directive_simple_or_reschedule(lifecycle_status)
                     }
                 }
-                TnuaBuiltinJumpState::MaintainingJump => {
+                TnuaBuiltinJumpState::MaintainingJump { .. } => {
                     let relevant_upward_velocity = effective_velocity.dot(up);
                     if relevant_upward_velocity <= 0.0 {
-                        *state = TnuaBuiltinJumpState::FallSection;
+                        *state = TnuaBuiltinJumpState::FallSection {
+                            bonked: false,
+                            air_jump,
+                        };
+                        ctx.emit(TnuaActionEventKind::JumpApex);
                         motor.lin.cancel_on_axis(up);
                     } else {
                         motor.lin.cancel_on_axis(up);
                         if relevant_upward_velocity < self.peak_prevention_at_upward_velocity {
                             motor.lin.acceleration -= self.peak_prevention_extra_gravity * up;
                         } else if self.takeoff_above_velocity <= relevant_upward_velocity {
-                            motor.lin.acceleration -= self.takeoff_extra_gravity * up;
+                            motor.lin.acceleration -= this.takeoff_extra_gravity * up;
                         }
                     }
                     match lifecycle_status {
@@ -258,12 +369,12 @@ impl TnuaAction for TnuaBuiltinJump {
                         }
                         TnuaActionLifecycleStatus::CancelledInto => self.finish_or_reschedule(),
                         TnuaActionLifecycleStatus::NoLongerFed => {
-                            *state = TnuaBuiltinJumpState::StoppedMaintainingJump;
+                            *state = TnuaBuiltinJumpState::StoppedMaintainingJump { air_jump };
                             TnuaActionLifecycleDirective::StillActive
                         }
                     }
                 }
-                TnuaBuiltinJumpState::StoppedMaintainingJump => {
+                TnuaBuiltinJumpState::StoppedMaintainingJump { .. } => {
                     if matches!(lifecycle_status, TnuaActionLifecycleStatus::CancelledInto) {
                         self.finish_or_reschedule()
                     } else {
@@ -272,18 +383,25 @@ impl TnuaAction for TnuaBuiltinJump {
                             .displacement()
                             .map_or(false, |displacement| displacement.dot(up) <= 0.0);
                         if landed {
+                            ctx.emit(TnuaActionEventKind::LandImpact {
+                                velocity: effective_velocity,
+                            });
                             self.finish_or_reschedule()
                         } else {
                             let upward_velocity = up.dot(effective_velocity);
                             if upward_velocity <= 0.0 {
-                                *state = TnuaBuiltinJumpState::FallSection;
+                                *state = TnuaBuiltinJumpState::FallSection {
+                                    bonked: false,
+                                    air_jump,
+                                };
+                                ctx.emit(TnuaActionEventKind::JumpApex);
                                 continue;
                             }
 
                             let extra_gravity = if self.takeoff_above_velocity <= upward_velocity {
-                                self.shorten_extra_gravity + self.takeoff_extra_gravity
+                                this.shorten_extra_gravity + this.takeoff_extra_gravity
                             } else {
-                                self.shorten_extra_gravity
+                                this.shorten_extra_gravity
                             };
 
                             motor.lin.cancel_on_axis(up);
@@ -292,18 +410,31 @@ impl TnuaAction for TnuaBuiltinJump {
                         }
                     }
                 }
-                TnuaBuiltinJumpState::FallSection => {
+                TnuaBuiltinJumpState::FallSection { .. } => {
                     let landed = ctx
                         .basis
                         .displacement()
                         .map_or(false, |displacement| displacement.dot(up) <= 0.0);
-                    if landed
-                        || matches!(lifecycle_status, TnuaActionLifecycleStatus::CancelledInto)
-                    {
+                    if landed {
+                        ctx.emit(TnuaActionEventKind::LandImpact {
+                            velocity: effective_velocity,
+                        });
+                        self.finish_or_reschedule()
+                    } else if matches!(lifecycle_status, TnuaActionLifecycleStatus::CancelledInto) {
                         self.finish_or_reschedule()
                     } else {
                         motor.lin.cancel_on_axis(up);
-                        motor.lin.acceleration -= self.fall_extra_gravity * up;
+                        motor.lin.boost -= cut_upward_velocity * up;
+                        // The basis' fall gravity multiplier only applies while moving down,
+                        // which is why the jump height calculation does not need to account for
+                        // it.
+                        let fall_gravity_multiplier = ctx
+                            .concrete_basis::<TnuaBuiltinWalk>()
+                            .map_or(1.0, |(walk, _)| walk.fall_gravity_multiplier);
+                        let basis_extra_gravity =
+                            (fall_gravity_multiplier - 1.0) * ctx.tracker.gravity.dot(-up);
+                        motor.lin.acceleration -=
+                            (this.fall_extra_gravity + basis_extra_gravity) * up;
                         TnuaActionLifecycleDirective::StillActive
                     }
                 }
@@ -315,6 +446,50 @@ impl TnuaAction for TnuaBuiltinJump {
 }
 
 impl TnuaBuiltinJump {
+    /// Check the parameters for known-bad combinations.
+    ///
+    /// Pass the walk basis the jump is used with (if there is one) to also check the jump height
+    /// against its [`cling_distance`](TnuaBuiltinWalk::cling_distance). See
+    /// [`TnuaConfigValidationPlugin`](crate::TnuaConfigValidationPlugin) for doing this
+    /// automatically.
+    pub fn validate(&self, walk: Option<&TnuaBuiltinWalk>) -> Vec<TnuaConfigWarning> {
+        let mut warnings = Vec::new();
+        if self.height <= 0.0 {
+            warnings.push(TnuaConfigWarning::new(
+                "height",
+                "must be positive - otherwise the character will not jump",
+            ));
+        } else if let Some(walk) = walk {
+            if self.height <= walk.cling_distance {
+                warnings.push(TnuaConfigWarning::new(
+                    "height",
+                    format!(
+                        "{} is not higher than the walk basis' `cling_distance` ({}), so the character will never become airborne",
+                        self.height, walk.cling_distance,
+                    ),
+                ));
+            }
+        }
+        warnings
+    }
+
+    pub(crate) fn with_air_overrides(&self) -> Self {
+        let overrides = &self.air_overrides;
+        Self {
+            height: overrides.height.unwrap_or(self.height),
+            takeoff_extra_gravity: overrides
+                .takeoff_extra_gravity
+                .unwrap_or(self.takeoff_extra_gravity),
+            shorten_extra_gravity: overrides
+                .shorten_extra_gravity
+                .unwrap_or(self.shorten_extra_gravity),
+            fall_extra_gravity: overrides
+                .fall_extra_gravity
+                .unwrap_or(self.fall_extra_gravity),
+            ..self.clone()
+        }
+    }
+
     fn finish_or_reschedule(&self) -> TnuaActionLifecycleDirective {
         if let Some(cooldown) = self.reschedule_cooldown {
             TnuaActionLifecycleDirective::Reschedule {
@@ -350,12 +525,40 @@ pub enum TnuaBuiltinJumpState {
         /// Calculating the desired velocity based on energy is easier than using the ballistic
         /// formulas.
         desired_energy: Float,
+        /// Whether the jump started while the character was airborne (see
+        /// [`TnuaBuiltinJump::air_overrides`]).
+        air_jump: bool,
     },
     SlowDownTooFastSlopeJump {
         desired_energy: Float,
         zero_potential_energy_at: Vector3,
+        air_jump: bool,
+    },
+    MaintainingJump {
+        air_jump: bool,
+    },
+    StoppedMaintainingJump {
+        air_jump: bool,
     },
-    MaintainingJump,
-    StoppedMaintainingJump,
-    FallSection,
+    FallSection {
+        /// Whether the fall started because the character hit a ceiling (see
+        /// [`TnuaBuiltinJump::hit_ceiling`]).
+        bonked: bool,
+        air_jump: bool,
+    },
+}
+
+impl TnuaBuiltinJumpState {
+    /// Whether the jump started while the character was airborne (see
+    /// [`TnuaBuiltinJump::air_overrides`]).
+    pub fn is_air_jump(&self) -> bool {
+        match self {
+            TnuaBuiltinJumpState::NoJump => false,
+            TnuaBuiltinJumpState::StartingJump { air_jump, .. }
+            | TnuaBuiltinJumpState::SlowDownTooFastSlopeJump { air_jump, .. }
+            | TnuaBuiltinJumpState::MaintainingJump { air_jump }
+            | TnuaBuiltinJumpState::StoppedMaintainingJump { air_jump }
+            | TnuaBuiltinJumpState::FallSection { air_jump, .. } => *air_jump,
+        }
+    }
 }
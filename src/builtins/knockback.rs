@@ -24,6 +24,26 @@ use bevy::prelude::*;
 /// * [`barrier_strength_diminishing`](Self::barrier_strength_diminishing). Setting it too low
 ///   makes it very hard for the character to push through the boundary. It starts getting slightly
 ///   weird below 1.0, and really weird below 0.5. Better keep it at above - 1.0 levels.
+///
+/// To refresh the knockback when the character gets hit again while it is still knocked back
+/// (e.g. during an air combo), without restarting the action (and its animation), use
+/// [`TnuaBuiltinKnockbackState::refresh`] instead of feeding the action again:
+///
+/// ```no_run
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::builtins::TnuaBuiltinKnockback;
+/// # use bevy_tnua::math::Vector3;
+/// # let mut controller = TnuaController::default();
+/// # let shove = Vector3::ZERO;
+/// if let Some((_, state)) = controller.concrete_action_mut::<TnuaBuiltinKnockback>() {
+///     state.refresh(shove);
+/// } else {
+///     controller.action(TnuaBuiltinKnockback {
+///         shove,
+///         ..Default::default()
+///     });
+/// }
+/// ```
 #[derive(Clone)]
 pub struct TnuaBuiltinKnockback {
     /// Initial impulse to apply to the character before the Pushover stage starts.
@@ -33,6 +53,15 @@ pub struct TnuaBuiltinKnockback {
     /// boundary based on it.
     pub shove: Vector3,
 
+    /// Initial angular impulse to apply to the character, to make it stagger.
+    ///
+    /// Given as the rotation axis multiplied by the change in the rotation speed (in radians per
+    /// second). The tilt part of it will be recovered from by the basis' tilt correction (e.g.
+    /// [`TnuaBuiltinWalk::tilt_offset_angvel`](crate::builtins::TnuaBuiltinWalk::tilt_offset_angvel)),
+    /// so the character will briefly tilt and then straighten up. See
+    /// [`from_impact`](Self::from_impact) for calculating it from a hit.
+    pub stagger: Vector3,
+
     /// Timeout (in seconds) for abandoning a Pushover boundary that no longer gets pushed.
     pub no_push_timeout: f32,
 
@@ -64,17 +93,89 @@ pub struct TnuaBuiltinKnockback {
     /// single frame. It is useful for when the knockback animation needs to be aligned with the
     /// knockback direction.
     pub force_forward: Option<Dir3>,
+
+    /// Multiply the gravity by this while the character is airborne and moving up faster than
+    /// [`float_velocity_threshold`](Self::float_velocity_threshold).
+    pub launch_gravity_multiplier: Float,
+
+    /// Multiply the gravity by this while the character is airborne and its vertical speed is
+    /// below [`float_velocity_threshold`](Self::float_velocity_threshold) - at the top of the
+    /// arc.
+    ///
+    /// Set it below 1.0 to make the character hang in the air, so that it can be juggled.
+    pub float_gravity_multiplier: Float,
+
+    /// Multiply the gravity by this while the character is airborne and moving down faster than
+    /// [`float_velocity_threshold`](Self::float_velocity_threshold).
+    pub fall_gravity_multiplier: Float,
+
+    /// The vertical speed that separates the launch and fall phases of the knockback from its
+    /// float phase.
+    pub float_velocity_threshold: Float,
+
+    /// Keep the action running while the character is airborne, even if the Pushover boundary was
+    /// cleared.
+    ///
+    /// Without this, a knockback that launches the character upward will usually finish
+    /// when it starts falling, so the [`fall_gravity_multiplier`](Self::fall_gravity_multiplier)
+    /// and the [`hard_landing_speed`](Self::hard_landing_speed) will not apply.
+    pub persist_while_airborne: bool,
+
+    /// When the character lands while falling at least at this speed, the landing is registered
+    /// as a [hard landing](TnuaBuiltinKnockbackState::hard_landing).
+    pub hard_landing_speed: Option<Float>,
 }
 
 impl Default for TnuaBuiltinKnockback {
     fn default() -> Self {
         Self {
             shove: Vector3::ZERO,
+            stagger: Vector3::ZERO,
             no_push_timeout: 0.2,
             barrier_strength_diminishing: 2.0,
             acceleration_limit: 3.0,
             air_acceleration_limit: 1.0,
             force_forward: None,
+            launch_gravity_multiplier: 1.0,
+            float_gravity_multiplier: 1.0,
+            fall_gravity_multiplier: 1.0,
+            float_velocity_threshold: 1.0,
+            persist_while_airborne: false,
+            hard_landing_speed: None,
+        }
+    }
+}
+
+impl TnuaBuiltinKnockback {
+    /// Create a knockback from an impulse (e.g. of a bullet) that hits the character at a
+    /// specific point.
+    ///
+    /// The [`shove`](Self::shove) is the impulse divided by the `mass`, and the
+    /// [`stagger`](Self::stagger) is the angular impulse around the `center_of_mass` divided by the
+    /// `angular_inertia` - so a hit to the shoulder will make the character tilt backward, while a
+    /// hit in the center of mass will only push it. The rest of the fields are set to their
+    /// defaults.
+    pub fn from_impact(
+        impulse: Vector3,
+        impact_point: Vector3,
+        center_of_mass: Vector3,
+        mass: Float,
+        angular_inertia: Float,
+    ) -> Self {
+        let shove = if 0.0 < mass {
+            impulse / mass
+        } else {
+            Vector3::ZERO
+        };
+        let stagger = if 0.0 < angular_inertia {
+            (impact_point - center_of_mass).cross(impulse) / angular_inertia
+        } else {
+            Vector3::ZERO
+        };
+        Self {
+            shove,
+            stagger,
+            ..Default::default()
         }
     }
 }
@@ -91,8 +192,12 @@ impl TnuaAction for TnuaBuiltinKnockback {
         _lifecycle_status: TnuaActionLifecycleStatus,
         motor: &mut TnuaMotor,
     ) -> TnuaActionLifecycleDirective {
+        let up = ctx.up_direction.adjust_precision();
+        let is_airborne = ctx.basis.is_airborne();
+
         match state {
             TnuaBuiltinKnockbackState::Shove => {
+                motor.ang += TnuaVelChange::boost(self.stagger);
                 let Some(boundary) = VelocityBoundary::new(
                     ctx.tracker.velocity,
                     ctx.tracker.velocity + self.shove,
@@ -101,12 +206,64 @@ impl TnuaAction for TnuaBuiltinKnockback {
                     return TnuaActionLifecycleDirective::Finished;
                 };
                 motor.lin += TnuaVelChange::boost(self.shove);
-                *state = TnuaBuiltinKnockbackState::Pushback { boundary };
+                *state = TnuaBuiltinKnockbackState::Pushback {
+                    boundary,
+                    pending_shove: Vector3::ZERO,
+                    was_airborne: is_airborne,
+                    last_velocity: ctx.tracker.velocity + self.shove,
+                    hard_landing: None,
+                };
             }
-            TnuaBuiltinKnockbackState::Pushback { boundary } => {
-                boundary.update(ctx.tracker.velocity, ctx.frame_duration_as_duration());
+            TnuaBuiltinKnockbackState::Pushback {
+                boundary,
+                pending_shove,
+                was_airborne,
+                last_velocity,
+                hard_landing,
+            } => {
+                let mut just_landed_hard = false;
+                if *was_airborne && !is_airborne {
+                    let impact_speed = -last_velocity.dot(up);
+                    if self
+                        .hard_landing_speed
+                        .is_some_and(|hard_landing_speed| hard_landing_speed <= impact_speed)
+                    {
+                        *hard_landing = Some(TnuaBuiltinKnockbackImpact {
+                            velocity: *last_velocity,
+                            entity: ctx
+                                .proximity_sensor
+                                .output
+                                .as_ref()
+                                .map(|output| output.entity),
+                        });
+                        just_landed_hard = true;
+                    }
+                } else if is_airborne {
+                    *hard_landing = None;
+                }
+                *was_airborne = is_airborne;
+                *last_velocity = ctx.tracker.velocity;
+
+                if *pending_shove != Vector3::ZERO {
+                    let shove = std::mem::take(pending_shove);
+                    if let Some(new_boundary) = VelocityBoundary::new(
+                        ctx.tracker.velocity,
+                        ctx.tracker.velocity + shove,
+                        self.no_push_timeout,
+                    ) {
+                        *boundary = new_boundary;
+                    }
+                    motor.lin += TnuaVelChange::boost(shove);
+                    *last_velocity += shove;
+                } else {
+                    boundary.update(ctx.tracker.velocity, ctx.frame_duration_as_duration());
+                }
                 if boundary.is_cleared() {
-                    return TnuaActionLifecycleDirective::Finished;
+                    // Stay for one more frame after a hard landing, so that it can be seen in the
+                    // state.
+                    if !(just_landed_hard || (self.persist_while_airborne && is_airborne)) {
+                        return TnuaActionLifecycleDirective::Finished;
+                    }
                 } else {
                     let regular_boost = crate::util::calc_boost(&motor.lin, ctx.frame_duration);
                     if let Some((component_direction, component_limit)) = boundary
@@ -146,6 +303,18 @@ impl TnuaAction for TnuaBuiltinKnockback {
             }
         }
 
+        if is_airborne {
+            let vertical_velocity = ctx.tracker.velocity.dot(up);
+            let gravity_multiplier = if self.float_velocity_threshold < vertical_velocity {
+                self.launch_gravity_multiplier
+            } else if vertical_velocity < -self.float_velocity_threshold {
+                self.fall_gravity_multiplier
+            } else {
+                self.float_gravity_multiplier
+            };
+            motor.lin.acceleration += (gravity_multiplier - 1.0) * ctx.tracker.gravity;
+        }
+
         if let Some(force_forward) = self.force_forward {
             let current_forward = ctx.tracker.rotation.mul_vec3(Vector3::NEG_Z);
             let rotation_along_up_axis = rotation_arc_around_axis(
@@ -186,7 +355,57 @@ pub enum TnuaBuiltinKnockbackState {
     /// Hindering the character's ability to overcome the
     /// [`Shove`](TnuaBuiltinKnockbackState::Shove) while waiting for it to overcome it despite the
     /// hindrance.
-    Pushback { boundary: VelocityBoundary },
+    Pushback {
+        boundary: VelocityBoundary,
+        /// A shove added by [`refresh`](TnuaBuiltinKnockbackState::refresh), to be applied in the
+        /// next frame.
+        pending_shove: Vector3,
+        was_airborne: bool,
+        /// The velocity of the character in the previous frame.
+        last_velocity: Vector3,
+        /// See [`hard_landing`](TnuaBuiltinKnockbackState::hard_landing).
+        hard_landing: Option<TnuaBuiltinKnockbackImpact>,
+    },
+}
+
+impl TnuaBuiltinKnockbackState {
+    /// Apply another shove without restarting the action.
+    ///
+    /// The shove will be applied in the next frame, and the Pushover boundary will be replaced
+    /// with one calculated from the new shove. The action itself (and therefore its animation and
+    /// the [hard landing](Self::hard_landing) detection) continues.
+    pub fn refresh(&mut self, shove: Vector3) {
+        match self {
+            TnuaBuiltinKnockbackState::Shove => {}
+            TnuaBuiltinKnockbackState::Pushback { pending_shove, .. } => {
+                *pending_shove += shove;
+            }
+        }
+    }
+
+    /// The impact of the character hitting the ground during the knockback, if it fell fast
+    /// enough.
+    ///
+    /// Only available when [`hard_landing_speed`](TnuaBuiltinKnockback::hard_landing_speed) is
+    /// set. Remains set until the character gets airborne again (or the action finishes) so that
+    /// the game can react to it (e.g. with a ground-slam effect, or a bounce using
+    /// [`refresh`](Self::refresh)).
+    pub fn hard_landing(&self) -> Option<&TnuaBuiltinKnockbackImpact> {
+        match self {
+            TnuaBuiltinKnockbackState::Shove => None,
+            TnuaBuiltinKnockbackState::Pushback { hard_landing, .. } => hard_landing.as_ref(),
+        }
+    }
+}
+
+/// Information about a [hard landing](TnuaBuiltinKnockbackState::hard_landing) during a
+/// knockback.
+#[derive(Debug, Clone)]
+pub struct TnuaBuiltinKnockbackImpact {
+    /// The velocity of the character right before it hit the ground.
+    pub velocity: Vector3,
+    /// The entity the character landed on.
+    pub entity: Option<Entity>,
 }
 
 /// An indication that a character was knocked back and "struggles" to get back to its original
@@ -2,10 +2,21 @@ mod crouch;
 mod dash;
 mod jump;
 mod knockback;
+mod rail;
+mod roll;
+mod sprint;
+mod vault;
 mod walk;
 
 pub use crouch::{TnuaBuiltinCrouch, TnuaBuiltinCrouchState};
 pub use dash::{TnuaBuiltinDash, TnuaBuiltinDashState};
-pub use jump::{TnuaBuiltinJump, TnuaBuiltinJumpState};
-pub use knockback::{TnuaBuiltinKnockback, TnuaBuiltinKnockbackState};
-pub use walk::{TnuaBuiltinWalk, TnuaBuiltinWalkState};
+pub use jump::{TnuaBuiltinJump, TnuaBuiltinJumpAirOverrides, TnuaBuiltinJumpState};
+pub use knockback::{TnuaBuiltinKnockback, TnuaBuiltinKnockbackImpact, TnuaBuiltinKnockbackState};
+pub use rail::{TnuaBuiltinRail, TnuaBuiltinRailState, TnuaRailPath};
+pub use roll::{TnuaBuiltinRoll, TnuaBuiltinRollState};
+pub use sprint::{TnuaBuiltinSprint, TnuaBuiltinSprintState};
+pub use vault::{TnuaBuiltinVault, TnuaBuiltinVaultState};
+pub use walk::{
+    TnuaBasisFloat, TnuaBasisLocomotion, TnuaBasisOrientation, TnuaBuiltinWalk,
+    TnuaBuiltinWalkState, TnuaFloatSpring, TnuaWalkInputResponse, TnuaWalkSuspension,
+};
@@ -0,0 +1,274 @@
+use crate::math::{AdjustPrecision, Float, Vector3};
+
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor,
+};
+
+/// A path, in world space, for [`TnuaBuiltinRail`] to move the character along.
+///
+/// The path is a polyline. Curves should be sampled into one with
+/// [`from_curve`](Self::from_curve).
+#[derive(Clone, Debug, Default)]
+pub struct TnuaRailPath {
+    points: Vec<Vector3>,
+    /// The distance along the path to each of the points.
+    distances: Vec<Float>,
+}
+
+impl TnuaRailPath {
+    /// Create a path that goes through the given points, in order.
+    pub fn polyline(points: impl IntoIterator<Item = Vector3>) -> Self {
+        let points: Vec<Vector3> = points.into_iter().collect();
+        let mut distances = Vec::with_capacity(points.len());
+        let mut distance = 0.0;
+        for (i, point) in points.iter().enumerate() {
+            if 0 < i {
+                distance += point.distance(points[i - 1]);
+            }
+            distances.push(distance);
+        }
+        Self { points, distances }
+    }
+
+    /// Create a path by sampling a curve.
+    ///
+    /// The curve is called with `samples + 1` evenly spaced parameters from `0.0` to `1.0`.
+    pub fn from_curve(samples: usize, curve: impl Fn(Float) -> Vector3) -> Self {
+        let samples = samples.max(1);
+        Self::polyline((0..=samples).map(|i| curve(i as Float / samples as Float)))
+    }
+
+    /// The points of the polyline.
+    pub fn points(&self) -> &[Vector3] {
+        &self.points
+    }
+
+    /// The total length of the path.
+    pub fn length(&self) -> Float {
+        self.distances.last().copied().unwrap_or(0.0)
+    }
+
+    /// Check if the path has at least one segment with a positive length.
+    pub fn is_valid(&self) -> bool {
+        0.0 < self.length() && self.points.iter().all(|point| point.is_finite())
+    }
+
+    fn segment_at(&self, distance: Float) -> usize {
+        let next = self.distances.partition_point(|d| *d <= distance);
+        next.clamp(1, self.points.len() - 1) - 1
+    }
+
+    /// The point on the path at the given distance from its start.
+    ///
+    /// The distance is clamped to the path.
+    pub fn point_at(&self, distance: Float) -> Vector3 {
+        if self.points.len() < 2 {
+            return self.points.first().copied().unwrap_or(Vector3::ZERO);
+        }
+        let segment = self.segment_at(distance);
+        let segment_length = self.distances[segment + 1] - self.distances[segment];
+        if segment_length <= 0.0 {
+            return self.points[segment];
+        }
+        let factor = ((distance - self.distances[segment]) / segment_length).clamp(0.0, 1.0);
+        self.points[segment].lerp(self.points[segment + 1], factor)
+    }
+
+    /// The direction of the path (toward its end) at the given distance from its start.
+    pub fn tangent_at(&self, distance: Float) -> Option<Vector3> {
+        if self.points.len() < 2 {
+            return None;
+        }
+        let segment = self.segment_at(distance);
+        (self.points[segment + 1] - self.points[segment]).try_normalize()
+    }
+
+    /// Find the point on the path closest to `point`.
+    ///
+    /// Returns the distance of that point from the start of the path, and its distance from
+    /// `point`.
+    pub fn closest(&self, point: Vector3) -> Option<(Float, Float)> {
+        self.points
+            .windows(2)
+            .zip(self.distances.iter())
+            .map(|(segment, start_distance)| {
+                let direction = segment[1] - segment[0];
+                let length_squared = direction.length_squared();
+                let factor = if 0.0 < length_squared {
+                    ((point - segment[0]).dot(direction) / length_squared).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let closest = segment[0] + factor * direction;
+                (
+                    start_distance + factor * length_squared.sqrt(),
+                    closest.distance(point),
+                )
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+}
+
+/// An [action](TnuaAction) that locks the character to a rail - for grinding rails, sliding on
+/// zip-lines, etc.
+///
+/// The character is moved along the [`path`](Self::path), ignoring the ground and the gravity
+/// (except for the part of the gravity along the rail - see
+/// [`gravity_factor`](Self::gravity_factor)). It starts with the part of its velocity that was
+/// along the rail, and when it leaves the rail (because the action is no longer fed, because it
+/// reached an end of the rail, or because it [jumped off](Self::jump_off)) it keeps the velocity
+/// it had along the rail.
+///
+/// Tnua does not detect rails by itself. The control system should detect them and feed the action
+/// with their path.
+#[derive(Clone)]
+pub struct TnuaBuiltinRail {
+    /// The rail to move along.
+    pub path: TnuaRailPath,
+
+    /// Where the character's center should be relative to the rail. For example, for a zip-line
+    /// the character should hang below the rail.
+    pub offset: Vector3,
+
+    /// The speed, along the rail, that the character will try to reach. Positive values move
+    /// toward the end of the path, and negative values toward its start.
+    pub desired_speed: Float,
+
+    /// The acceleration used for reaching [`desired_speed`](Self::desired_speed).
+    ///
+    /// Set to zero to ignore `desired_speed` - e.g. for a zip-line that is only driven by gravity.
+    pub acceleration: Float,
+
+    /// How much of the gravity along the rail affects the speed. `1.0` means the character speeds
+    /// up going down a slanted rail as if it was frictionless, and `0.0` means the slope of the
+    /// rail has no effect.
+    pub gravity_factor: Float,
+
+    /// The action will not start if the character is further than this from the rail.
+    pub max_attach_distance: Float,
+
+    /// Set to `true` to make the character jump off the rail.
+    ///
+    /// The character keeps its velocity along the rail, and gets an extra upward boost of
+    /// [`jump_off_speed`](Self::jump_off_speed).
+    pub jump_off: bool,
+
+    /// The upward speed added to the character when it [jumps off](Self::jump_off) the rail.
+    pub jump_off_speed: Float,
+}
+
+impl Default for TnuaBuiltinRail {
+    fn default() -> Self {
+        Self {
+            path: Default::default(),
+            offset: Vector3::ZERO,
+            desired_speed: 0.0,
+            acceleration: 0.0,
+            gravity_factor: 1.0,
+            max_attach_distance: 0.5,
+            jump_off: false,
+            jump_off_speed: 5.0,
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinRail {
+    const NAME: &'static str = "TnuaBuiltinRail";
+    type State = TnuaBuiltinRailState;
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    fn initiation_decision(
+        &self,
+        ctx: TnuaActionContext,
+        _being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        if !self.path.is_valid() || self.jump_off {
+            return TnuaActionInitiationDirective::Reject;
+        }
+        match self.path.closest(ctx.tracker.translation - self.offset) {
+            Some((_, distance_from_rail)) if distance_from_rail <= self.max_attach_distance => {
+                TnuaActionInitiationDirective::Allow
+            }
+            _ => TnuaActionInitiationDirective::Reject,
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        if !self.path.is_valid() {
+            return TnuaActionLifecycleDirective::Finished;
+        }
+
+        if let TnuaBuiltinRailState::NoRail = state {
+            let Some((distance, _)) = self.path.closest(ctx.tracker.translation - self.offset)
+            else {
+                return TnuaActionLifecycleDirective::Finished;
+            };
+            let tangent = self.path.tangent_at(distance).unwrap_or(Vector3::ZERO);
+            *state = TnuaBuiltinRailState::Riding {
+                distance,
+                speed: ctx.tracker.velocity.dot(tangent),
+            };
+        }
+        let TnuaBuiltinRailState::Riding { distance, speed } = state else {
+            // Already left the rail.
+            return TnuaActionLifecycleDirective::Finished;
+        };
+
+        let tangent = self.path.tangent_at(*distance).unwrap_or(Vector3::ZERO);
+
+        let left_rail = !lifecycle_status.is_active()
+            || (*distance <= 0.0 && *speed < 0.0)
+            || (self.path.length() <= *distance && 0.0 < *speed);
+        if self.jump_off || left_rail {
+            // The rail has been cancelling the gravity and the velocity that was not along it, so
+            // setting the velocity to the speed along the rail is all that's needed for keeping
+            // it.
+            motor.lin = Default::default();
+            motor.lin.boost = *speed * tangent - ctx.tracker.velocity;
+            if self.jump_off {
+                motor.lin.boost += self.jump_off_speed * ctx.up_direction.adjust_precision();
+            }
+            *state = TnuaBuiltinRailState::LeftRail;
+            return TnuaActionLifecycleDirective::Finished;
+        }
+
+        if 0.0 < self.acceleration {
+            let max_change = self.acceleration * ctx.frame_duration;
+            *speed += (self.desired_speed - *speed).clamp(-max_change, max_change);
+        }
+        *speed += self.gravity_factor * ctx.tracker.gravity.dot(tangent) * ctx.frame_duration;
+        *distance = (*distance + *speed * ctx.frame_duration).clamp(0.0, self.path.length());
+
+        // The velocity that would bring the character to where it should be in the next frame.
+        let next_position = self.path.point_at(*distance) + self.offset;
+        let desired_velocity = (next_position - ctx.tracker.translation) / ctx.frame_duration;
+        motor.lin = Default::default();
+        motor.lin.acceleration = -ctx.tracker.gravity;
+        motor.lin.boost = desired_velocity - ctx.tracker.velocity;
+
+        TnuaActionLifecycleDirective::StillActive
+    }
+}
+
+#[derive(Default, Debug)]
+pub enum TnuaBuiltinRailState {
+    #[default]
+    NoRail,
+    /// Moving along the rail.
+    Riding {
+        /// The distance of the character from the start of the rail's path.
+        distance: Float,
+        /// The speed of the character along the rail. Positive when moving toward the end of the
+        /// path.
+        speed: Float,
+    },
+    /// The character has left the rail, and the action is about to end.
+    LeftRail,
+}
@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+use crate::math::{AdjustPrecision, Float, Vector3};
+use bevy::prelude::*;
+
+use crate::{TnuaBasis, TnuaBasisContext, TnuaGroundDetails, TnuaMotor, TnuaVelChange};
+
+/// A [basis](TnuaBasis) for spherical characters that move by rolling.
+///
+/// Unlike [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk), this basis does not float the
+/// character and does not push it horizontally while it is on the ground. Instead, it spins the
+/// ball toward the angular velocity that would make it roll at the
+/// [`desired_velocity`](Self::desired_velocity), and lets the friction between the ball and the
+/// ground do the rest. This means that the rigid body must be allowed to rotate (unlike with the
+/// walk basis) and that its collider must be a ball with the given [`radius`](Self::radius).
+///
+/// The proximity sensor is still used for deciding if the character is on the ground, so actions
+/// like [`TnuaBuiltinJump`](crate::builtins::TnuaBuiltinJump) work with this basis.
+#[derive(Clone)]
+pub struct TnuaBuiltinRoll {
+    /// The direction (in the world space) and speed to roll in.
+    pub desired_velocity: Vector3,
+
+    /// The radius of the ball.
+    pub radius: Float,
+
+    /// Extra distance, beyond the [`radius`](Self::radius), at which the ball is still considered
+    /// to be on the ground.
+    pub ground_tolerance: Float,
+
+    /// The maximum angular acceleration, in radians per second squared, for spinning the ball.
+    pub angular_acceleration: Float,
+
+    /// The acceleration for horizontal movement while in the air.
+    ///
+    /// Set to 0.0 to completely disable air control.
+    pub air_acceleration: Float,
+
+    /// The time, in seconds, the character can still jump after losing their footing.
+    pub coyote_time: Float,
+}
+
+impl Default for TnuaBuiltinRoll {
+    fn default() -> Self {
+        Self {
+            desired_velocity: Vector3::ZERO,
+            radius: 0.5,
+            ground_tolerance: 0.1,
+            angular_acceleration: 100.0,
+            air_acceleration: 5.0,
+            coyote_time: 0.15,
+        }
+    }
+}
+
+impl TnuaBasis for TnuaBuiltinRoll {
+    const NAME: &'static str = "TnuaBuiltinRoll";
+    type State = TnuaBuiltinRollState;
+
+    fn apply(&self, state: &mut Self::State, ctx: TnuaBasisContext, motor: &mut TnuaMotor) {
+        if let Some(timer) = &mut state.airborne_timer {
+            #[allow(clippy::unnecessary_cast)]
+            timer.tick(Duration::from_secs_f64(ctx.frame_duration as f64));
+        }
+        let up = ctx.up_direction.adjust_precision();
+
+        let sensor_output = ctx
+            .proximity_sensor
+            .output
+            .as_ref()
+            .filter(|sensor_output| {
+                sensor_output.proximity.adjust_precision() <= self.radius + self.ground_tolerance
+                    && ctx.is_walkable(sensor_output.entity, sensor_output.normal)
+            });
+
+        if let Some(sensor_output) = sensor_output {
+            state.airborne_timer = None;
+            state.effective_velocity = ctx.tracker.velocity - sensor_output.entity_linvel;
+            // Rolling along a slope makes the ball go up or down with it.
+            state.vertical_velocity = state.effective_velocity.dot(up);
+            state.standing_offset =
+                (sensor_output.proximity.adjust_precision() - self.radius - self.ground_tolerance)
+                    .max(0.0)
+                    * up;
+            state.ground = Some(TnuaGroundDetails {
+                entity: sensor_output.entity,
+                normal: sensor_output.normal,
+                contact_point: ctx.tracker.translation
+                    + ctx
+                        .tracker
+                        .rotation
+                        .mul_vec3(ctx.proximity_sensor.cast_origin)
+                    + sensor_output.proximity
+                        * ctx.proximity_sensor.cast_direction.adjust_precision(),
+                slope_angle: sensor_output
+                    .normal
+                    .angle_between(*ctx.up_direction)
+                    .adjust_precision(),
+            });
+        } else {
+            #[allow(clippy::unnecessary_cast)]
+            if state.airborne_timer.is_none() {
+                state.airborne_timer = Some(Timer::from_seconds(
+                    self.coyote_time as f32,
+                    TimerMode::Once,
+                ));
+            }
+            state.effective_velocity = ctx.tracker.velocity;
+            state.vertical_velocity = 0.0;
+            state.ground = None;
+        }
+
+        let desired_velocity = self.desired_velocity.reject_from(up);
+
+        // A ball rolling without slipping at velocity `v` spins at `up × v / radius`.
+        let desired_angvel = if 0.0 < self.radius {
+            up.cross(desired_velocity) / self.radius
+        } else {
+            Vector3::ZERO
+        };
+        // Spinning around the up axis does not move the ball, so it is left alone.
+        let angvel_change = (desired_angvel - ctx.tracker.angvel)
+            .reject_from(up)
+            .clamp_length_max(self.angular_acceleration * ctx.frame_duration);
+        motor.ang = if 0.0 < ctx.frame_duration {
+            TnuaVelChange::acceleration(angvel_change / ctx.frame_duration)
+        } else {
+            TnuaVelChange::ZERO
+        };
+
+        motor.lin = if state.ground.is_none() && 0.0 < ctx.frame_duration {
+            let velocity_change = (desired_velocity - state.effective_velocity.reject_from(up))
+                .clamp_length_max(self.air_acceleration * ctx.frame_duration);
+            TnuaVelChange::acceleration(velocity_change / ctx.frame_duration)
+        } else {
+            TnuaVelChange::ZERO
+        };
+    }
+
+    fn proximity_sensor_cast_range(&self, _state: &Self::State) -> Float {
+        self.radius + self.ground_tolerance
+    }
+
+    fn displacement(&self, state: &Self::State) -> Option<Vector3> {
+        match state.airborne_timer {
+            None => Some(state.standing_offset),
+            Some(_) => None,
+        }
+    }
+
+    fn effective_velocity(&self, state: &Self::State) -> Vector3 {
+        state.effective_velocity
+    }
+
+    fn vertical_velocity(&self, state: &Self::State) -> Float {
+        state.vertical_velocity
+    }
+
+    fn neutralize(&mut self) {
+        self.desired_velocity = Vector3::ZERO;
+    }
+
+    fn is_airborne(&self, state: &Self::State) -> bool {
+        state
+            .airborne_timer
+            .as_ref()
+            .is_some_and(|timer| timer.finished())
+    }
+
+    fn ground_details(&self, state: &Self::State) -> Option<TnuaGroundDetails> {
+        state.ground
+    }
+
+    fn violate_coyote_time(&self, state: &mut Self::State) {
+        if let Some(timer) = &mut state.airborne_timer {
+            timer.set_duration(Duration::ZERO);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TnuaBuiltinRollState {
+    airborne_timer: Option<Timer>,
+    /// How far the ball is above the ground, beyond the
+    /// [`ground_tolerance`](TnuaBuiltinRoll::ground_tolerance).
+    pub standing_offset: Vector3,
+    effective_velocity: Vector3,
+    vertical_velocity: Float,
+    ground: Option<TnuaGroundDetails>,
+}
+
+impl TnuaBuiltinRollState {
+    /// The entity the ball is rolling on (if any).
+    pub fn standing_on_entity(&self) -> Option<Entity> {
+        Some(self.ground?.entity)
+    }
+}
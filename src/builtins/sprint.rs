@@ -0,0 +1,176 @@
+use crate::math::{AdjustPrecision, Float, Vector3};
+use bevy::prelude::*;
+
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor,
+};
+
+use super::{TnuaBasisLocomotion, TnuaBuiltinWalk};
+
+/// An [action](TnuaAction) for sprinting. Only works when [`TnuaBuiltinWalk`] is the
+/// [basis](crate::TnuaBasis).
+///
+/// While the action is fed, the [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) and the
+/// acceleration of the walk basis are multiplied by the
+/// [`speed_multiplier`](Self::speed_multiplier) and the
+/// [`acceleration_multiplier`](Self::acceleration_multiplier). The multipliers are ramped up when
+/// the sprint starts and ramped down when it stops, so the transitions are smooth. The control
+/// system should keep feeding the walk basis with the regular (non-sprinting) desired velocity.
+///
+/// Like any other action, a sprint is cancelled by other actions (e.g. a jump). When it starts
+/// again, its ramp resumes from the speed the character already has - so a character that jumps
+/// while sprinting keeps sprinting after landing.
+///
+/// To limit the sprint with stamina, use the state's
+/// [`is_sprinting`](TnuaBuiltinSprintState::is_sprinting) for draining it and set
+/// [`exhausted`](Self::exhausted) when it runs out.
+#[derive(Clone)]
+pub struct TnuaBuiltinSprint {
+    /// The multiplier for the walk basis' desired velocity at the full sprint.
+    pub speed_multiplier: Float,
+
+    /// The multiplier for the walk basis' acceleration at the full sprint.
+    pub acceleration_multiplier: Float,
+
+    /// The time, in seconds, it takes to ramp up to the full sprint.
+    pub ramp_up_time: Float,
+
+    /// The time, in seconds, it takes to ramp down from the full sprint after the action is no
+    /// longer fed.
+    pub ramp_down_time: Float,
+
+    /// Set to `true` to stop the sprint (with a ramp down) even though the action is still fed -
+    /// e.g. when the character runs out of stamina.
+    pub exhausted: bool,
+}
+
+impl Default for TnuaBuiltinSprint {
+    fn default() -> Self {
+        Self {
+            speed_multiplier: 1.6,
+            acceleration_multiplier: 1.5,
+            ramp_up_time: 0.3,
+            ramp_down_time: 0.2,
+            exhausted: false,
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinSprint {
+    const NAME: &'static str = "TnuaBuiltinSprint";
+    type State = TnuaBuiltinSprintState;
+    const VIOLATES_COYOTE_TIME: bool = false;
+
+    fn initiation_decision(
+        &self,
+        ctx: TnuaActionContext,
+        _being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        if self.exhausted || ctx.concrete_basis::<TnuaBuiltinWalk>().is_none() {
+            TnuaActionInitiationDirective::Reject
+        } else {
+            TnuaActionInitiationDirective::Allow
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        let Some((walk_basis, walk_state)) = ctx.concrete_basis::<TnuaBuiltinWalk>() else {
+            error!("Cannot sprint - basis is not TnuaBuiltinWalk");
+            return TnuaActionLifecycleDirective::Finished;
+        };
+        let up = ctx.up_direction.adjust_precision();
+        let desired_velocity = if walk_basis.input_response.is_some() {
+            walk_state.responded_desired_velocity()
+        } else {
+            walk_basis.directional_desired_velocity()
+        };
+        let current_velocity = ctx.basis.effective_velocity().reject_from(up);
+
+        if lifecycle_status.just_started() {
+            // Resume from the speed the character already has (e.g. when landing from a jump
+            // that cancelled the sprint)
+            let desired_speed = desired_velocity.length();
+            state.ramp = if 0.0 < desired_speed && 1.0 < self.speed_multiplier {
+                ((current_velocity.length() / desired_speed - 1.0) / (self.speed_multiplier - 1.0))
+                    .clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+        }
+
+        let ramping_up = lifecycle_status.is_active() && !self.exhausted;
+        if ramping_up {
+            state.ramp = ramp_toward(state.ramp, 1.0, self.ramp_up_time, ctx.frame_duration);
+        } else {
+            state.ramp = ramp_toward(state.ramp, 0.0, self.ramp_down_time, ctx.frame_duration);
+        }
+        state.sprinting = ramping_up;
+
+        let speed_multiplier = 1.0 + (self.speed_multiplier - 1.0) * state.ramp;
+        let acceleration_multiplier = 1.0 + (self.acceleration_multiplier - 1.0) * state.ramp;
+
+        // The walk basis already moved the character toward the regular desired velocity, so only
+        // the difference between the sprinting velocity change and the regular one is added.
+        let locomotion = walk_basis.locomotion(ctx.basis.is_airborne());
+        let sprint_locomotion = TnuaBasisLocomotion {
+            acceleration: acceleration_multiplier * locomotion.acceleration,
+            ..locomotion.clone()
+        };
+        let velocity_change = |locomotion: &TnuaBasisLocomotion, desired_velocity: Vector3| {
+            let desired_velocity = locomotion.effective_desired_velocity(
+                desired_velocity,
+                current_velocity,
+                ctx.frame_duration,
+            );
+            locomotion.velocity_change(desired_velocity, current_velocity, ctx.frame_duration)
+        };
+        let extra_boost = velocity_change(&sprint_locomotion, speed_multiplier * desired_velocity)
+            - velocity_change(&locomotion, desired_velocity);
+        if 0.0 < ctx.frame_duration {
+            motor.lin.acceleration += extra_boost.reject_from(up) / ctx.frame_duration;
+        }
+
+        if matches!(lifecycle_status, TnuaActionLifecycleStatus::CancelledInto) {
+            // Do not delay the action that cancels the sprint.
+            TnuaActionLifecycleDirective::Finished
+        } else if ramping_up || 0.0 < state.ramp {
+            TnuaActionLifecycleDirective::StillActive
+        } else {
+            TnuaActionLifecycleDirective::Finished
+        }
+    }
+}
+
+fn ramp_toward(ramp: Float, target: Float, ramp_time: Float, frame_duration: Float) -> Float {
+    if ramp_time <= 0.0 {
+        return target;
+    }
+    let max_change = frame_duration / ramp_time;
+    ramp + (target - ramp).clamp(-max_change, max_change)
+}
+
+#[derive(Default, Debug)]
+pub struct TnuaBuiltinSprintState {
+    ramp: Float,
+    sprinting: bool,
+}
+
+impl TnuaBuiltinSprintState {
+    /// How far, from 0.0 to 1.0, the sprint is ramped up. Can be used for blending the walk and
+    /// the run animations.
+    pub fn ramp(&self) -> Float {
+        self.ramp
+    }
+
+    /// Check if the character is sprinting (rather than ramping down after the sprint stopped).
+    pub fn is_sprinting(&self) -> bool {
+        self.sprinting
+    }
+}
@@ -0,0 +1,189 @@
+use crate::math::{AdjustPrecision, Float, Vector3};
+use bevy::prelude::*;
+
+use crate::util::rotation_arc_around_axis;
+use crate::{
+    TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaMotor,
+};
+
+/// An [action](TnuaAction) for vaulting over low obstacles.
+///
+/// The action moves the character over the obstacle along an arc, ignoring the ground (and the
+/// gravity) until it reaches the other side, where the basis takes over again.
+///
+/// Tnua does not detect the obstacle by itself. The control system should detect it (e.g. with a
+/// ray cast, or a [custom sensor](crate::custom_sensors::TnuaCustomSensor)) and feed the action
+/// with the [`obstacle_height`](Self::obstacle_height) and the
+/// [`displacement`](Self::displacement) needed to pass it.
+#[derive(Clone)]
+pub struct TnuaBuiltinVault {
+    /// The horizontal direction and distance from the starting point to the landing point on the
+    /// other side of the obstacle.
+    ///
+    /// This input parameter is cached when the action starts.
+    pub displacement: Vector3,
+
+    /// The height of the top of the obstacle above the ground the character stands on.
+    ///
+    /// This input parameter is cached when the action starts.
+    pub obstacle_height: Float,
+
+    /// Point the negative Z axis of the characetr model in that direction during the vault.
+    ///
+    /// This input parameter is cached when the action starts.
+    pub desired_forward: Option<Dir3>,
+
+    /// The action will not start if the [`obstacle_height`](Self::obstacle_height) is lower than
+    /// this.
+    pub min_obstacle_height: Float,
+
+    /// The action will not start if the [`obstacle_height`](Self::obstacle_height) is higher than
+    /// this.
+    pub max_obstacle_height: Float,
+
+    /// Extra height, above the [`obstacle_height`](Self::obstacle_height), that the character
+    /// rises at the top of the arc.
+    pub clearance: Float,
+
+    /// The duration, in seconds, of moving over the obstacle.
+    pub duration: Float,
+
+    /// A duration, in seconds, where a player can press a vault button before a vault becomes
+    /// possible (typically when a character is still in the air and about the land) and the
+    /// vault action would still get registered and be executed once the vault is possible.
+    pub input_buffer_time: Float,
+}
+
+impl Default for TnuaBuiltinVault {
+    fn default() -> Self {
+        Self {
+            displacement: Vector3::ZERO,
+            obstacle_height: 0.0,
+            desired_forward: None,
+            min_obstacle_height: 0.3,
+            max_obstacle_height: 1.2,
+            clearance: 0.2,
+            duration: 0.4,
+            input_buffer_time: 0.2,
+        }
+    }
+}
+
+impl TnuaAction for TnuaBuiltinVault {
+    const NAME: &'static str = "TnuaBuiltinVault";
+    type State = TnuaBuiltinVaultState;
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    fn initiation_decision(
+        &self,
+        ctx: TnuaActionContext,
+        being_fed_for: &bevy::time::Stopwatch,
+    ) -> TnuaActionInitiationDirective {
+        if !self.displacement.is_finite()
+            || self.displacement == Vector3::ZERO
+            || self.duration <= 0.0
+            || !(self.min_obstacle_height..=self.max_obstacle_height)
+                .contains(&self.obstacle_height)
+        {
+            TnuaActionInitiationDirective::Reject
+        } else if !ctx.basis.is_airborne() {
+            TnuaActionInitiationDirective::Allow
+        } else if (being_fed_for.elapsed().as_secs_f64() as Float) < self.input_buffer_time {
+            TnuaActionInitiationDirective::Delay
+        } else {
+            TnuaActionInitiationDirective::Reject
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: TnuaActionContext,
+        _lifecycle_status: TnuaActionLifecycleStatus,
+        motor: &mut TnuaMotor,
+    ) -> TnuaActionLifecycleDirective {
+        let up = ctx.up_direction.adjust_precision();
+
+        if let TnuaBuiltinVaultState::NoVault = state {
+            *state = TnuaBuiltinVaultState::Vaulting {
+                start: ctx.tracker.translation,
+                displacement: self.displacement.reject_from(up),
+                arc_height: self.obstacle_height + self.clearance,
+                desired_forward: self.desired_forward,
+                elapsed: 0.0,
+            };
+        }
+        let TnuaBuiltinVaultState::Vaulting {
+            start,
+            displacement,
+            arc_height,
+            desired_forward,
+            elapsed,
+        } = state
+        else {
+            unreachable!()
+        };
+
+        if self.duration <= *elapsed {
+            return TnuaActionLifecycleDirective::Finished;
+        }
+        *elapsed += ctx.frame_duration;
+        let progress = (*elapsed / self.duration).min(1.0);
+        let next_position = *start
+            + progress * *displacement
+            + 4.0 * *arc_height * progress * (1.0 - progress) * up;
+
+        // The velocity that would bring the character to where it should be in the next frame.
+        let desired_velocity = (next_position - ctx.tracker.translation) / ctx.frame_duration;
+        motor.lin = Default::default();
+        motor.lin.acceleration = -ctx.tracker.gravity;
+        motor.lin.boost = desired_velocity - ctx.tracker.velocity;
+
+        if let Some(desired_forward) = desired_forward {
+            let current_forward = ctx.tracker.rotation.mul_vec3(Vector3::NEG_Z);
+            let rotation_along_up_axis = rotation_arc_around_axis(
+                ctx.up_direction,
+                current_forward,
+                desired_forward.adjust_precision(),
+            )
+            .unwrap_or(0.0);
+            let desired_angvel = rotation_along_up_axis / ctx.frame_duration;
+            let existing_angvel = ctx.tracker.angvel.dot(up);
+            motor.ang.cancel_on_axis(up);
+            motor.ang.boost += (desired_angvel - existing_angvel) * up;
+        }
+
+        TnuaActionLifecycleDirective::StillActive
+    }
+}
+
+#[derive(Default, Debug)]
+pub enum TnuaBuiltinVaultState {
+    #[default]
+    NoVault,
+    /// Moving over the obstacle.
+    Vaulting {
+        /// Where the character was when the vault started.
+        start: Vector3,
+        /// The horizontal displacement from the start to the landing point.
+        displacement: Vector3,
+        /// How high above the start the character rises at the top of the arc.
+        arc_height: Float,
+        desired_forward: Option<Dir3>,
+        /// The time, in seconds, since the vault started.
+        elapsed: Float,
+    },
+}
+
+impl TnuaBuiltinVaultState {
+    /// How far, from 0.0 to 1.0, the character went over the obstacle.
+    pub fn progress(&self, vault: &TnuaBuiltinVault) -> Float {
+        match self {
+            TnuaBuiltinVaultState::NoVault => 0.0,
+            TnuaBuiltinVaultState::Vaulting { elapsed, .. } => {
+                (*elapsed / vault.duration).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::math::{float_consts, AdjustPrecision, Float};
+use bevy::prelude::*;
+
+use crate::{TnuaBasisContext, TnuaVelChange};
+
+/// The float spring of [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk), for reuse in custom
+/// [bases](crate::TnuaBasis).
+///
+/// This part does not keep any memory of its own - the basis that uses it is responsible for
+/// detecting the ground (usually with the proximity sensor) and for tracking the velocities.
+#[derive(Clone, Debug)]
+pub struct TnuaBasisFloat {
+    /// The height at which the center of the character should float above the ground.
+    pub float_height: Float,
+
+    /// The force that pushes the character to the float height.
+    ///
+    /// The actual force applied is in direct linear relationship to the displacement from the
+    /// [`float_height`](Self::float_height).
+    pub spring_strengh: Float,
+
+    /// A force that slows down the characters vertical spring motion.
+    pub spring_dampening: Float,
+
+    /// How [`spring_strengh`](Self::spring_strengh) and
+    /// [`spring_dampening`](Self::spring_dampening) are used.
+    pub float_spring: TnuaFloatSpring,
+}
+
+impl TnuaBasisFloat {
+    /// Calculate the vertical spring force needed when the character's vertical distance from
+    /// the float height equals `spring_offset` (positive when the character is too low).
+    ///
+    /// `relative_velocity` is the vertical velocity of the character relative to the ground, minus
+    /// the vertical velocity the character is supposed to have (e.g. when climbing a slope).
+    pub fn spring_force(
+        &self,
+        ctx: &TnuaBasisContext,
+        spring_offset: Float,
+        relative_velocity: Float,
+    ) -> TnuaVelChange {
+        let (spring_strengh, spring_dampening) = self.float_spring.coefficients(
+            self.spring_strengh,
+            self.spring_dampening,
+            ctx.frame_duration,
+        );
+
+        let spring_force: Float = spring_offset * spring_strengh;
+
+        let gravity_compensation = -ctx.tracker.gravity;
+
+        let dampening_boost = relative_velocity * spring_dampening;
+
+        TnuaVelChange {
+            acceleration: ctx.up_direction.adjust_precision() * spring_force + gravity_compensation,
+            boost: ctx.up_direction.adjust_precision() * -dampening_boost,
+        }
+    }
+}
+
+/// The tuning of the spring that keeps a character at its float height.
+#[derive(Clone, Debug, Default)]
+pub enum TnuaFloatSpring {
+    /// Use [`spring_strengh`](TnuaBasisFloat::spring_strengh) and
+    /// [`spring_dampening`](TnuaBasisFloat::spring_dampening) as they are.
+    ///
+    /// A warning will be logged (once) if the combination is unstable at the current frame
+    /// duration.
+    #[default]
+    Manual,
+
+    /// Derive the strength and the dampening of the spring from the desired response frequency (in
+    /// Hz), so that the spring is critically damped - it returns to the float height as fast as
+    /// possible without bouncing.
+    ///
+    /// This ignores [`spring_strengh`](TnuaBasisFloat::spring_strengh) and
+    /// [`spring_dampening`](TnuaBasisFloat::spring_dampening). The coefficients are computed
+    /// every frame from the frame duration, so they remain stable at any tick rate. To keep them
+    /// stable, the frequency is capped at `1 / (2π * frame_duration)`.
+    CriticallyDamped { frequency: Float },
+}
+
+impl TnuaFloatSpring {
+    /// The spring strength and dampening to use in a frame of the given duration.
+    ///
+    /// `spring_strengh` and `spring_dampening` are the ones configured in the [`TnuaBasisFloat`]
+    /// (or in the [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk)).
+    pub fn coefficients(
+        &self,
+        spring_strengh: Float,
+        spring_dampening: Float,
+        frame_duration: Float,
+    ) -> (Float, Float) {
+        match self {
+            TnuaFloatSpring::Manual => {
+                if !Self::is_stable(spring_strengh, spring_dampening, frame_duration) {
+                    warn_once!(
+                        "The float spring of TnuaBuiltinWalk (spring_strengh = {spring_strengh}, \
+                        spring_dampening = {spring_dampening}) is unstable at frame duration \
+                        {frame_duration}. Consider using TnuaFloatSpring::CriticallyDamped."
+                    );
+                }
+                (spring_strengh, spring_dampening)
+            }
+            TnuaFloatSpring::CriticallyDamped { frequency } => {
+                if frame_duration <= 0.0 {
+                    return (0.0, 0.0);
+                }
+                let angular_frequency = (float_consts::TAU * frequency).min(1.0 / frame_duration);
+                (
+                    angular_frequency.powi(2),
+                    1.0 - (-2.0 * angular_frequency * frame_duration).exp(),
+                )
+            }
+        }
+    }
+
+    /// Check if a spring with the given strength and dampening would converge when applied in
+    /// frames of the given duration.
+    pub fn is_stable(
+        spring_strengh: Float,
+        spring_dampening: Float,
+        frame_duration: Float,
+    ) -> bool {
+        (0.0..2.0).contains(&spring_dampening)
+            && spring_strengh * frame_duration.powi(2) < 4.0 - 2.0 * spring_dampening
+    }
+}
@@ -0,0 +1,93 @@
+use crate::math::{Float, Vector3};
+
+/// The horizontal locomotion of [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk), for reuse
+/// in custom [bases](crate::TnuaBasis).
+///
+/// It calculates how the velocity should change to reach the desired velocity, given the
+/// acceleration limits. The velocities are on the plane the character moves on (e.g. for a walking
+/// character - perpendicular to the up direction) and relative to whatever the character moves
+/// relative to (e.g. the platform it stands on). This part does not keep any memory of its own.
+#[derive(Clone, Debug)]
+pub struct TnuaBasisLocomotion {
+    /// The acceleration for starting the motion and for reaching the top speed.
+    ///
+    /// When changing direction the acceleration is greater, up to 2 times `acceleration` when
+    /// doing a 180 turn - unless [`deceleration`](Self::deceleration) is set.
+    pub acceleration: Float,
+
+    /// The acceleration for braking - when the character stops or when the desired velocity is
+    /// opposed to the current velocity.
+    ///
+    /// When set to `None`, braking uses the [`acceleration`](Self::acceleration) (multiplied by
+    /// the direction change factor).
+    pub deceleration: Option<Float>,
+
+    /// The rate, in speed units per second, at which the character loses speed that exceeds the
+    /// desired speed, instead of braking.
+    pub over_speed_decay: Option<Float>,
+
+    /// When set, the character drifts to a stop when the desired velocity is zero, losing its
+    /// velocity exponentially at this rate (per second) instead of braking with the
+    /// [`deceleration`](Self::deceleration).
+    pub friction: Option<Float>,
+}
+
+impl TnuaBasisLocomotion {
+    /// The velocity the character should aim for in the current frame.
+    ///
+    /// This is the `desired_velocity` itself, unless the character moves faster than it and
+    /// [`over_speed_decay`](Self::over_speed_decay) is set.
+    pub fn effective_desired_velocity(
+        &self,
+        desired_velocity: Vector3,
+        current_velocity: Vector3,
+        frame_duration: Float,
+    ) -> Vector3 {
+        let Some(over_speed_decay) = self.over_speed_decay else {
+            return desired_velocity;
+        };
+        let current_speed = current_velocity.length();
+        let desired_speed = desired_velocity.length();
+        if desired_speed < current_speed {
+            let direction = if desired_velocity == Vector3::ZERO {
+                current_velocity / current_speed
+            } else {
+                desired_velocity / desired_speed
+            };
+            direction * (current_speed - frame_duration * over_speed_decay).max(desired_speed)
+        } else {
+            desired_velocity
+        }
+    }
+
+    /// Calculate the velocity change, for the current frame, that brings the character from the
+    /// `current_velocity` toward the `desired_velocity` within the acceleration limits.
+    ///
+    /// `desired_velocity` should be the one returned by
+    /// [`effective_desired_velocity`](Self::effective_desired_velocity).
+    pub fn velocity_change(
+        &self,
+        desired_velocity: Vector3,
+        current_velocity: Vector3,
+        frame_duration: Float,
+    ) -> Vector3 {
+        if let Some(friction) = self.friction.filter(|_| desired_velocity == Vector3::ZERO) {
+            return current_velocity * ((-friction * frame_duration).exp() - 1.0);
+        }
+
+        let desired_boost = desired_velocity - current_velocity;
+
+        let safe_direction_coefficient = desired_velocity
+            .normalize_or_zero()
+            .dot(current_velocity.normalize_or_zero());
+        let direction_change_factor = 1.5 - 0.5 * safe_direction_coefficient;
+
+        let is_braking = desired_velocity == Vector3::ZERO || safe_direction_coefficient < 0.0;
+        let max_acceleration = match self.deceleration {
+            Some(deceleration_limit) if is_braking => deceleration_limit,
+            _ => direction_change_factor * self.acceleration,
+        };
+
+        desired_boost.clamp_length_max(frame_duration * max_acceleration)
+    }
+}
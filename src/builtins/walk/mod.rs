@@ -0,0 +1,1213 @@
+mod float;
+mod locomotion;
+mod orientation;
+
+use std::time::Duration;
+
+use crate::math::{float_consts, AdjustPrecision, AsF32, Float, Vector3};
+use bevy::prelude::*;
+
+use crate::TnuaBasisContext;
+use crate::{
+    TnuaBasis, TnuaColliderDimensions, TnuaConfigWarning, TnuaGroundDetails, TnuaVelChange,
+};
+
+pub use float::{TnuaBasisFloat, TnuaFloatSpring};
+pub use locomotion::TnuaBasisLocomotion;
+pub use orientation::TnuaBasisOrientation;
+
+/// The most common [basis](TnuaBasis) - walk around as a floating capsule.
+///
+/// This basis implements the floating capsule character controller explained in
+/// <https://youtu.be/qdskE8PJy6Q>. It controls both the floating and the movement. Most of its
+/// fields have sane defaults, except:
+///
+/// * [`float_height`](Self::float_height) - this field defaults to 0.0, which means the character
+///   will not float. Set it to be higher than the distance from the center of the entity to the
+///   bottom of the collider.
+/// * [`desired_velocity`](Self::desired_velocity) - while leaving this as as the default
+///   `Vector3::ZERO`, doing so would mean that the character will not move.
+/// * [`desired_forward`](Self::desired_forward) - leaving this is the default `None` will mean
+///   that Tnua will not attempt to fix the character's rotation along the up axis.
+///
+///   This is fine if rotation along the up axis is locked (Rapier and Avian only support locking
+///   cardinal axes, but the up direction is based on the gravity which means it defaults to the Y
+///   axis so it usually works out).
+///
+///   This is also fine for 2D games (or games with 3D graphics and 2D physics) played from side
+///   view where the physics engine cannot rotate the character along the up axis.
+///
+///   But if the physics engine is free to rotate the character's rigid body along the up axis,
+///   leaving `desired_forward` as the default `Vector3::ZERO` may cause the character to spin
+///   uncontrollably when it contacts other colliders. Unless, of course, some other mechanism
+///   prevents that.
+#[derive(Clone)]
+pub struct TnuaBuiltinWalk {
+    /// The direction (in the world space) and speed to accelerate to.
+    ///
+    /// Tnua assumes that this vector is orthogonal to the up dierction.
+    pub desired_velocity: Vector3,
+
+    /// If non-zero, Tnua will rotate the character so that its negative Z will face in that
+    /// direction.
+    ///
+    /// Tnua assumes that this vector is orthogonal to the up direction.
+    pub desired_forward: Option<Dir3>,
+
+    /// The height at which the character will float above ground at rest.
+    ///
+    /// Note that this is the height of the character's center of mass - not the distance from its
+    /// collision mesh.
+    ///
+    /// To set it relative to the bottom of the collider, add
+    /// [`TnuaColliderDimensions`](crate::TnuaColliderDimensions) to the character entity and use
+    /// [`float_height_above_bottom`](crate::TnuaColliderDimensions::float_height_above_bottom).
+    ///
+    /// To make a character crouch, instead of altering this field, prefer to use the
+    /// [`TnuaBuiltinCrouch`](crate::builtins::TnuaBuiltinCrouch) action.
+    pub float_height: Float,
+
+    /// Extra distance above the `float_height` where the spring is still in effect.
+    ///
+    /// When the character is at at most this distance above the
+    /// [`float_height`](Self::float_height), the spring force will kick in and move it to the
+    /// float height - even if that means pushing it down. If the character is above that distance
+    /// above the `float_height`, Tnua will consider it to be in the air.
+    pub cling_distance: Float,
+
+    /// The force that pushes the character to the float height.
+    ///
+    /// The actual force applied is in direct linear relationship to the displacement from the
+    /// `float_height`.
+    pub spring_strengh: Float,
+
+    /// A force that slows down the characters vertical spring motion.
+    ///
+    /// The actual dampening is in direct linear relationship to the vertical velocity it tries to
+    /// dampen.
+    ///
+    /// Note that as this approaches 2.0, the character starts to shake violently and eventually
+    /// get launched upward at great speed.
+    pub spring_dampening: Float,
+
+    /// How the spring that keeps the character at the float height is tuned.
+    ///
+    /// Defaults to [`TnuaFloatSpring::Manual`], which uses
+    /// [`spring_strengh`](Self::spring_strengh) and [`spring_dampening`](Self::spring_dampening)
+    /// as they are.
+    pub float_spring: TnuaFloatSpring,
+
+    /// Makes the float height dip with the horizontal speed and when landing.
+    ///
+    /// When set to `None` (the default), the character always floats at the
+    /// [`float_height`](Self::float_height).
+    pub suspension: Option<TnuaWalkSuspension>,
+
+    /// Shapes the [`desired_velocity`](Self::desired_velocity) fed from analog input (dead zone,
+    /// response curve, walk/run threshold and smoothing) before the basis uses it.
+    ///
+    /// When set to `None` (the default), the desired velocity is used as is.
+    pub input_response: Option<TnuaWalkInputResponse>,
+
+    /// A multiplier for the part of the [`desired_velocity`](Self::desired_velocity) that goes
+    /// in the direction of the [`desired_forward`](Self::desired_forward).
+    ///
+    /// The speed multipliers only take effect when `desired_forward` is set. When all three are
+    /// 1.0 (the default) the desired velocity is used as is.
+    pub forward_speed_multiplier: Float,
+
+    /// A multiplier for the part of the [`desired_velocity`](Self::desired_velocity) that goes
+    /// opposite to the [`desired_forward`](Self::desired_forward).
+    ///
+    /// Use this to make a character that faces one way (e.g. to aim) move slower when it walks
+    /// backward.
+    pub backward_speed_multiplier: Float,
+
+    /// A multiplier for the part of the [`desired_velocity`](Self::desired_velocity) that is
+    /// perpendicular to the [`desired_forward`](Self::desired_forward).
+    pub strafe_speed_multiplier: Float,
+
+    /// The acceleration for horizontal movement.
+    ///
+    /// Note that this is the acceleration for starting the horizontal motion and for reaching the
+    /// top speed. When braking or changing direction the acceleration is greater, up to 2 times
+    /// `acceleration` when doing a 180 turn - unless [`deceleration`](Self::deceleration) is set.
+    pub acceleration: Float,
+
+    /// The acceleration for horizontal movement while in the air.
+    ///
+    /// Set to 0.0 to completely disable air movement.
+    pub air_acceleration: Float,
+
+    /// The acceleration for braking - when the character stops or when the desired velocity is
+    /// opposed to the current velocity.
+    ///
+    /// When set to `None`, braking uses the [`acceleration`](Self::acceleration) (multiplied by
+    /// the direction change factor).
+    pub deceleration: Option<Float>,
+
+    /// The acceleration for braking while in the air.
+    ///
+    /// When set to `None`, braking in the air uses the
+    /// [`air_acceleration`](Self::air_acceleration) (multiplied by the direction change factor).
+    pub air_deceleration: Option<Float>,
+
+    /// The rate, in speed units per second, at which the character loses horizontal speed that
+    /// exceeds the speed of the [`desired_velocity`](Self::desired_velocity).
+    ///
+    /// When the character moves faster than desired (e.g. after a dash, or after being pushed) the
+    /// walk basis normally brakes it using the regular acceleration. When this is set, the excess
+    /// speed is instead bled gradually at the given rate, preserving the character's momentum.
+    /// The speed is measured relative to the entity the character stands on, so moving platforms
+    /// are not affected.
+    ///
+    /// When set to `None`, excess speed is treated like any other difference from the desired
+    /// velocity.
+    pub over_speed_decay: Option<Float>,
+
+    /// Let the character drift to a stop on the ground when there is no input, instead of braking.
+    ///
+    /// When the [`desired_velocity`](Self::desired_velocity) is zero, the character loses its
+    /// velocity exponentially at this rate (per second) - e.g. with 2.0 it loses about 86% of its
+    /// velocity every second - which gives the classic top-down "accelerate and drift to a stop"
+    /// feel. It does not affect braking when the desired velocity is opposed to the current
+    /// velocity, and it does not apply in the air.
+    ///
+    /// When set to `None`, the character brakes with the [`deceleration`](Self::deceleration).
+    pub friction: Option<Float>,
+
+    /// A multiplier for the [`acceleration`](Self::acceleration), the
+    /// [`deceleration`](Self::deceleration) and the [`friction`](Self::friction) on the ground, for
+    /// the surface the character stands on.
+    ///
+    /// Lower values make the surface more slippery (e.g. 0.1 for ice) - the character takes longer
+    /// to start, to stop and to turn, and keeps sliding when pushed. Tnua does not know the
+    /// materials of the surfaces - the control system should set this according to the entity
+    /// the character stands on (e.g. the entity of the proximity sensor's output).
+    pub surface_grip: Float,
+
+    /// Keep the character anchored to a point on the entity it stands on while it stands still.
+    ///
+    /// The walk basis carries the character along with the entity it stands on by matching its
+    /// velocity, which lets the character drift when that entity changes its velocity (e.g. a
+    /// moving platform that reverses its direction). When this is set, the point the character
+    /// stopped at is tracked as it moves with the entity, and the character is pulled back toward
+    /// it. The value is the rate (per second) at which the drift is corrected - e.g. with 10.0 a
+    /// drift of 0.1 is corrected by moving at 1.0 toward the anchor.
+    ///
+    /// The anchor is dropped when the [`desired_velocity`](Self::desired_velocity) is not zero,
+    /// when the character is airborne or slipping, and when it steps onto a different entity.
+    pub platform_anchoring: Option<Float>,
+
+    /// Compensate for the rotation of the entity the character stands on.
+    ///
+    /// The velocity of a point on a rotating entity keeps turning toward the rotation axis, so a
+    /// character that only matches the velocity of the point it stands on slowly drifts outward
+    /// (e.g. on a turntable). When this is set, the centripetal acceleration of that point is
+    /// added to the character as well.
+    ///
+    /// Disabled by default.
+    ///
+    /// The compensation assumes the entity rotates around its own center. For entities that both
+    /// move and rotate it can add a small drift of its own, so it may be better to disable it for
+    /// them.
+    pub rotating_platform_compensation: bool,
+
+    /// The time, in seconds, the character can still jump after losing their footing.
+    pub coyote_time: Float,
+
+    /// The time, in seconds, during which a missed proximity sensor hit does not make the
+    /// character airborne.
+    ///
+    /// On uneven terrain the sensor may miss the ground for a single frame, which makes the
+    /// character airborne (and restarts the [coyote time](Self::coyote_time)) for no reason. With
+    /// this set, the character stays on the ground until the sensor misses for longer than this.
+    /// During that window the float spring is paused but gravity is still cancelled, so the
+    /// character holds its height. Unlike the coyote time, which only keeps the jumps allowed,
+    /// this keeps the character from becoming airborne at all - so it should be kept very short.
+    ///
+    /// This only applies while the character is not moving upward, so it does not delay jumps.
+    pub ground_persistence: Float,
+
+    /// Extra gravity for free fall (fall that's not initiated by a jump or some other action that
+    /// provides its own fall gravity)
+    ///
+    /// **NOTE**: This force will be added to the normal gravity.
+    ///
+    /// **NOTE**: If the parameter set to this option is too low, the character may be able to run
+    /// up a slope and "jump" potentially even higher than a regular jump, even without pressing
+    /// the jump button.
+    pub free_fall_extra_gravity: Float,
+
+    /// Multiply the gravity by this while the character is airborne and moving down.
+    ///
+    /// Unlike [`free_fall_extra_gravity`](Self::free_fall_extra_gravity), this also applies while
+    /// falling from a jump - [`TnuaBuiltinJump`](crate::builtins::TnuaBuiltinJump) uses it in its
+    /// fall section. Since it only applies while moving down, it does not affect the height of
+    /// the jumps.
+    ///
+    /// **NOTE**: This is applied in addition to the
+    /// [`free_fall_extra_gravity`](Self::free_fall_extra_gravity).
+    pub fall_gravity_multiplier: Float,
+
+    /// The maximum angular velocity used for keeping the character standing upright.
+    ///
+    /// NOTE: The character's rotation can also be locked to prevent it from being tilted, in which
+    /// case this paramter is redundant and can be set to 0.0.
+    pub tilt_offset_angvel: Float,
+
+    /// The maximum angular acceleration used for reaching `tilt_offset_angvel`.
+    ///
+    /// NOTE: The character's rotation can also be locked to prevent it from being tilted, in which
+    /// case this paramter is redundant and can be set to 0.0.
+    pub tilt_offset_angacl: Float,
+
+    /// The maximum angular velocity used for turning the character when the direction changes.
+    pub turning_angvel: Float,
+
+    /// The maximum angular velocity used for turning the character while in the air.
+    ///
+    /// When set to `None`, [`turning_angvel`](Self::turning_angvel) is used in the air as well.
+    pub air_turning_angvel: Option<Float>,
+
+    /// When the angle, in radians, between the character's forward and the
+    /// [`desired_forward`](Self::desired_forward) is at most this value, the character will snap
+    /// to the desired direction immediately instead of turning gradually.
+    ///
+    /// Set to 0.0 to disable snapping.
+    pub turning_snap_angle: Float,
+
+    /// A multiplier for the turning angular velocity when the character needs to turn around.
+    ///
+    /// The multiplier is interpolated linearly based on the turning angle - from 1.0 when no turn
+    /// is needed to this value when the character needs to make a 180 turn.
+    pub turning_reverse_multiplier: Float,
+
+    /// The maximum slope, in radians, that the character can stand on without slipping.
+    ///
+    /// Surfaces can also be made unwalkable regardless of their slope using the
+    /// [`TnuaWalkableFilter`](crate::controller::TnuaWalkableFilter) component.
+    pub max_slope: Float,
+
+    /// The slope, in radians, above which the character starts losing its uphill speed.
+    ///
+    /// Between this slope and the [`max_slope`](Self::max_slope), the uphill part of the
+    /// [`desired_velocity`](Self::desired_velocity) is scaled down according to the steepness (see
+    /// [`soft_slope_exponent`](Self::soft_slope_exponent)), reaching zero at the `max_slope` - so
+    /// the character slows down on steep climbs before it starts slipping. Moving sideways or
+    /// downhill is not affected.
+    ///
+    /// When set to `None`, the character climbs at full speed all the way to the `max_slope`.
+    pub soft_max_slope: Option<Float>,
+
+    /// The exponent of the curve for scaling the uphill speed between the
+    /// [`soft_max_slope`](Self::soft_max_slope) and the [`max_slope`](Self::max_slope).
+    ///
+    /// With `1.0` the uphill speed drops linearly with the slope. Higher values keep the speed
+    /// higher for longer and drop it closer to the `max_slope`.
+    pub soft_slope_exponent: Float,
+
+    /// Let the gravity speed the character up when it runs downhill, up to this ratio of the
+    /// speed of the [`desired_velocity`](Self::desired_velocity).
+    ///
+    /// Normally the walk basis holds the character at the desired speed on any slope. When this is
+    /// set, the part of the gravity along the slope is added to the speed when the character runs
+    /// downhill - e.g. with 0.5 it can reach up to 1.5 times the desired speed. Once the slope ends
+    /// the extra speed is braked like any other speed above the desired one (see
+    /// [`over_speed_decay`](Self::over_speed_decay) for preserving it).
+    ///
+    /// When set to `None`, the character runs downhill at the desired speed.
+    pub downhill_speed_bonus: Option<Float>,
+
+    /// When the proximity sensor reports multiple hits, stand on the one with the flattest
+    /// surface instead of the nearest one.
+    ///
+    /// This can smooth the character's behavior on ledges and on seams between colliders. It only
+    /// has effect if the [`max_hits`](crate::TnuaProximitySensor::max_hits) of the character's
+    /// proximity sensor is set to more than 1.
+    pub prefer_flattest_sensor_hit: bool,
+
+    /// Extra acceleration in the downhill direction applied to the character while it slips on a
+    /// slope steeper than [`max_slope`](Self::max_slope).
+    pub slip_acceleration: Float,
+
+    /// The maximum speed, in the downhill direction, the walk basis would push the character to
+    /// while it slips on a slope steeper than [`max_slope`](Self::max_slope).
+    ///
+    /// Note that this does not prevent the physics engine itself from making the character slide
+    /// faster.
+    pub max_slip_speed: Float,
+}
+
+impl Default for TnuaBuiltinWalk {
+    fn default() -> Self {
+        Self {
+            desired_velocity: Vector3::ZERO,
+            desired_forward: None,
+            float_height: 0.0,
+            cling_distance: 1.0,
+            spring_strengh: 400.0,
+            spring_dampening: 1.2,
+            float_spring: TnuaFloatSpring::Manual,
+            suspension: None,
+            input_response: None,
+            forward_speed_multiplier: 1.0,
+            backward_speed_multiplier: 1.0,
+            strafe_speed_multiplier: 1.0,
+            acceleration: 60.0,
+            air_acceleration: 20.0,
+            deceleration: None,
+            air_deceleration: None,
+            over_speed_decay: None,
+            friction: None,
+            surface_grip: 1.0,
+            platform_anchoring: None,
+            rotating_platform_compensation: false,
+            coyote_time: 0.15,
+            ground_persistence: 0.0,
+            free_fall_extra_gravity: 60.0,
+            fall_gravity_multiplier: 1.0,
+            tilt_offset_angvel: 5.0,
+            tilt_offset_angacl: 500.0,
+            turning_angvel: 10.0,
+            air_turning_angvel: None,
+            turning_snap_angle: 0.0,
+            turning_reverse_multiplier: 1.0,
+            max_slope: float_consts::FRAC_PI_2,
+            soft_max_slope: None,
+            soft_slope_exponent: 1.0,
+            downhill_speed_bonus: None,
+            prefer_flattest_sensor_hit: false,
+            slip_acceleration: 0.0,
+            max_slip_speed: Float::INFINITY,
+        }
+    }
+}
+
+impl TnuaBasis for TnuaBuiltinWalk {
+    const NAME: &'static str = "TnuaBuiltinWalk";
+    type State = TnuaBuiltinWalkState;
+
+    fn apply(&self, state: &mut Self::State, ctx: TnuaBasisContext, motor: &mut crate::TnuaMotor) {
+        if let Some(stopwatch) = &mut state.airborne_timer {
+            #[allow(clippy::unnecessary_cast)]
+            stopwatch.tick(Duration::from_secs_f64(ctx.frame_duration as f64));
+        }
+
+        let sensor_output = if self.prefer_flattest_sensor_hit {
+            // Using `min_by` with reversed comparison so that on ties the nearest hit is picked
+            ctx.proximity_sensor.all_outputs().min_by(|a, b| {
+                b.normal
+                    .dot(*ctx.up_direction)
+                    .total_cmp(&a.normal.dot(*ctx.up_direction))
+            })
+        } else {
+            ctx.proximity_sensor.output.as_ref()
+        };
+
+        let climb_vectors: Option<ClimbVectors>;
+        let considered_in_air: bool;
+        let impulse_to_offset: Vector3;
+        let slipping_vector: Option<Vector3>;
+        let mut anchor_correction = Vector3::ZERO;
+        let mut platform_acceleration = Vector3::ZERO;
+
+        let directional_desired_velocity = if let Some(input_response) = &self.input_response {
+            input_response.respond(
+                self.directional_desired_velocity(),
+                &mut state.responded_desired_velocity,
+                ctx.frame_duration,
+            )
+        } else {
+            state.responded_desired_velocity = Vector3::ZERO;
+            self.directional_desired_velocity()
+        };
+
+        if let Some(sensor_output) = sensor_output {
+            state.effective_velocity = ctx.tracker.velocity - sensor_output.entity_linvel;
+            let sideways_unnormalized = sensor_output
+                .normal
+                .cross(*ctx.up_direction)
+                .adjust_precision();
+            if sideways_unnormalized == Vector3::ZERO {
+                climb_vectors = None;
+            } else {
+                climb_vectors = Some(ClimbVectors {
+                    direction: sideways_unnormalized
+                        .cross(sensor_output.normal.adjust_precision())
+                        .normalize_or_zero()
+                        .adjust_precision(),
+                    sideways: sideways_unnormalized.normalize_or_zero().adjust_precision(),
+                });
+            }
+
+            slipping_vector = {
+                let angle_with_floor = sensor_output
+                    .normal
+                    .angle_between(*ctx.up_direction)
+                    .adjust_precision();
+                if angle_with_floor <= self.max_slope
+                    && ctx.is_walkable(sensor_output.entity, sensor_output.normal)
+                {
+                    None
+                } else {
+                    Some(
+                        sensor_output
+                            .normal
+                            .reject_from(*ctx.up_direction)
+                            .adjust_precision(),
+                    )
+                }
+            };
+
+            if state.airborne_timer.is_some() {
+                considered_in_air = true;
+                impulse_to_offset = Vector3::ZERO;
+                state.standing_on = None;
+            } else {
+                let mut anchor = None;
+                if let Some(standing_on_state) = &state.standing_on {
+                    if standing_on_state.entity != sensor_output.entity {
+                        impulse_to_offset = Vector3::ZERO;
+                    } else {
+                        impulse_to_offset =
+                            sensor_output.entity_linvel - standing_on_state.entity_linvel;
+                        anchor = standing_on_state.anchor.map(|anchor| {
+                            anchor + ctx.frame_duration * sensor_output.entity_linvel
+                        });
+                    }
+                } else {
+                    impulse_to_offset = Vector3::ZERO;
+                }
+
+                if slipping_vector.is_none() {
+                    considered_in_air = false;
+                    if self.rotating_platform_compensation {
+                        // Following the contact point, the velocity of a rotating entity turns
+                        // toward its rotation axis.
+                        platform_acceleration = sensor_output
+                            .entity_angvel
+                            .cross(sensor_output.entity_linvel);
+                    }
+                    let anchor = if let Some(platform_anchoring) = self
+                        .platform_anchoring
+                        .filter(|_| directional_desired_velocity == Vector3::ZERO)
+                    {
+                        // Only set the anchor once the character can stop in a single frame, so
+                        // that it will not get pulled back to where it started braking.
+                        let anchor = anchor.or_else(|| {
+                            (state
+                                .effective_velocity
+                                .reject_from(ctx.up_direction.adjust_precision())
+                                .length()
+                                <= ctx.frame_duration * self.acceleration)
+                                .then_some(ctx.tracker.translation)
+                        });
+                        if let Some(anchor) = anchor {
+                            anchor_correction = platform_anchoring
+                                * (anchor - ctx.tracker.translation)
+                                    .reject_from(ctx.up_direction.adjust_precision());
+                        }
+                        anchor
+                    } else {
+                        None
+                    };
+                    state.standing_on = Some(StandingOnState {
+                        entity: sensor_output.entity,
+                        // The acceleration is already applied, so it should not be counted again
+                        // as a change in the entity's velocity in the next frame.
+                        entity_linvel: sensor_output.entity_linvel
+                            + ctx.frame_duration * platform_acceleration,
+                        anchor,
+                    });
+                } else {
+                    considered_in_air = true;
+                    state.standing_on = None;
+                }
+            }
+        } else {
+            state.effective_velocity = ctx.tracker.velocity;
+            climb_vectors = None;
+            considered_in_air = true;
+            impulse_to_offset = Vector3::ZERO;
+            slipping_vector = None;
+            state.standing_on = None;
+        }
+        state.effective_velocity += impulse_to_offset;
+        state.slipping_vector = slipping_vector;
+
+        let velocity_on_plane = state
+            .effective_velocity
+            .reject_from(ctx.up_direction.adjust_precision());
+
+        let directional_desired_velocity = match sensor_output {
+            Some(sensor_output) if !considered_in_air => {
+                let desired_velocity = self.soft_slope_desired_velocity(
+                    directional_desired_velocity,
+                    sensor_output.normal,
+                    ctx.up_direction,
+                );
+                self.downhill_desired_velocity(
+                    desired_velocity,
+                    velocity_on_plane,
+                    sensor_output.normal,
+                    &ctx,
+                )
+            }
+            _ => directional_desired_velocity,
+        };
+
+        let locomotion = self.locomotion(considered_in_air);
+        // The anchor correction is applied by pretending the character is moving away from it.
+        let velocity_for_locomotion = velocity_on_plane - anchor_correction;
+        let desired_velocity = locomotion.effective_desired_velocity(
+            directional_desired_velocity,
+            velocity_for_locomotion,
+            ctx.frame_duration,
+        );
+        let walk_boost = locomotion.velocity_change(
+            desired_velocity,
+            velocity_for_locomotion,
+            ctx.frame_duration,
+        );
+
+        state.vertical_velocity = if let Some(climb_vectors) = &climb_vectors {
+            state.effective_velocity.dot(climb_vectors.direction)
+                * climb_vectors
+                    .direction
+                    .dot(ctx.up_direction.adjust_precision())
+        } else {
+            0.0
+        };
+
+        let walk_vel_change = if desired_velocity == Vector3::ZERO && slipping_vector.is_none() {
+            // When stopping, prefer a boost to be able to reach a precise stop (see issue #39)
+            let walk_boost = if let Some(climb_vectors) = &climb_vectors {
+                climb_vectors.project(walk_boost)
+            } else {
+                walk_boost
+            };
+            TnuaVelChange::boost(walk_boost)
+        } else {
+            // When accelerating, prefer an acceleration because the physics backends treat it
+            // better (see issue #34)
+            let walk_acceleration = walk_boost / ctx.frame_duration;
+            let walk_acceleration =
+                if let (Some(climb_vectors), None) = (&climb_vectors, slipping_vector) {
+                    climb_vectors.project(walk_acceleration)
+                } else {
+                    walk_acceleration
+                };
+
+            let slipping_boost = 'slipping_boost: {
+                let Some(slipping_vector) = slipping_vector else {
+                    break 'slipping_boost Vector3::ZERO;
+                };
+                let vertical_velocity = if 0.0 <= state.vertical_velocity {
+                    ctx.tracker.gravity.dot(ctx.up_direction.adjust_precision())
+                        * ctx.frame_duration
+                } else {
+                    state.vertical_velocity
+                };
+
+                let Ok((slipping_direction, slipping_per_vertical_unit)) =
+                    Dir3::new_and_length(slipping_vector.f32())
+                else {
+                    break 'slipping_boost Vector3::ZERO;
+                };
+
+                let expected_velocity = velocity_on_plane + walk_acceleration * ctx.frame_duration;
+                let expected_velocity_in_slipping_direction =
+                    expected_velocity.dot(slipping_direction.adjust_precision());
+                let required_veloicty_in_slipping_direction =
+                    (slipping_per_vertical_unit.adjust_precision() * -vertical_velocity)
+                        .max(
+                            expected_velocity_in_slipping_direction
+                                + self.slip_acceleration * ctx.frame_duration,
+                        )
+                        .min(self.max_slip_speed);
+
+                let diff = required_veloicty_in_slipping_direction
+                    - expected_velocity_in_slipping_direction;
+
+                if diff <= 0.0 {
+                    break 'slipping_boost Vector3::ZERO;
+                }
+
+                slipping_direction.adjust_precision() * diff
+            };
+            TnuaVelChange {
+                acceleration: walk_acceleration,
+                boost: slipping_boost,
+            }
+        };
+
+        let was_airborne = state.airborne_timer.is_some();
+        let float_height = self.float_height - state.suspension_dip;
+
+        let upward_impulse: TnuaVelChange = 'upward_impulse: {
+            let should_disable_due_to_slipping =
+                slipping_vector.is_some() && state.vertical_velocity <= 0.0;
+            for _ in 0..2 {
+                #[allow(clippy::unnecessary_cast)]
+                match &mut state.airborne_timer {
+                    None => {
+                        if let (false, Some(sensor_output)) =
+                            (should_disable_due_to_slipping, sensor_output)
+                        {
+                            // not doing the jump calculation here
+                            state.missed_ground_for = 0.0;
+                            let spring_offset =
+                                float_height - sensor_output.proximity.adjust_precision();
+                            state.standing_offset =
+                                -spring_offset * ctx.up_direction.adjust_precision();
+                            break 'upward_impulse self.spring_force(state, &ctx, spring_offset);
+                        } else if sensor_output.is_none()
+                            && state.missed_ground_for + ctx.frame_duration
+                                <= self.ground_persistence
+                            && state
+                                .effective_velocity
+                                .dot(ctx.up_direction.adjust_precision())
+                                <= 0.0
+                        {
+                            state.missed_ground_for += ctx.frame_duration;
+                            // Without a proximity there is nothing to feed the spring, but gravity
+                            // is still cancelled so that the missed frame does not dip the
+                            // character (and keeps the last `standing_offset` accurate).
+                            break 'upward_impulse TnuaVelChange::acceleration(
+                                -ctx.tracker.gravity,
+                            );
+                        } else {
+                            state.missed_ground_for = 0.0;
+                            state.airborne_timer = Some(Timer::from_seconds(
+                                self.coyote_time as f32,
+                                TimerMode::Once,
+                            ));
+                            continue;
+                        }
+                    }
+                    Some(_) => {
+                        if let (false, Some(sensor_output)) =
+                            (should_disable_due_to_slipping, sensor_output)
+                        {
+                            if sensor_output.proximity.adjust_precision() <= float_height {
+                                state.airborne_timer = None;
+                                continue;
+                            }
+                        }
+                        let up = ctx.up_direction.adjust_precision();
+                        let mut extra_gravity = 0.0;
+                        if state.vertical_velocity <= 0.0 {
+                            extra_gravity += self.free_fall_extra_gravity;
+                        }
+                        // `vertical_velocity` is zero when the sensor does not detect anything,
+                        // so the actual velocity is used to tell if the character is moving down.
+                        if state.effective_velocity.dot(up) < 0.0 {
+                            extra_gravity +=
+                                (self.fall_gravity_multiplier - 1.0) * ctx.tracker.gravity.dot(-up);
+                        }
+                        break 'upward_impulse TnuaVelChange::acceleration(-extra_gravity * up);
+                    }
+                }
+            }
+            error!("Tnua could not decide on jump state");
+            TnuaVelChange::ZERO
+        };
+
+        let ground = sensor_output
+            .filter(|_| state.airborne_timer.is_none())
+            .map(|sensor_output| TnuaGroundDetails {
+                entity: sensor_output.entity,
+                normal: sensor_output.normal,
+                contact_point: ctx.tracker.translation
+                    + ctx
+                        .tracker
+                        .rotation
+                        .mul_vec3(ctx.proximity_sensor.cast_origin)
+                    + sensor_output.proximity
+                        * ctx.proximity_sensor.cast_direction.adjust_precision(),
+                slope_angle: sensor_output
+                    .normal
+                    .angle_between(*ctx.up_direction)
+                    .adjust_precision(),
+            });
+        // While the ground persists through a missed sensor hit, keep the last ground details.
+        if ground.is_some() || 0.0 == state.missed_ground_for {
+            state.ground = ground;
+        }
+
+        if let Some(suspension) = &self.suspension {
+            let is_airborne = state.airborne_timer.is_some();
+            if was_airborne && !is_airborne {
+                let landing_speed = -state
+                    .effective_velocity
+                    .dot(ctx.up_direction.adjust_precision());
+                state.landing_dip += suspension.landing_dip * landing_speed.max(0.0);
+            }
+            state.landing_dip *= (-suspension.landing_recovery * ctx.frame_duration).exp();
+            let target_dip = if is_airborne {
+                0.0
+            } else {
+                (suspension.speed_dip * velocity_on_plane.length() + state.landing_dip)
+                    .min(suspension.max_dip)
+            };
+            state.suspension_dip += (target_dip - state.suspension_dip)
+                * (1.0 - (-suspension.response * ctx.frame_duration).exp());
+        } else {
+            state.suspension_dip = 0.0;
+            state.landing_dip = 0.0;
+        }
+
+        motor.lin = walk_vel_change
+            + TnuaVelChange::boost(impulse_to_offset)
+            + TnuaVelChange::acceleration(platform_acceleration)
+            + upward_impulse;
+        let new_velocity = state.effective_velocity
+            + motor.lin.boost
+            + ctx.frame_duration * motor.lin.acceleration
+            - impulse_to_offset
+            - ctx.frame_duration * platform_acceleration;
+        state.running_velocity = new_velocity.reject_from(ctx.up_direction.adjust_precision());
+
+        motor.ang = self.orientation(considered_in_air).angular_vel_change(&ctx);
+    }
+
+    fn proximity_sensor_cast_range(&self, _state: &Self::State) -> Float {
+        self.float_height + self.cling_distance
+    }
+
+    fn displacement(&self, state: &Self::State) -> Option<Vector3> {
+        match state.airborne_timer {
+            None => Some(state.standing_offset),
+            Some(_) => None,
+        }
+    }
+
+    fn effective_velocity(&self, state: &Self::State) -> Vector3 {
+        state.effective_velocity
+    }
+
+    fn vertical_velocity(&self, state: &Self::State) -> Float {
+        state.vertical_velocity
+    }
+
+    fn neutralize(&mut self) {
+        self.desired_velocity = Vector3::ZERO;
+        self.desired_forward = None;
+    }
+
+    fn is_airborne(&self, state: &Self::State) -> bool {
+        state
+            .airborne_timer
+            .as_ref()
+            .is_some_and(|timer| timer.finished())
+    }
+
+    fn ground_details(&self, state: &Self::State) -> Option<TnuaGroundDetails> {
+        state.ground
+    }
+
+    fn violate_coyote_time(&self, state: &mut Self::State) {
+        if let Some(timer) = &mut state.airborne_timer {
+            timer.set_duration(Duration::ZERO);
+        }
+    }
+}
+
+impl TnuaBuiltinWalk {
+    /// Calculate the vertical spring force that this basis would need to apply assuming its
+    /// vertical distance from the vertical distance it needs to be at equals the `spring_offset`
+    /// argument.
+    ///
+    /// Note: this is exposed so that actions like
+    /// [`TnuaBuiltinCrouch`](crate::builtins::TnuaBuiltinCrouch) may rely on it.
+    pub fn spring_force(
+        &self,
+        state: &TnuaBuiltinWalkState,
+        ctx: &TnuaBasisContext,
+        spring_offset: Float,
+    ) -> TnuaVelChange {
+        let relative_velocity = state
+            .effective_velocity
+            .dot(ctx.up_direction.adjust_precision())
+            - state.vertical_velocity;
+        self.float()
+            .spring_force(ctx, spring_offset, relative_velocity)
+    }
+
+    /// Check the parameters for known-bad combinations.
+    ///
+    /// Pass the [`TnuaColliderDimensions`] of the character (if it has them) to also check the
+    /// [`float_height`](Self::float_height) against the collider. See
+    /// [`TnuaConfigValidationPlugin`](crate::TnuaConfigValidationPlugin) for doing this
+    /// automatically.
+    pub fn validate(
+        &self,
+        collider_dimensions: Option<&TnuaColliderDimensions>,
+    ) -> Vec<TnuaConfigWarning> {
+        let mut warnings = Vec::new();
+        if let Some(collider_dimensions) = collider_dimensions {
+            if self.float_height <= collider_dimensions.bottom_offset {
+                warnings.push(TnuaConfigWarning::new(
+                    "float_height",
+                    format!(
+                        "{} does not lift the bottom of the collider ({} below the center) off the ground - the collider will drag on the ground",
+                        self.float_height, collider_dimensions.bottom_offset,
+                    ),
+                ));
+            }
+        }
+        if self.cling_distance <= 0.0 {
+            warnings.push(TnuaConfigWarning::new(
+                "cling_distance",
+                "must be positive - otherwise the character will be considered airborne whenever it bobs above the float height",
+            ));
+        }
+        if matches!(self.float_spring, TnuaFloatSpring::Manual) && 2.0 <= self.spring_dampening {
+            warnings.push(TnuaConfigWarning::new(
+                "spring_dampening",
+                format!(
+                    "{} is 2.0 or higher, which makes the character shake and get launched upward",
+                    self.spring_dampening,
+                ),
+            ));
+        }
+        warnings
+    }
+
+    /// The [float spring](TnuaBasisFloat) part of this basis.
+    pub fn float(&self) -> TnuaBasisFloat {
+        TnuaBasisFloat {
+            float_height: self.float_height,
+            spring_strengh: self.spring_strengh,
+            spring_dampening: self.spring_dampening,
+            float_spring: self.float_spring.clone(),
+        }
+    }
+
+    /// The [locomotion](TnuaBasisLocomotion) part of this basis, on the ground or in the air.
+    pub fn locomotion(&self, in_air: bool) -> TnuaBasisLocomotion {
+        if in_air {
+            TnuaBasisLocomotion {
+                acceleration: self.air_acceleration,
+                deceleration: self.air_deceleration,
+                over_speed_decay: self.over_speed_decay,
+                friction: None,
+            }
+        } else {
+            TnuaBasisLocomotion {
+                acceleration: self.surface_grip * self.acceleration,
+                deceleration: self
+                    .deceleration
+                    .map(|deceleration| self.surface_grip * deceleration),
+                over_speed_decay: self.over_speed_decay,
+                friction: self.friction.map(|friction| self.surface_grip * friction),
+            }
+        }
+    }
+
+    /// The [orientation](TnuaBasisOrientation) part of this basis, on the ground or in the air.
+    pub fn orientation(&self, in_air: bool) -> TnuaBasisOrientation {
+        TnuaBasisOrientation {
+            desired_forward: self.desired_forward,
+            tilt_offset_angvel: self.tilt_offset_angvel,
+            tilt_offset_angacl: self.tilt_offset_angacl,
+            turning_angvel: if in_air {
+                self.air_turning_angvel.unwrap_or(self.turning_angvel)
+            } else {
+                self.turning_angvel
+            },
+            turning_snap_angle: self.turning_snap_angle,
+            turning_reverse_multiplier: self.turning_reverse_multiplier,
+        }
+    }
+
+    /// The [`desired_velocity`](Self::desired_velocity) after applying the forward, backward and
+    /// strafe speed multipliers.
+    pub fn directional_desired_velocity(&self) -> Vector3 {
+        let Some(desired_forward) = self.desired_forward else {
+            return self.desired_velocity;
+        };
+        let desired_forward = desired_forward.adjust_precision();
+        let forward_speed = self.desired_velocity.dot(desired_forward);
+        let strafe_velocity = self.desired_velocity - forward_speed * desired_forward;
+        let forward_multiplier = if 0.0 <= forward_speed {
+            self.forward_speed_multiplier
+        } else {
+            self.backward_speed_multiplier
+        };
+        forward_multiplier * forward_speed * desired_forward
+            + self.strafe_speed_multiplier * strafe_velocity
+    }
+
+    fn soft_slope_desired_velocity(
+        &self,
+        desired_velocity: Vector3,
+        normal: Dir3,
+        up_direction: Dir3,
+    ) -> Vector3 {
+        let Some(soft_max_slope) = self.soft_max_slope else {
+            return desired_velocity;
+        };
+        let angle_with_floor = normal.angle_between(*up_direction).adjust_precision();
+        if angle_with_floor <= soft_max_slope {
+            return desired_velocity;
+        }
+        let Some(uphill) = (-normal.reject_from(*up_direction))
+            .adjust_precision()
+            .try_normalize()
+        else {
+            return desired_velocity;
+        };
+        let uphill_speed = desired_velocity.dot(uphill);
+        if uphill_speed <= 0.0 {
+            return desired_velocity;
+        }
+        let steepness = ((angle_with_floor - soft_max_slope)
+            / (self.max_slope - soft_max_slope).max(Float::EPSILON))
+        .clamp(0.0, 1.0);
+        let uphill_factor = 1.0 - steepness.powf(self.soft_slope_exponent);
+        desired_velocity - (1.0 - uphill_factor) * uphill_speed * uphill
+    }
+
+    fn downhill_desired_velocity(
+        &self,
+        desired_velocity: Vector3,
+        velocity_on_plane: Vector3,
+        normal: Dir3,
+        ctx: &TnuaBasisContext,
+    ) -> Vector3 {
+        let Some(downhill_speed_bonus) = self.downhill_speed_bonus else {
+            return desired_velocity;
+        };
+        let desired_speed = desired_velocity.length();
+        if desired_speed == 0.0 {
+            return desired_velocity;
+        }
+        let direction = desired_velocity / desired_speed;
+        // The length of this vector is the sine of the slope.
+        let downhill = normal.reject_from(*ctx.up_direction).adjust_precision();
+        let downhill_alignment = direction.dot(downhill);
+        if downhill_alignment <= 0.0 {
+            return desired_velocity;
+        }
+        // The gravity along the slope is `g * sin(slope)`, and its horizontal part is
+        // `g * sin(slope) * cos(slope)`.
+        let gravity = ctx
+            .tracker
+            .gravity
+            .dot(-ctx.up_direction.adjust_precision());
+        let acceleration =
+            gravity * downhill_alignment * normal.dot(*ctx.up_direction).adjust_precision();
+        let speed = velocity_on_plane.dot(direction) + acceleration * ctx.frame_duration;
+        direction * speed.clamp(desired_speed, (1.0 + downhill_speed_bonus) * desired_speed)
+    }
+}
+
+/// A suspension for a [`TnuaBuiltinWalk`] character, which makes its float height dip with its
+/// horizontal speed and when it lands.
+///
+/// This fits heavy or vehicle-like characters. The dip does not follow the speed and the landings
+/// immediately - it moves toward its target at the [`response`](Self::response) rate, and the
+/// float spring moves the character to the dipped float height - so the changes are gradual.
+#[derive(Clone, Debug)]
+pub struct TnuaWalkSuspension {
+    /// How much the float height dips for every unit of horizontal speed.
+    pub speed_dip: Float,
+
+    /// How much the float height dips, when landing, for every unit of downward speed.
+    pub landing_dip: Float,
+
+    /// The maximum total dip.
+    pub max_dip: Float,
+
+    /// The rate at which the landing dip recovers. Higher values mean a shorter dip.
+    pub landing_recovery: Float,
+
+    /// The rate at which the dip follows its target. Higher values mean a stiffer suspension.
+    pub response: Float,
+}
+
+impl Default for TnuaWalkSuspension {
+    fn default() -> Self {
+        Self {
+            speed_dip: 0.02,
+            landing_dip: 0.05,
+            max_dip: 0.3,
+            landing_recovery: 5.0,
+            response: 10.0,
+        }
+    }
+}
+
+/// Shapes the analog input fed to a [`TnuaBuiltinWalk`] - see
+/// [`input_response`](TnuaBuiltinWalk::input_response).
+///
+/// The shaping is done on the magnitude of the desired velocity relative to the
+/// [`full_speed`](Self::full_speed), so the control system should feed the stick's value (from
+/// 0.0 to 1.0) multiplied by the `full_speed`. The direction of the desired velocity is not
+/// changed.
+#[derive(Clone, Debug)]
+pub struct TnuaWalkInputResponse {
+    /// The speed of the desired velocity that corresponds to a fully tilted stick.
+    pub full_speed: Float,
+
+    /// Inputs below this fraction of the [`full_speed`](Self::full_speed) are ignored. The range
+    /// above it is stretched so that the speed still starts from zero.
+    pub dead_zone: Float,
+
+    /// The exponent of the response curve. `1.0` is linear, and higher values give finer control
+    /// over the lower speeds.
+    pub exponent: Float,
+
+    /// When set, the character only moves at two speeds - this speed for inputs up to the
+    /// [`run_threshold`](Self::run_threshold), and the [`full_speed`](Self::full_speed) above it.
+    pub walk_speed: Option<Float>,
+
+    /// The fraction of the [`full_speed`](Self::full_speed) above which the character runs instead
+    /// of walking. Only used when [`walk_speed`](Self::walk_speed) is set.
+    pub run_threshold: Float,
+
+    /// The time constant, in seconds, for smoothing the changes in the desired velocity. `0.0`
+    /// disables the smoothing.
+    pub smoothing_time: Float,
+}
+
+impl Default for TnuaWalkInputResponse {
+    fn default() -> Self {
+        Self {
+            full_speed: 10.0,
+            dead_zone: 0.1,
+            exponent: 1.0,
+            walk_speed: None,
+            run_threshold: 0.6,
+            smoothing_time: 0.05,
+        }
+    }
+}
+
+impl TnuaWalkInputResponse {
+    /// Apply the dead zone, the response curve and the walk/run threshold to a desired velocity.
+    pub fn shape(&self, desired_velocity: Vector3) -> Vector3 {
+        let speed = desired_velocity.length();
+        if speed == 0.0 || self.full_speed <= 0.0 {
+            return desired_velocity;
+        }
+        let input = speed / self.full_speed;
+        if input <= self.dead_zone {
+            return Vector3::ZERO;
+        }
+        let input = (input - self.dead_zone) / (1.0 - self.dead_zone).max(Float::EPSILON);
+        let shaped_speed = if let Some(walk_speed) = self.walk_speed {
+            if input <= self.run_threshold {
+                walk_speed
+            } else {
+                self.full_speed
+            }
+        } else if input < 1.0 {
+            self.full_speed * input.powf(self.exponent)
+        } else {
+            // Inputs beyond the full speed (e.g. from a speed boost) are left linear.
+            self.full_speed * input
+        };
+        desired_velocity * (shaped_speed / speed)
+    }
+
+    fn respond(
+        &self,
+        desired_velocity: Vector3,
+        responded: &mut Vector3,
+        frame_duration: Float,
+    ) -> Vector3 {
+        let target = self.shape(desired_velocity);
+        if self.smoothing_time <= 0.0 {
+            *responded = target;
+            return target;
+        }
+        *responded += (target - *responded) * (1.0 - (-frame_duration / self.smoothing_time).exp());
+        // Smoothing never reaches the target exactly, but the walk basis needs an exact zero for
+        // stopping precisely.
+        if target == Vector3::ZERO
+            && responded.length() <= self.full_speed * self.dead_zone.max(0.01)
+        {
+            *responded = Vector3::ZERO;
+        }
+        *responded
+    }
+}
+
+#[derive(Debug)]
+struct StandingOnState {
+    entity: Entity,
+    entity_linvel: Vector3,
+    anchor: Option<Vector3>,
+}
+
+#[derive(Default)]
+pub struct TnuaBuiltinWalkState {
+    airborne_timer: Option<Timer>,
+    /// The current distance of the character from the distance its supposed to float at.
+    pub standing_offset: Vector3,
+    standing_on: Option<StandingOnState>,
+    effective_velocity: Vector3,
+    vertical_velocity: Float,
+    slipping_vector: Option<Vector3>,
+    ground: Option<TnuaGroundDetails>,
+    suspension_dip: Float,
+    landing_dip: Float,
+    missed_ground_for: Float,
+    responded_desired_velocity: Vector3,
+    /// The velocity, perpendicular to the up direction, that the character is supposed to move at.
+    ///
+    /// If the character is standing on something else
+    /// ([`standing_on_entity`](Self::standing_on_entity) returns `Some`) then the
+    /// `running_velocity` will be relative to the velocity of that entity.
+    pub running_velocity: Vector3,
+}
+
+impl TnuaBuiltinWalkState {
+    /// Returns the entity that the character currently stands on.
+    pub fn standing_on_entity(&self) -> Option<Entity> {
+        Some(self.standing_on.as_ref()?.entity)
+    }
+
+    /// Checks if the character is slipping on a slope steeper than
+    /// [`max_slope`](TnuaBuiltinWalk::max_slope).
+    pub fn is_slipping(&self) -> bool {
+        self.slipping_vector.is_some()
+    }
+
+    /// The downhill direction the character is slipping at, perpendicular to the up direction.
+    ///
+    /// Returns `None` if the character is not [slipping](Self::is_slipping).
+    pub fn slipping_direction(&self) -> Option<Dir3> {
+        Dir3::new(self.slipping_vector?.f32()).ok()
+    }
+
+    /// How much the float height currently dips due to the
+    /// [`suspension`](TnuaBuiltinWalk::suspension).
+    pub fn suspension_dip(&self) -> Float {
+        self.suspension_dip
+    }
+
+    /// The desired velocity after it was shaped and smoothed by the
+    /// [`input_response`](TnuaBuiltinWalk::input_response).
+    ///
+    /// This is zero when the `input_response` is not set.
+    pub fn responded_desired_velocity(&self) -> Vector3 {
+        self.responded_desired_velocity
+    }
+}
+
+struct ClimbVectors {
+    direction: Vector3,
+    sideways: Vector3,
+}
+
+impl ClimbVectors {
+    fn project(&self, vector: Vector3) -> Vector3 {
+        let axis_direction = vector.dot(self.direction) * self.direction;
+        let axis_sideways = vector.dot(self.sideways) * self.sideways;
+        axis_direction + axis_sideways
+    }
+}
@@ -0,0 +1,98 @@
+use crate::math::{float_consts, AdjustPrecision, Float, Vector3};
+use bevy::prelude::*;
+
+use crate::util::{rotation_arc_around_axis, rotation_arc_with_fallback_axis};
+use crate::{TnuaBasisContext, TnuaVelChange};
+
+/// The orientation controller of [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk), for reuse
+/// in custom [bases](crate::TnuaBasis).
+///
+/// It keeps the character standing upright (along the up direction) and turns it toward the
+/// [`desired_forward`](Self::desired_forward). This part does not keep any memory of its own.
+#[derive(Clone, Debug)]
+pub struct TnuaBasisOrientation {
+    /// The direction the negative Z axis of the character should point at. When `None`, the
+    /// character will not be turned along the up axis.
+    pub desired_forward: Option<Dir3>,
+
+    /// The maximum angular velocity used for keeping the character standing upright.
+    pub tilt_offset_angvel: Float,
+
+    /// The maximum angular acceleration used for reaching
+    /// [`tilt_offset_angvel`](Self::tilt_offset_angvel).
+    pub tilt_offset_angacl: Float,
+
+    /// The maximum angular velocity used for turning the character when the direction changes.
+    pub turning_angvel: Float,
+
+    /// When the angle, in radians, between the character's forward and the
+    /// [`desired_forward`](Self::desired_forward) is at most this value, the character will snap
+    /// to the desired direction immediately instead of turning gradually.
+    pub turning_snap_angle: Float,
+
+    /// A multiplier for the [`turning_angvel`](Self::turning_angvel) when the character needs to
+    /// turn around, interpolated linearly from 1.0 when no turn is needed to this value when the
+    /// character needs to make a 180 turn.
+    pub turning_reverse_multiplier: Float,
+}
+
+impl TnuaBasisOrientation {
+    /// Calculate the angular velocity change needed for fixing the tilt and for turning.
+    pub fn angular_vel_change(&self, ctx: &TnuaBasisContext) -> TnuaVelChange {
+        // Tilt
+
+        let torque_to_fix_tilt = {
+            let tilted_up = ctx.tracker.rotation.mul_vec3(Vector3::Y);
+
+            // When the character is upside down, roll it around its own Z axis - which, in 2D, is
+            // the only axis it can be rotated around.
+            let rotation_required_to_fix_tilt = rotation_arc_with_fallback_axis(
+                tilted_up,
+                ctx.up_direction.adjust_precision(),
+                ctx.tracker.rotation.mul_vec3(Vector3::Z),
+            );
+
+            let desired_angvel = (rotation_required_to_fix_tilt.xyz() / ctx.frame_duration)
+                .clamp_length_max(self.tilt_offset_angvel);
+            let angular_velocity_diff = desired_angvel - ctx.tracker.angvel;
+            angular_velocity_diff.clamp_length_max(ctx.frame_duration * self.tilt_offset_angacl)
+        };
+
+        // Turning
+
+        let desired_angvel = if let Some(desired_forward) = self.desired_forward {
+            let current_forward = ctx.tracker.rotation.mul_vec3(Vector3::NEG_Z);
+            let rotation_along_up_axis = rotation_arc_around_axis(
+                ctx.up_direction,
+                current_forward,
+                desired_forward.adjust_precision(),
+            )
+            .unwrap_or(0.0);
+            if rotation_along_up_axis.abs() <= self.turning_snap_angle {
+                rotation_along_up_axis / ctx.frame_duration
+            } else {
+                let turning_angvel = self.turning_angvel
+                    * (1.0
+                        + (self.turning_reverse_multiplier - 1.0) * rotation_along_up_axis.abs()
+                            / float_consts::PI);
+                (rotation_along_up_axis / ctx.frame_duration).clamp(-turning_angvel, turning_angvel)
+            }
+        } else {
+            0.0
+        };
+
+        // NOTE: This is the regular axis system so we used the configured up.
+        let existing_angvel = ctx.tracker.angvel.dot(ctx.up_direction.adjust_precision());
+
+        // This is the torque. Should it be clamped by an acceleration? From experimenting with
+        // this I think it's meaningless and only causes bugs.
+        let torque_to_turn = desired_angvel - existing_angvel;
+
+        let existing_turn_torque = torque_to_fix_tilt.dot(ctx.up_direction.adjust_precision());
+        let torque_to_turn = torque_to_turn - existing_turn_torque;
+
+        TnuaVelChange::boost(
+            torque_to_fix_tilt + torque_to_turn * ctx.up_direction.adjust_precision(),
+        )
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 
 use crate::controller::TnuaActionFlowStatus;
 use crate::prelude::*;
@@ -230,3 +231,167 @@ impl TnuaSimpleAirActionsCounter {
         self.air_actions_count
     }
 }
+
+#[derive(Debug, Clone, Copy)]
+enum AirActionCountingRule {
+    Counted(usize),
+    EndsCounting,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AirActionSlot {
+    limit: usize,
+    count: usize,
+}
+
+/// A counter that counts the air actions a character is able to perform, with a separate pool
+/// for each group of actions.
+///
+/// Unlike [`TnuaSimpleAirActionsCounter`], which counts all the air actions together, this
+/// counter allows - for example - to have one air jump and one air dash, where using the air dash
+/// does not consume the air jump. Each slot is configured with its own limit, and actions that are
+/// not assigned to any slot are not counted at all.
+///
+/// Actions registered with [`with_ending_action`](Self::with_ending_action) (e.g. grabbing a
+/// ledge or sliding down a wall) reset all the counts when they start, as if the character has
+/// landed.
+///
+/// It's [`update`](Self::update) must be called every frame.
+///
+/// ```no_run
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::builtins::TnuaBuiltinDash;
+/// # use bevy_tnua::control_helpers::TnuaAirActionSlotsCounter;
+/// let air_actions_counter = TnuaAirActionSlotsCounter::default()
+///     // One air jump
+///     .with_slot(&[TnuaBuiltinJump::NAME], 1)
+///     // Two air dashes, that do not consume the air jump
+///     .with_slot(&[TnuaBuiltinDash::NAME], 2);
+/// ```
+#[derive(Component, Default)]
+pub struct TnuaAirActionSlotsCounter {
+    tracker: TnuaAirActionsTracker,
+    rules: HashMap<&'static str, AirActionCountingRule>,
+    slots: Vec<AirActionSlot>,
+    current_action: Option<(&'static str, usize)>,
+}
+
+impl TnuaAirActionSlotsCounter {
+    /// Add a slot for counting some actions together, and allow `limit` of them in the air.
+    ///
+    /// The limit does not include actions that started from the ground - so a slot for jumps with
+    /// a limit of 1 allows one jump from the ground and one air jump.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the actions was already registered in another slot or as an ending
+    /// action.
+    pub fn with_slot(mut self, action_names: &[&'static str], limit: usize) -> Self {
+        let slot_index = self.slots.len();
+        self.slots.push(AirActionSlot { limit, count: 0 });
+        for action_name in action_names {
+            self.register_rule(action_name, AirActionCountingRule::Counted(slot_index));
+        }
+        self
+    }
+
+    /// Register an action that resets all the counters when it starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the action was already registered in a slot or as an ending action.
+    pub fn with_ending_action(mut self, action_name: &'static str) -> Self {
+        self.register_rule(action_name, AirActionCountingRule::EndsCounting);
+        self
+    }
+
+    fn register_rule(&mut self, action_name: &'static str, rule: AirActionCountingRule) {
+        let existing = self.rules.insert(action_name, rule);
+        assert!(
+            existing.is_none(),
+            "Action {action_name:?} was already registered in the air actions counter"
+        );
+    }
+
+    /// Call this every frame to track the air actions.
+    pub fn update(&mut self, controller: &TnuaController) {
+        let was_in_air = self.tracker.considered_in_air;
+        let update = self.tracker.update(controller);
+        match update {
+            TnuaAirActionsUpdate::NoChange => {}
+            TnuaAirActionsUpdate::FreeFallStarted => {
+                self.current_action = None;
+            }
+            TnuaAirActionsUpdate::AirActionStarted(action_name) => {
+                match self.rules.get(action_name) {
+                    None => {
+                        self.current_action = None;
+                    }
+                    Some(AirActionCountingRule::Counted(slot_index)) => {
+                        if was_in_air {
+                            let slot = &mut self.slots[*slot_index];
+                            slot.count += 1;
+                            self.current_action = Some((action_name, slot.count));
+                        } else {
+                            self.current_action = Some((action_name, 0));
+                        }
+                    }
+                    Some(AirActionCountingRule::EndsCounting) => {
+                        self.reset_all_slots();
+                        self.current_action = None;
+                    }
+                }
+            }
+            TnuaAirActionsUpdate::ActionFinishedInAir => {
+                self.current_action = None;
+            }
+            TnuaAirActionsUpdate::JustLanded => {
+                self.reset_all_slots();
+                self.current_action = None;
+            }
+        }
+    }
+
+    fn reset_all_slots(&mut self) {
+        for slot in self.slots.iter_mut() {
+            slot.count = 0;
+        }
+    }
+
+    /// Calculate the "air number" of an action within its slot.
+    ///
+    /// The air number of a ground action is 0. The first air action of the slot has an air number
+    /// of 1, the second has an air number of 2 and so on. Actions that are not registered in any
+    /// slot always get 0.
+    ///
+    /// Like [`TnuaSimpleAirActionsCounter::air_count_for`], the number will not change while the
+    /// action continues to be fed - as long as the correct action name is passed.
+    pub fn air_count_for(&self, action_name: &str) -> usize {
+        if let Some((current_action, air_number)) = self.current_action {
+            if current_action == action_name {
+                return air_number;
+            }
+        }
+        let Some(AirActionCountingRule::Counted(slot_index)) = self.rules.get(action_name) else {
+            return 0;
+        };
+        if self.tracker.considered_in_air {
+            self.slots[*slot_index].count + 1
+        } else {
+            0
+        }
+    }
+
+    /// Determine if the action can be performed in the air, according to the limit of its slot.
+    ///
+    /// Use this to set the `allow_in_air` field of the action. Actions that are not registered in
+    /// any slot are always allowed.
+    pub fn allows_in_air(&self, action_name: &str) -> bool {
+        match self.rules.get(action_name) {
+            Some(AirActionCountingRule::Counted(slot_index)) => {
+                self.air_count_for(action_name) <= self.slots[*slot_index].limit
+            }
+            None | Some(AirActionCountingRule::EndsCounting) => true,
+        }
+    }
+}
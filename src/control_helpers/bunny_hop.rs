@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Float;
+
+use crate::builtins::{TnuaBuiltinJump, TnuaBuiltinWalk};
+use crate::controller::TnuaController;
+use crate::TnuaAction;
+
+/// A helper for frame-perfect bunny hopping.
+///
+/// While the jump button is held (and the jump action keeps getting fed), a jump passed through
+/// [`jump`](Self::jump) will start again on the frame the character lands. Each hop in a chain can
+/// also make the character a little faster - pass the walk basis through [`walk`](Self::walk) to
+/// apply the [`speed_boost_per_hop`](Self::speed_boost_per_hop). The chain breaks when the
+/// character stays on the ground for longer than [`chain_window`](Self::chain_window).
+///
+/// Since this works by modifying what the control system feeds to the controller, it does not
+/// bypass the controller's own mechanisms - input buffering, coyote time and the rules set with
+/// [`TnuaActionTransitionRules`](crate::controller::TnuaActionTransitionRules) apply to the hops
+/// like they apply to any other jump.
+///
+/// Its [`update`](Self::update) must be called every frame, before feeding the controller:
+///
+/// ```no_run
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaBunnyHop;
+/// # use bevy_tnua::math::Float;
+/// # let mut controller = TnuaController::default();
+/// # let mut bunny_hop = TnuaBunnyHop::default();
+/// # let (frame_duration, jump_pressed): (Float, bool) = (0.0, false);
+/// bunny_hop.update(&controller, frame_duration);
+/// controller.basis(bunny_hop.walk(TnuaBuiltinWalk {
+///     float_height: 1.5,
+///     ..Default::default()
+/// }));
+/// if jump_pressed {
+///     controller.action(bunny_hop.jump(TnuaBuiltinJump {
+///         height: 4.0,
+///         ..Default::default()
+///     }));
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct TnuaBunnyHop {
+    /// How much each hop in a chain adds to the multiplier of the walk basis'
+    /// [`desired_velocity`](TnuaBuiltinWalk::desired_velocity). The first jump of a chain does not
+    /// get a boost.
+    pub speed_boost_per_hop: Float,
+
+    /// The maximum multiplier of the walk basis' desired velocity.
+    pub max_speed_multiplier: Float,
+
+    /// The time, in seconds, the character can stay on the ground between hops without breaking
+    /// the chain.
+    pub chain_window: Float,
+
+    /// The name the jump action is fed with.
+    pub jump_action_name: &'static str,
+
+    hops: usize,
+    grounded_for: Float,
+}
+
+impl Default for TnuaBunnyHop {
+    fn default() -> Self {
+        Self {
+            speed_boost_per_hop: 0.0,
+            max_speed_multiplier: 1.0,
+            chain_window: 0.1,
+            jump_action_name: TnuaBuiltinJump::NAME,
+            hops: 0,
+            grounded_for: 0.0,
+        }
+    }
+}
+
+impl TnuaBunnyHop {
+    /// Call this every frame to track the hops.
+    pub fn update(&mut self, controller: &TnuaController, frame_duration: Float) {
+        if controller.action_flow_status().just_starting() == Some(self.jump_action_name) {
+            if 0 < self.hops && self.grounded_for <= self.chain_window {
+                self.hops += 1;
+            } else {
+                self.hops = 1;
+            }
+            self.grounded_for = 0.0;
+            return;
+        }
+
+        let is_jumping = controller.action_name() == Some(self.jump_action_name);
+        if is_jumping || controller.is_airborne().unwrap_or(true) {
+            self.grounded_for = 0.0;
+        } else {
+            self.grounded_for += frame_duration;
+            if self.chain_window < self.grounded_for {
+                self.hops = 0;
+            }
+        }
+    }
+
+    /// The number of jumps in the current chain, including the first one. Zero when there is no
+    /// chain.
+    pub fn hops(&self) -> usize {
+        self.hops
+    }
+
+    /// The current multiplier of the walk basis' desired velocity.
+    pub fn speed_multiplier(&self) -> Float {
+        let boosted_hops = self.hops.saturating_sub(1) as Float;
+        (1.0 + self.speed_boost_per_hop * boosted_hops).min(self.max_speed_multiplier.max(1.0))
+    }
+
+    /// Apply the [`speed_multiplier`](Self::speed_multiplier) to the walk basis.
+    pub fn walk(&self, walk: TnuaBuiltinWalk) -> TnuaBuiltinWalk {
+        TnuaBuiltinWalk {
+            desired_velocity: self.speed_multiplier() * walk.desired_velocity,
+            ..walk
+        }
+    }
+
+    /// Make the jump start again as soon as the character lands while it is still fed.
+    pub fn jump(&self, jump: TnuaBuiltinJump) -> TnuaBuiltinJump {
+        TnuaBuiltinJump {
+            reschedule_cooldown: Some(0.0),
+            ..jump
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+pub struct TnuaCameraFollowPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaCameraFollowPlugin {
+    /// The `schedule` should run after the physics backend writes the positions of the rigid
+    /// bodies back to their [`Transform`]s.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+/// Unlike most Tnua plugins, this one defaults to [`PostUpdate`] - because the camera must be
+/// updated after the physics backend has moved the character.
+impl Default for TnuaCameraFollowPlugin {
+    fn default() -> Self {
+        Self::new(PostUpdate)
+    }
+}
+
+/// A plugin required for making [`TnuaCameraFollow`] work.
+impl Plugin for TnuaCameraFollowPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(
+            self.schedule,
+            TnuaCameraFollowSystemSet.before(TransformSystem::TransformPropagate),
+        );
+        app.add_systems(
+            self.schedule,
+            update_camera_follow_system.in_set(TnuaCameraFollowSystemSet),
+        );
+    }
+}
+
+/// The system set in which [`TnuaCameraFollow`] cameras are moved.
+///
+/// The camera must be moved after the physics backend writes the character's position back to its
+/// [`Transform`] (otherwise it'll be one frame behind the character, which looks like jitter) but
+/// before the transforms are propagated (otherwise the new camera position will only be rendered
+/// in the next frame). The plugin already orders this set before
+/// [`TransformSystem::TransformPropagate`], but if the physics backend's writeback runs in the same
+/// schedule it may be required to order this set after it.
+#[derive(SystemSet, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct TnuaCameraFollowSystemSet;
+
+/// Makes a camera (or any other entity) follow a character.
+///
+/// Place this component on the camera entity. It'll move the camera to the
+/// [`offset`](Self::offset) from the [`target`](Self::target) entity and make it look at the
+/// target. The camera's position is read from, and written to, the [`Transform`] - so both the
+/// camera and the target should be root entities.
+///
+/// This is mainly meant as a reference implementation that updates the camera in the correct
+/// place in the schedule (see [`TnuaCameraFollowSystemSet`]). Games with more complex camera
+/// control should probably write their own systems, but should still place them in the same place
+/// in the schedule.
+///
+/// Requires the [`TnuaCameraFollowPlugin`].
+#[derive(Component)]
+pub struct TnuaCameraFollow {
+    /// The entity to follow.
+    pub target: Entity,
+
+    /// The position of the camera relative to the target, in world coordinates.
+    pub offset: Vec3,
+
+    /// The point, relative to the target, that the camera looks at. `None` means the camera's
+    /// rotation is not changed.
+    pub look_at_offset: Option<Vec3>,
+
+    /// The time constant, in seconds, of the exponential smoothing of the camera's movement.
+    ///
+    /// `0.0` means the camera sticks to the target without any smoothing.
+    pub smoothing_time_constant: f32,
+}
+
+impl TnuaCameraFollow {
+    pub fn new(target: Entity, offset: Vec3) -> Self {
+        Self {
+            target,
+            offset,
+            look_at_offset: Some(Vec3::ZERO),
+            smoothing_time_constant: 0.0,
+        }
+    }
+}
+
+fn update_camera_follow_system(
+    time: Res<Time>,
+    mut cameras_query: Query<(&TnuaCameraFollow, &mut Transform)>,
+    targets_query: Query<&Transform, Without<TnuaCameraFollow>>,
+) {
+    let frame_duration = time.delta_secs();
+    for (follow, mut camera_transform) in cameras_query.iter_mut() {
+        let Ok(target_transform) = targets_query.get(follow.target) else {
+            continue;
+        };
+        let desired_translation = target_transform.translation + follow.offset;
+        let factor = if 0.0 < follow.smoothing_time_constant {
+            1.0 - (-frame_duration / follow.smoothing_time_constant).exp()
+        } else {
+            1.0
+        };
+        camera_transform.translation = camera_transform
+            .translation
+            .lerp(desired_translation, factor);
+        if let Some(look_at_offset) = follow.look_at_offset {
+            camera_transform.look_at(target_transform.translation + look_at_offset, Vec3::Y);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
+
+use crate::builtins::TnuaBuiltinJump;
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{TnuaPipelineStages, TnuaProximitySensor, TnuaToggle};
+
+pub struct TnuaCeilingSensorPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaCeilingSensorPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaCeilingSensorPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaCeilingSensor`] work.
+impl Plugin for TnuaCeilingSensorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_ceiling_sensor_system.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Detects when the character's head hits a ceiling, so that a jump can stop going up.
+///
+/// This will create a child entity with a proximity sensor pointed upward (like
+/// [`TnuaCrouchEnforcer`](crate::control_helpers::TnuaCrouchEnforcer) does). To make the jump
+/// respond to the ceiling, pass it through the component's [`sensing`](Self::sensing) method:
+///
+/// ```no_run
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaCeilingSensor;
+/// # let mut controller = TnuaController::default();
+/// # let ceiling_sensor = TnuaCeilingSensor::new(Default::default(), 0.1, |_| {});
+/// controller.action(ceiling_sensor.sensing(TnuaBuiltinJump {
+///     height: 4.0,
+///     ..Default::default()
+/// }));
+/// ```
+///
+/// Requires the [`TnuaCeilingSensorPlugin`].
+#[derive(Component)]
+pub struct TnuaCeilingSensor {
+    sensor_entity: Option<Entity>,
+    offset: Vector3,
+    range: Float,
+    modify_sensor: Box<dyn Send + Sync + Fn(&mut EntityCommands)>,
+    hit_ceiling: bool,
+}
+
+impl TnuaCeilingSensor {
+    /// Create a new ceiling sensor, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `offset` - the origin of the proximity sensor. Should be placed at the top of the
+    ///   collider. The sensor is always pointed upwards.
+    /// * `range` - the distance above the `offset` at which a ceiling is considered hit.
+    /// * `modify_sensor` - a function called with the command that creates the sensor. This
+    ///   function has the opportunity to add things to the sensor entity - mostly cast-shape
+    ///   components.
+    pub fn new(
+        offset: Vector3,
+        range: Float,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        Self {
+            sensor_entity: None,
+            offset,
+            range,
+            modify_sensor: Box::new(modify_sensor),
+            hit_ceiling: false,
+        }
+    }
+
+    /// Check if the sensor detected a ceiling this frame.
+    pub fn hit_ceiling(&self) -> bool {
+        self.hit_ceiling
+    }
+
+    /// Set the jump's [`hit_ceiling`](TnuaBuiltinJump::hit_ceiling) according to the sensor.
+    pub fn sensing(&self, jump: TnuaBuiltinJump) -> TnuaBuiltinJump {
+        TnuaBuiltinJump {
+            hit_ceiling: jump.hit_ceiling || self.hit_ceiling,
+            ..jump
+        }
+    }
+}
+
+fn update_ceiling_sensor_system(
+    mut query: Query<(Entity, &mut TnuaCeilingSensor, Option<&TnuaToggle>)>,
+    mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
+    mut commands: Commands,
+) {
+    for (owner_entity, mut ceiling_sensor, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let Ok((main_sensor, _)) = sensors_query.get(owner_entity) else {
+            continue;
+        };
+        let cast_direction = -main_sensor.cast_direction;
+
+        if let Some((mut subservient_sensor, true)) = ceiling_sensor
+            .sensor_entity
+            .and_then(|entity| sensors_query.get_mut(entity).ok())
+        {
+            ceiling_sensor.hit_ceiling = subservient_sensor.output.is_some();
+            subservient_sensor.cast_origin = ceiling_sensor.offset;
+            subservient_sensor.cast_direction = cast_direction;
+            subservient_sensor.cast_range = ceiling_sensor.range;
+        } else {
+            ceiling_sensor.hit_ceiling = false;
+            let mut cmd = commands.spawn((
+                Transform::default(),
+                TnuaSubservientSensor { owner_entity },
+                TnuaProximitySensor {
+                    cast_origin: ceiling_sensor.offset,
+                    cast_direction,
+                    cast_range: ceiling_sensor.range,
+                    ..Default::default()
+                },
+            ));
+            cmd.set_parent(owner_entity);
+            (ceiling_sensor.modify_sensor)(&mut cmd);
+            ceiling_sensor.sensor_entity = Some(cmd.id());
+        }
+    }
+}
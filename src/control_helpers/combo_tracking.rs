@@ -0,0 +1,163 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Float;
+
+use crate::controller::TnuaActionFlowStatus;
+use crate::prelude::*;
+
+/// Something that happened to the character, as recorded by [`TnuaComboTracker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaComboEvent {
+    /// An action with that name has started (possibly by cancelling another action).
+    ActionStarted(&'static str),
+
+    /// An action with that name has stopped being fed, or was cancelled into another action.
+    ActionEnded(&'static str),
+
+    /// The character was in the air and has landed.
+    Landed,
+
+    /// The character was on the ground and became airborne.
+    LeftGround,
+}
+
+/// A helper for gating special moves (e.g. dash-jump or slide-jump) on the recent history of the
+/// character.
+///
+/// It's [`update`](Self::update) must be called every frame, after the controller was applied
+/// (typically in the control system, before feeding the controller with the actions of the
+/// current frame). It records the [events](TnuaComboEvent) that happened to the character, with
+/// their times, for [`history_duration`](Self::history_duration) seconds.
+///
+/// ```no_run
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::builtins::TnuaBuiltinDash;
+/// # use bevy_tnua::control_helpers::{TnuaComboEvent, TnuaComboTracker};
+/// # let controller = TnuaController::default();
+/// # let mut combo_tracker = TnuaComboTracker::default();
+/// # let frame_duration = 1.0 / 60.0;
+/// combo_tracker.update(&controller, frame_duration);
+/// // Allow a super jump right after landing from a dash.
+/// let super_jump = combo_tracker.followed_within(
+///     TnuaComboEvent::ActionStarted(TnuaBuiltinDash::NAME),
+///     TnuaComboEvent::Landed,
+///     1.0,
+/// ) && combo_tracker
+///     .time_since(TnuaComboEvent::Landed)
+///     .is_some_and(|time| time < 0.2);
+/// ```
+#[derive(Component)]
+pub struct TnuaComboTracker {
+    /// For how long, in seconds, to remember the events.
+    ///
+    /// Queries cannot look further back than this.
+    pub history_duration: Float,
+
+    time: Float,
+    history: VecDeque<(Float, TnuaComboEvent)>,
+    was_airborne: Option<bool>,
+}
+
+impl Default for TnuaComboTracker {
+    fn default() -> Self {
+        Self {
+            history_duration: 2.0,
+            time: 0.0,
+            history: Default::default(),
+            was_airborne: None,
+        }
+    }
+}
+
+impl TnuaComboTracker {
+    /// Call this every frame to record the events.
+    pub fn update(&mut self, controller: &TnuaController, frame_duration: Float) {
+        self.time += frame_duration;
+        while self
+            .history
+            .front()
+            .is_some_and(|(time, _)| self.history_duration < self.time - time)
+        {
+            self.history.pop_front();
+        }
+
+        match controller.action_flow_status() {
+            TnuaActionFlowStatus::NoAction | TnuaActionFlowStatus::ActionOngoing(_) => {}
+            TnuaActionFlowStatus::ActionStarted(action_name) => {
+                self.record(TnuaComboEvent::ActionStarted(action_name));
+            }
+            TnuaActionFlowStatus::ActionEnded(action_name) => {
+                self.record(TnuaComboEvent::ActionEnded(action_name));
+            }
+            TnuaActionFlowStatus::Cancelled { old, new } => {
+                self.record(TnuaComboEvent::ActionEnded(old));
+                self.record(TnuaComboEvent::ActionStarted(new));
+            }
+        }
+
+        if let Some(is_airborne) = controller.dynamic_basis().map(|basis| basis.is_airborne()) {
+            match (self.was_airborne, is_airborne) {
+                (Some(false), true) => self.record(TnuaComboEvent::LeftGround),
+                (Some(true), false) => self.record(TnuaComboEvent::Landed),
+                _ => {}
+            }
+            self.was_airborne = Some(is_airborne);
+        }
+    }
+
+    fn record(&mut self, event: TnuaComboEvent) {
+        self.history.push_back((self.time, event));
+    }
+
+    /// The time, in seconds, since the last time the event happened.
+    ///
+    /// Returns `None` if the event did not happen in the last
+    /// [`history_duration`](Self::history_duration) seconds.
+    pub fn time_since(&self, event: TnuaComboEvent) -> Option<Float> {
+        self.history
+            .iter()
+            .rev()
+            .find(|(_, recorded)| *recorded == event)
+            .map(|(time, _)| self.time - time)
+    }
+
+    /// Check if the last time `then` happened, it was no more than `within` seconds after `first`
+    /// happened.
+    ///
+    /// Other events may have happened between the two.
+    pub fn followed_within(
+        &self,
+        first: TnuaComboEvent,
+        then: TnuaComboEvent,
+        within: Float,
+    ) -> bool {
+        let Some(then_index) = self
+            .history
+            .iter()
+            .rposition(|(_, recorded)| *recorded == then)
+        else {
+            return false;
+        };
+        let then_time = self.history[then_index].0;
+        self.history
+            .iter()
+            .take(then_index)
+            .rev()
+            .take_while(|(time, _)| then_time - time <= within)
+            .any(|(_, recorded)| *recorded == first)
+    }
+
+    /// The recorded events, from the oldest to the newest, together with the time (in seconds)
+    /// since each of them happened.
+    pub fn history(&self) -> impl '_ + DoubleEndedIterator<Item = (Float, TnuaComboEvent)> {
+        self.history
+            .iter()
+            .map(|(time, event)| (self.time - time, *event))
+    }
+
+    /// Forget all the recorded events.
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
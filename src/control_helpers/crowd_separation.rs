@@ -0,0 +1,143 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float, Vector3};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::{
+    TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaSystemSet, TnuaToggle,
+    TnuaUserControlsSystemSet,
+};
+
+pub struct TnuaCrowdSeparationPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaCrowdSeparationPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaCrowdSeparationPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaCrowdSeparation`] work.
+impl Plugin for TnuaCrowdSeparationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            apply_crowd_separation_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaUserControlsSystemSet)
+                .before(TnuaPipelineStages::Logic),
+        );
+    }
+}
+
+/// Makes a character softly steer away from other Tnua characters.
+///
+/// When many characters are crowded together, each controller fights for its own desired velocity
+/// and they end up pushing each other erratically. Placing this component on a character entity
+/// (one with a [`TnuaController`]) will make Tnua add a separation term to the
+/// [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) of its [`TnuaBuiltinWalk`] basis,
+/// pointing away from the other characters (any entity with a [`TnuaController`]) that are within
+/// the [`radius`](Self::radius). The closer the other character is, the stronger the separation.
+///
+/// The separation term is added on top of the basis that was fed this frame, so the basis must be
+/// fed every frame (which is how Tnua is meant to be used anyway). Otherwise, the separation term
+/// will accumulate.
+///
+/// This checks every character with this component against every other character, so it is only
+/// suitable for moderately sized crowds.
+///
+/// Requires the [`TnuaCrowdSeparationPlugin`].
+#[derive(Component)]
+pub struct TnuaCrowdSeparation {
+    /// Other characters further than this distance (measured between the entities' origins,
+    /// ignoring the up direction) do not affect this character.
+    pub radius: Float,
+
+    /// The separation speed when the other character is at the exact same position. It decreases
+    /// linearly to zero at the [`radius`](Self::radius), and the total separation term from all
+    /// the other characters never exceeds it.
+    pub strength: Float,
+}
+
+impl Default for TnuaCrowdSeparation {
+    fn default() -> Self {
+        Self {
+            radius: 1.5,
+            strength: 2.0,
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_crowd_separation_system(
+    mut query: Query<(
+        Entity,
+        &TnuaCrowdSeparation,
+        &mut TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+        Option<&TnuaToggle>,
+    )>,
+    others_query: Query<(Entity, &TnuaRigidBodyTracker), With<TnuaController>>,
+) {
+    for (entity, separation, mut controller, tracker, sensor, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly => continue,
+            TnuaToggle::Enabled => {}
+        }
+        if separation.radius <= 0.0 {
+            continue;
+        }
+        let Some(basis_name) = controller.basis_name() else {
+            continue;
+        };
+        let Some((walk, _)) = controller.concrete_basis::<TnuaBuiltinWalk>() else {
+            continue;
+        };
+
+        let up = -sensor.cast_direction.adjust_precision();
+        let mut separation_term = Vector3::ZERO;
+        for (other_entity, other_tracker) in others_query.iter() {
+            if other_entity == entity {
+                continue;
+            }
+            let offset = (tracker.translation - other_tracker.translation).reject_from(up);
+            let distance = offset.length();
+            if separation.radius <= distance {
+                continue;
+            }
+            let direction = if 0.0 < distance {
+                offset / distance
+            } else {
+                // Characters at the exact same position have nothing to separate by, so use the
+                // entities' order to at least make them separate in opposite directions.
+                let direction = up.any_orthonormal_vector();
+                if entity < other_entity {
+                    direction
+                } else {
+                    -direction
+                }
+            };
+            separation_term += (1.0 - distance / separation.radius) * direction;
+        }
+        if separation_term == Vector3::ZERO {
+            continue;
+        }
+
+        let mut walk = walk.clone();
+        walk.desired_velocity += (separation.strength * separation_term)
+            .clamp_length_max(separation.strength)
+            .reject_from(up);
+        controller.named_basis(basis_name, walk);
+    }
+}
@@ -0,0 +1,373 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{
+    AdjustPrecision, AsF32, Float, Quaternion, Vector3,
+};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{
+    TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaSystemSet, TnuaToggle,
+    TnuaUserControlsSystemSet,
+};
+
+pub struct TnuaEdgeDetectionPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaEdgeDetectionPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaEdgeDetectionPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaEdgeDetection`] work.
+impl Plugin for TnuaEdgeDetectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_edge_detection_system.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+        app.add_systems(
+            self.schedule,
+            prevent_falling_off_ledges_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaUserControlsSystemSet)
+                .before(TnuaPipelineStages::Logic),
+        );
+    }
+}
+
+/// Detects when the character is standing near a ledge, with part of its support hanging over it.
+///
+/// This will create child entities with proximity sensors pointed downward (like
+/// [`TnuaCeilingSensor`](crate::control_helpers::TnuaCeilingSensor) does) - probes placed in a
+/// ring of radius [`probe_radius`](Self::probe_radius) around the origin of the character's own
+/// proximity sensor. A probe that does not find ground (or finds it more than
+/// [`max_step_down`](Self::max_step_down) below the ground the character stands on) is considered
+/// to be hanging over a ledge.
+///
+/// The result is exposed as the [`edge_direction`](Self::edge_direction) and the
+/// [`overhang_fraction`](Self::overhang_fraction), which can be used for playing a teeter
+/// animation or for nudging the character away from the edge.
+///
+/// When [`prevent_falling_off_ledges`](Self::prevent_falling_off_ledges) is set, another probe
+/// is placed ahead of the character, and the [`desired_velocity`](TnuaBuiltinWalk::desired_velocity)
+/// of its [`TnuaBuiltinWalk`] basis is clamped so that it does not carry the character over a
+/// ledge. The basis must be fed every frame for this to work.
+///
+/// Requires the [`TnuaEdgeDetectionPlugin`].
+#[derive(Component)]
+pub struct TnuaEdgeDetection {
+    probe_entities: Vec<Entity>,
+    modify_sensor: Box<dyn Send + Sync + Fn(&mut EntityCommands)>,
+
+    /// The horizontal distance of the probes from the origin of the character's proximity sensor.
+    ///
+    /// This should usually be about the radius of the character's collider.
+    pub probe_radius: Float,
+
+    /// How much further than the ground under the character the ground under a probe can be for
+    /// the probe to still be considered supported (e.g. when standing near a small step down).
+    pub max_step_down: Float,
+
+    /// Stop the character from walking off ledges - useful for AI patrolling platforms and for
+    /// auto-running characters.
+    pub prevent_falling_off_ledges: bool,
+
+    edge_direction: Option<Dir3>,
+    overhang_fraction: Float,
+    lookahead_probe_entity: Option<Entity>,
+    lookahead_direction: Option<Dir3>,
+    ledge_ahead: Option<Dir3>,
+    fed_desired_velocity: Vector3,
+}
+
+impl TnuaEdgeDetection {
+    /// The number of probes placed around the character.
+    pub const PROBE_COUNT: usize = 8;
+
+    /// Create a new edge detection, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `probe_radius` - see [`probe_radius`](Self::probe_radius).
+    /// * `modify_sensor` - a function called with the command that creates each of the probes.
+    ///   This function has the opportunity to add things to the probe entity - mostly cast-shape
+    ///   components.
+    pub fn new(
+        probe_radius: Float,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        Self {
+            probe_entities: Vec::new(),
+            modify_sensor: Box::new(modify_sensor),
+            probe_radius,
+            max_step_down: 0.2,
+            prevent_falling_off_ledges: false,
+            edge_direction: None,
+            overhang_fraction: 0.0,
+            lookahead_probe_entity: None,
+            lookahead_direction: None,
+            ledge_ahead: None,
+            fed_desired_velocity: Vector3::ZERO,
+        }
+    }
+
+    /// The horizontal direction, from the character, of the ledge it is hanging over.
+    ///
+    /// `None` when the character is not hanging over a ledge, or when it is hanging over ledges
+    /// in opposite directions (e.g. when standing on a narrow beam).
+    pub fn edge_direction(&self) -> Option<Dir3> {
+        self.edge_direction
+    }
+
+    /// The fraction, from 0.0 to 1.0, of the probes that hang over a ledge.
+    ///
+    /// This is always zero when the character's own proximity sensor does not detect any ground.
+    pub fn overhang_fraction(&self) -> Float {
+        self.overhang_fraction
+    }
+
+    /// Check if any part of the character's support hangs over a ledge.
+    pub fn is_teetering(&self) -> bool {
+        0.0 < self.overhang_fraction
+    }
+
+    /// The horizontal direction in which the character is about to walk off a ledge.
+    ///
+    /// Only detected when [`prevent_falling_off_ledges`](Self::prevent_falling_off_ledges) is
+    /// set.
+    pub fn ledge_ahead(&self) -> Option<Dir3> {
+        self.ledge_ahead
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_edge_detection_system(
+    mut query: Query<(
+        Entity,
+        &mut TnuaEdgeDetection,
+        &TnuaRigidBodyTracker,
+        Option<&TnuaController>,
+        Option<&TnuaToggle>,
+    )>,
+    mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
+    mut commands: Commands,
+) {
+    for (owner_entity, mut edge_detection, tracker, controller, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let edge_detection = edge_detection.as_mut();
+        let Ok((main_sensor, _)) = sensors_query.get(owner_entity) else {
+            continue;
+        };
+        let cast_origin = main_sensor.cast_origin;
+        let cast_direction = main_sensor.cast_direction;
+        let cast_range = main_sensor.cast_range + edge_detection.max_step_down;
+        let main_proximity = main_sensor.output.as_ref().map(|output| output.proximity);
+
+        // The ring is laid out in the world space, but the cast origins of the probes are in the
+        // coord system of the character.
+        let up = -cast_direction.adjust_precision();
+        let first_direction = up.any_orthonormal_vector();
+        let probe_directions = (0..TnuaEdgeDetection::PROBE_COUNT).map(|index| {
+            let angle = std::f64::consts::TAU as Float * index as Float
+                / TnuaEdgeDetection::PROBE_COUNT as Float;
+            Quaternion::from_axis_angle(up, angle).mul_vec3(first_direction)
+        });
+        let probe_origins = probe_directions.clone().map(|direction| {
+            cast_origin
+                + tracker
+                    .rotation
+                    .inverse()
+                    .mul_vec3(edge_detection.probe_radius * direction)
+        });
+
+        if edge_detection.prevent_falling_off_ledges {
+            let walk =
+                controller.and_then(|controller| controller.concrete_basis::<TnuaBuiltinWalk>());
+            let velocity = controller
+                .and_then(|controller| controller.dynamic_basis())
+                .map_or(tracker.velocity, |basis| basis.effective_velocity())
+                .reject_from(up);
+            // Look where the character wants to go, or - if it does not want to go anywhere -
+            // where it is carried to. The desired velocity in the basis was already clamped, so
+            // the one that was fed is used instead - otherwise the probe will stop looking at the
+            // ledge as soon as the character stops.
+            let lookahead_direction =
+                Dir3::new(edge_detection.fed_desired_velocity.reject_from(up).f32())
+                    .ok()
+                    .or_else(|| Dir3::new(velocity.f32()).ok());
+            // Place the probe where the character would stop if it started braking now.
+            let speed = lookahead_direction.map_or(0.0, |direction| {
+                velocity.dot(direction.adjust_precision()).max(0.0)
+            });
+            let braking_distance = match walk {
+                Some((walk, _)) if 0.0 < walk.acceleration => {
+                    speed.powi(2) / (2.0 * walk.acceleration)
+                }
+                _ => 0.0,
+            };
+            let lookahead_origin = cast_origin
+                + tracker.rotation.inverse().mul_vec3(
+                    (edge_detection.probe_radius + braking_distance)
+                        * lookahead_direction
+                            .map_or(Vector3::ZERO, |direction| direction.adjust_precision()),
+                );
+
+            if let Some((mut probe, true)) = edge_detection
+                .lookahead_probe_entity
+                .and_then(|entity| sensors_query.get_mut(entity).ok())
+            {
+                // The output is of the cast from the previous frame, so it is checked against the
+                // direction of that cast.
+                edge_detection.ledge_ahead =
+                    match (main_proximity, edge_detection.lookahead_direction) {
+                        (Some(main_proximity), Some(direction)) => {
+                            let supported = probe.output.as_ref().is_some_and(|output| {
+                                output.proximity <= main_proximity + edge_detection.max_step_down
+                            });
+                            (!supported).then_some(direction)
+                        }
+                        _ => None,
+                    };
+                probe.cast_origin = lookahead_origin;
+                probe.cast_direction = cast_direction;
+                probe.cast_range = cast_range;
+            } else {
+                edge_detection.ledge_ahead = None;
+                let mut cmd = commands.spawn((
+                    Transform::default(),
+                    TnuaSubservientSensor { owner_entity },
+                    TnuaProximitySensor {
+                        cast_origin: lookahead_origin,
+                        cast_direction,
+                        cast_range,
+                        ..Default::default()
+                    },
+                ));
+                cmd.set_parent(owner_entity);
+                (edge_detection.modify_sensor)(&mut cmd);
+                edge_detection.lookahead_probe_entity = Some(cmd.id());
+            }
+            edge_detection.lookahead_direction = lookahead_direction;
+        } else {
+            edge_detection.ledge_ahead = None;
+            edge_detection.lookahead_direction = None;
+        }
+
+        if edge_detection.probe_entities.len() != TnuaEdgeDetection::PROBE_COUNT {
+            edge_detection.edge_direction = None;
+            edge_detection.overhang_fraction = 0.0;
+            for probe_origin in probe_origins {
+                let mut cmd = commands.spawn((
+                    Transform::default(),
+                    TnuaSubservientSensor { owner_entity },
+                    TnuaProximitySensor {
+                        cast_origin: probe_origin,
+                        cast_direction,
+                        cast_range,
+                        ..Default::default()
+                    },
+                ));
+                cmd.set_parent(owner_entity);
+                (edge_detection.modify_sensor)(&mut cmd);
+                edge_detection.probe_entities.push(cmd.id());
+            }
+            continue;
+        }
+
+        let mut overhanging_probes = 0;
+        let mut edge_direction_sum = Vector3::ZERO;
+        for ((probe_entity, direction), probe_origin) in edge_detection
+            .probe_entities
+            .iter()
+            .zip(probe_directions)
+            .zip(probe_origins)
+        {
+            let Ok((mut probe, true)) = sensors_query.get_mut(*probe_entity) else {
+                continue;
+            };
+            if let Some(main_proximity) = main_proximity {
+                let supported = probe.output.as_ref().is_some_and(|output| {
+                    output.proximity <= main_proximity + edge_detection.max_step_down
+                });
+                if !supported {
+                    overhanging_probes += 1;
+                    edge_direction_sum += direction;
+                }
+            }
+            probe.cast_origin = probe_origin;
+            probe.cast_direction = cast_direction;
+            probe.cast_range = cast_range;
+        }
+        edge_detection.overhang_fraction =
+            overhanging_probes as Float / TnuaEdgeDetection::PROBE_COUNT as Float;
+        // Probes on opposite sides cancel each other out, and with floating point errors the sum
+        // may not be exactly zero.
+        edge_detection.edge_direction = if 0.5 <= edge_direction_sum.length() {
+            Dir3::new(edge_direction_sum.f32()).ok()
+        } else {
+            None
+        };
+    }
+}
+
+fn prevent_falling_off_ledges_system(
+    mut query: Query<(
+        &mut TnuaEdgeDetection,
+        &mut TnuaController,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    for (mut edge_detection, mut controller, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly => continue,
+            TnuaToggle::Enabled => {}
+        }
+        if !edge_detection.prevent_falling_off_ledges {
+            continue;
+        }
+        let Some(basis_name) = controller.basis_name() else {
+            continue;
+        };
+        let Some((walk, _)) = controller.concrete_basis::<TnuaBuiltinWalk>() else {
+            edge_detection.fed_desired_velocity = Vector3::ZERO;
+            continue;
+        };
+        edge_detection.fed_desired_velocity = walk.desired_velocity;
+
+        let mut desired_velocity = walk.desired_velocity;
+        for ledge_direction in [edge_detection.ledge_ahead, edge_detection.edge_direction]
+            .into_iter()
+            .flatten()
+        {
+            let ledge_direction = ledge_direction.adjust_precision();
+            let toward_ledge = desired_velocity.dot(ledge_direction);
+            if 0.0 < toward_ledge {
+                desired_velocity -= toward_ledge * ledge_direction;
+            }
+        }
+        if desired_velocity == walk.desired_velocity {
+            continue;
+        }
+
+        let mut walk = walk.clone();
+        walk.desired_velocity = desired_velocity;
+        controller.named_basis(basis_name, walk);
+    }
+}
@@ -0,0 +1,215 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{Float, Quaternion, Vector3};
+
+use crate::util::rotation_arc_with_fallback_axis;
+use crate::{TnuaMotor, TnuaPipelineStages, TnuaRigidBodyTracker, TnuaSystemSet, TnuaToggle};
+
+pub struct TnuaGravityTransitionPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaGravityTransitionPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaGravityTransitionPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaGravityTransition`] work.
+impl Plugin for TnuaGravityTransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TnuaGravityTransitionEvent>();
+        app.add_systems(
+            self.schedule,
+            override_tracker_gravity_system.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+        app.add_systems(
+            self.schedule,
+            compensate_physics_gravity_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Logic)
+                .before(TnuaPipelineStages::Motors),
+        );
+    }
+}
+
+/// Gives a character its own gravity, and smoothly transitions it to a new one.
+///
+/// Tnua gets the gravity from the physics backend, and changing the physics gravity changes it
+/// instantly - which snaps the character's up direction (and everything that depends on it) in a
+/// single frame. With this component on the character entity, the basis and the actions see the
+/// gravity of the component instead of the physics gravity, and the difference between the two is
+/// added to the motor. Use [`transition_to`](Self::transition_to) to start a transition - the
+/// direction of the gravity will be rotated toward the new direction and its magnitude will be
+/// interpolated linearly, so that the up direction of the controller follows the gravity smoothly
+/// during the transition.
+///
+/// A [`TnuaGravityTransitionEvent`] is sent when a transition starts and when it ends.
+///
+/// Requires the [`TnuaGravityTransitionPlugin`].
+#[derive(Component, Default)]
+pub struct TnuaGravityTransition {
+    gravity: Option<Vector3>,
+    physics_gravity: Vector3,
+    requested: Option<(Vector3, Float)>,
+    transition: Option<GravityTransitionState>,
+}
+
+struct GravityTransitionState {
+    from: Vector3,
+    to: Vector3,
+    duration: Float,
+    elapsed: Float,
+}
+
+impl TnuaGravityTransition {
+    /// Start transitioning the character's gravity to `gravity`, over `duration` seconds.
+    ///
+    /// The transition starts from the current gravity of the character - which may be in the
+    /// middle of another transition. A `duration` of zero changes the gravity immediately.
+    pub fn transition_to(&mut self, gravity: Vector3, duration: Float) {
+        self.requested = Some((gravity, duration));
+    }
+
+    /// The gravity the character currently has.
+    ///
+    /// Before the first transition starts, this is `None` and the character uses the physics
+    /// gravity.
+    pub fn gravity(&self) -> Option<Vector3> {
+        self.gravity
+    }
+
+    /// Check if the gravity is in the middle of a transition.
+    pub fn is_transitioning(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// How far, from 0.0 to 1.0, the current transition went. `None` if there is no transition.
+    pub fn progress(&self) -> Option<Float> {
+        let transition = self.transition.as_ref()?;
+        Some(if 0.0 < transition.duration {
+            (transition.elapsed / transition.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        })
+    }
+}
+
+/// Sent by [`TnuaGravityTransition`] when a gravity transition starts or ends.
+#[derive(Event, Debug, Clone)]
+pub enum TnuaGravityTransitionEvent {
+    Started {
+        /// The character entity.
+        entity: Entity,
+        /// The gravity at the start of the transition.
+        from: Vector3,
+        /// The gravity at the end of the transition.
+        to: Vector3,
+    },
+    Ended {
+        /// The character entity.
+        entity: Entity,
+        /// The gravity the character has after the transition.
+        gravity: Vector3,
+    },
+}
+
+/// Rotate the direction from `from` to `to`, and interpolate the magnitude linearly.
+fn interpolate_gravity(from: Vector3, to: Vector3, factor: Float) -> Vector3 {
+    let (Some(from_direction), Some(to_direction)) = (from.try_normalize(), to.try_normalize())
+    else {
+        return from.lerp(to, factor);
+    };
+    // Flipping the gravity should rotate it around the Z axis when possible, so that 2D gravity
+    // does not leave the XY plane.
+    let fallback_axis = Vector3::Z
+        .reject_from(from_direction)
+        .try_normalize()
+        .unwrap_or_else(|| from_direction.any_orthonormal_vector());
+    let rotation = Quaternion::IDENTITY.slerp(
+        rotation_arc_with_fallback_axis(from_direction, to_direction, fallback_axis),
+        factor,
+    );
+    let magnitude = from.length() + (to.length() - from.length()) * factor;
+    magnitude * rotation.mul_vec3(from_direction)
+}
+
+fn override_tracker_gravity_system(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut TnuaGravityTransition,
+        &mut TnuaRigidBodyTracker,
+        Option<&TnuaToggle>,
+    )>,
+    mut event_writer: EventWriter<TnuaGravityTransitionEvent>,
+) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
+    for (entity, mut gravity_transition, mut tracker, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let gravity_transition = gravity_transition.as_mut();
+        gravity_transition.physics_gravity = tracker.gravity;
+        let current_gravity = gravity_transition.gravity.unwrap_or(tracker.gravity);
+
+        if let Some((to, duration)) = gravity_transition.requested.take() {
+            gravity_transition.transition = Some(GravityTransitionState {
+                from: current_gravity,
+                to,
+                duration,
+                elapsed: 0.0,
+            });
+            event_writer.send(TnuaGravityTransitionEvent::Started {
+                entity,
+                from: current_gravity,
+                to,
+            });
+        } else if let Some(transition) = gravity_transition.transition.as_mut() {
+            transition.elapsed += frame_duration;
+        }
+
+        if let Some(transition) = gravity_transition.transition.as_ref() {
+            if transition.elapsed < transition.duration {
+                gravity_transition.gravity = Some(interpolate_gravity(
+                    transition.from,
+                    transition.to,
+                    transition.elapsed / transition.duration,
+                ));
+            } else {
+                let gravity = transition.to;
+                gravity_transition.gravity = Some(gravity);
+                gravity_transition.transition = None;
+                event_writer.send(TnuaGravityTransitionEvent::Ended { entity, gravity });
+            }
+        }
+        if let Some(gravity) = gravity_transition.gravity {
+            tracker.gravity = gravity;
+        }
+    }
+}
+
+fn compensate_physics_gravity_system(
+    mut query: Query<(&TnuaGravityTransition, &mut TnuaMotor, Option<&TnuaToggle>)>,
+) {
+    for (gravity_transition, mut motor, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly => continue,
+            TnuaToggle::Enabled => {}
+        }
+        let Some(gravity) = gravity_transition.gravity else {
+            continue;
+        };
+        motor.lin.acceleration += gravity - gravity_transition.physics_gravity;
+    }
+}
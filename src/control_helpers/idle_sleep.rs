@@ -0,0 +1,161 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Float;
+
+use crate::controller::TnuaController;
+use crate::{
+    TnuaMotor, TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaSystemSet,
+    TnuaToggle,
+};
+
+pub struct TnuaIdleSleepPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaIdleSleepPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaIdleSleepPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaIdleSleep`] work.
+impl Plugin for TnuaIdleSleepPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_idle_sleep_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Logic)
+                .before(TnuaPipelineStages::Motors),
+        );
+    }
+}
+
+/// Stops casting the proximity sensor of characters that are standing still.
+///
+/// Placing this component on a character entity (one with a [`TnuaController`]) will make Tnua
+/// track whether the character is idle - not moving, not performing any action, not trying to
+/// move and not standing on a moving platform. Once it has been idle for
+/// [`frames_before_sleep`](Self::frames_before_sleep) consecutive frames, the character falls
+/// asleep, and the physics backend will skip casting its sensor (see
+/// [`TnuaProximitySensor::skip_next_cast`]), leaving the controller to use the last sensor output.
+///
+/// The controller logic keeps running while the character is asleep, so the character reacts
+/// immediately to new input. Any input, action or external disturbance (e.g. the character gets
+/// pushed) will wake the character up. Because a sleeping character cannot see changes in the
+/// ground below it, the sensor is still cast once every
+/// [`refresh_interval`](Self::refresh_interval) frames, and any change in its output will also
+/// wake the character up.
+///
+/// Requires the [`TnuaIdleSleepPlugin`].
+#[derive(Component)]
+pub struct TnuaIdleSleep {
+    /// The number of consecutive idle frames after which the character falls asleep.
+    pub frames_before_sleep: usize,
+
+    /// Velocities (of the character, its motor and the ground it stands on) below this are
+    /// considered as no movement.
+    pub velocity_threshold: Float,
+
+    /// While asleep, the sensor is still cast once every this many frames. `0` means that the
+    /// sensor is never cast while the character is asleep.
+    pub refresh_interval: usize,
+
+    idle_frames: usize,
+    last_sensor_output: Option<(Entity, Float)>,
+}
+
+impl Default for TnuaIdleSleep {
+    fn default() -> Self {
+        Self {
+            frames_before_sleep: 30,
+            velocity_threshold: 0.01,
+            refresh_interval: 10,
+            idle_frames: 0,
+            last_sensor_output: None,
+        }
+    }
+}
+
+impl TnuaIdleSleep {
+    /// Check if the character is currently asleep.
+    pub fn is_asleep(&self) -> bool {
+        self.frames_before_sleep <= self.idle_frames
+    }
+
+    /// Wake the character up, making it recast its sensor on the next frame.
+    ///
+    /// The character will fall asleep again after being idle for
+    /// [`frames_before_sleep`](Self::frames_before_sleep) more frames.
+    pub fn wake_up(&mut self) {
+        self.idle_frames = 0;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_idle_sleep_system(
+    mut query: Query<(
+        &mut TnuaIdleSleep,
+        &TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaMotor,
+        &mut TnuaProximitySensor,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    for (mut idle_sleep, controller, tracker, motor, mut sensor, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly => {
+                idle_sleep.wake_up();
+                continue;
+            }
+            TnuaToggle::Enabled => {}
+        }
+
+        let threshold = idle_sleep.velocity_threshold;
+        let sensor_output = sensor
+            .output
+            .as_ref()
+            .map(|output| (output.entity, output.proximity));
+        let is_idle = controller.action_name().is_none()
+            && tracker.velocity.length() < threshold
+            && tracker.angvel.length() < threshold
+            && motor.lin.boost.length() < threshold
+            && motor.ang.boost.length() < threshold
+            && sensor.output.as_ref().is_some_and(|output| {
+                output.entity_linvel.length() < threshold
+                    && output.entity_angvel.length() < threshold
+            })
+            && match (idle_sleep.last_sensor_output, sensor_output) {
+                (Some((last_entity, last_proximity)), Some((entity, proximity))) => {
+                    last_entity == entity && (proximity - last_proximity).abs() < threshold
+                }
+                _ => false,
+            };
+        idle_sleep.last_sensor_output = sensor_output;
+
+        if is_idle {
+            idle_sleep.idle_frames = idle_sleep.idle_frames.saturating_add(1);
+        } else {
+            idle_sleep.wake_up();
+        }
+
+        if let Some(frames_asleep) = idle_sleep
+            .idle_frames
+            .checked_sub(idle_sleep.frames_before_sleep)
+        {
+            let refresh_interval = idle_sleep.refresh_interval;
+            if refresh_interval == 0 || (frames_asleep + 1) % refresh_interval != 0 {
+                sensor.skip_next_cast = true;
+            }
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use std::sync::Arc;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Float;
+
+use crate::controller::TnuaController;
+use crate::{
+    TnuaAction, TnuaBasis, TnuaPipelineStages, TnuaSystemSet, TnuaToggle, TnuaUserControlsSystemSet,
+};
+
+pub struct TnuaInputRecordingPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaInputRecordingPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaInputRecordingPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaInputRecorder`] and [`TnuaInputReplayer`] work.
+impl Plugin for TnuaInputRecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            (
+                replay_inputs_system.in_set(TnuaUserControlsSystemSet),
+                record_inputs_system
+                    .in_set(TnuaSystemSet)
+                    .after(TnuaUserControlsSystemSet)
+                    .before(TnuaPipelineStages::Logic),
+            ),
+        );
+    }
+}
+
+type RecordedFeed = Arc<dyn Fn(&mut TnuaController) + Send + Sync>;
+
+/// The inputs fed to a controller over a span of frames, recorded by [`TnuaInputRecorder`] and
+/// played back by [`TnuaInputReplayer`].
+///
+/// Since the basis and the actions can be of any type, the recording is kept in memory and cannot
+/// be saved to a file. Cloning it is cheap.
+#[derive(Clone, Default)]
+pub struct TnuaInputRecording {
+    frames: Arc<Vec<Vec<RecordedFeed>>>,
+}
+
+impl TnuaInputRecording {
+    /// The number of recorded frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Check if no frames were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+/// Records the inputs fed to a character's controller, so that they can be played back with
+/// [`TnuaInputReplayer`].
+///
+/// Place this component on a character entity (one with a [`TnuaController`]), and feed the basis
+/// and the actions through its methods (which mirror the feeding methods of [`TnuaController`])
+/// instead of directly through the controller. The recorder feeds the inputs to the controller,
+/// and keeps a copy of them for every frame - including frames where nothing was fed.
+///
+/// Use [`take_recording`](Self::take_recording) to get the recording.
+///
+/// Requires the [`TnuaInputRecordingPlugin`].
+#[derive(Component, Default)]
+pub struct TnuaInputRecorder {
+    frames: Vec<Vec<RecordedFeed>>,
+    current_frame: Vec<RecordedFeed>,
+}
+
+impl TnuaInputRecorder {
+    /// Feed a basis to the controller and record it. See [`TnuaController::basis`].
+    pub fn basis<B: TnuaBasis + Clone>(&mut self, controller: &mut TnuaController, basis: B) {
+        self.named_basis(controller, B::NAME, basis);
+    }
+
+    /// Feed a basis with a custom name to the controller and record it. See
+    /// [`TnuaController::named_basis`].
+    pub fn named_basis<B: TnuaBasis + Clone>(
+        &mut self,
+        controller: &mut TnuaController,
+        name: &'static str,
+        basis: B,
+    ) {
+        self.record_feed(controller, move |controller| {
+            controller.named_basis(name, basis.clone())
+        });
+    }
+
+    /// Feed an action to the controller and record it. See [`TnuaController::action`].
+    pub fn action<A: TnuaAction + Clone>(&mut self, controller: &mut TnuaController, action: A) {
+        self.named_action(controller, A::NAME, action);
+    }
+
+    /// Feed an action with a custom name to the controller and record it. See
+    /// [`TnuaController::named_action`].
+    pub fn named_action<A: TnuaAction + Clone>(
+        &mut self,
+        controller: &mut TnuaController,
+        name: &'static str,
+        action: A,
+    ) {
+        self.record_feed(controller, move |controller| {
+            controller.named_action(name, action.clone())
+        });
+    }
+
+    /// Feed a buffered action to the controller and record it. See
+    /// [`TnuaController::buffered_action`].
+    pub fn buffered_action<A: TnuaAction + Clone>(
+        &mut self,
+        controller: &mut TnuaController,
+        action: A,
+        buffer_time: Float,
+    ) {
+        self.named_buffered_action(controller, A::NAME, action, buffer_time);
+    }
+
+    /// Feed a buffered action with a custom name to the controller and record it. See
+    /// [`TnuaController::named_buffered_action`].
+    pub fn named_buffered_action<A: TnuaAction + Clone>(
+        &mut self,
+        controller: &mut TnuaController,
+        name: &'static str,
+        action: A,
+        buffer_time: Float,
+    ) {
+        self.record_feed(controller, move |controller| {
+            controller.named_buffered_action(name, action.clone(), buffer_time)
+        });
+    }
+
+    fn record_feed(
+        &mut self,
+        controller: &mut TnuaController,
+        feed: impl Fn(&mut TnuaController) + Send + Sync + 'static,
+    ) {
+        feed(controller);
+        self.current_frame.push(Arc::new(feed));
+    }
+
+    /// Return everything recorded so far, and start a new recording.
+    pub fn take_recording(&mut self) -> TnuaInputRecording {
+        TnuaInputRecording {
+            frames: Arc::new(std::mem::take(&mut self.frames)),
+        }
+    }
+}
+
+/// Plays back a [`TnuaInputRecording`] into a character's controller.
+///
+/// Place this component on a character entity (one with a [`TnuaController`]) instead of feeding
+/// it from the game's controls. On every frame, the inputs recorded for the corresponding frame
+/// will be fed to the controller, until the recording runs out.
+///
+/// The playback is deterministic as long as the character starts from the same state as it was in
+/// when the recording started, and Tnua runs in a fixed schedule (e.g.
+/// [`FixedUpdate`](bevy::app::FixedUpdate)) - both when recording and when playing back.
+///
+/// Requires the [`TnuaInputRecordingPlugin`].
+#[derive(Component)]
+pub struct TnuaInputReplayer {
+    recording: TnuaInputRecording,
+    frame: usize,
+}
+
+impl TnuaInputReplayer {
+    pub fn new(recording: TnuaInputRecording) -> Self {
+        Self {
+            recording,
+            frame: 0,
+        }
+    }
+
+    /// The number of frames already played back.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Check if the entire recording was played back.
+    pub fn is_finished(&self) -> bool {
+        self.recording.len() <= self.frame
+    }
+}
+
+fn record_inputs_system(mut query: Query<(&mut TnuaInputRecorder, Option<&TnuaToggle>)>) {
+    for (mut recorder, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let frame = std::mem::take(&mut recorder.current_frame);
+        recorder.frames.push(frame);
+    }
+}
+
+fn replay_inputs_system(
+    mut query: Query<(
+        &mut TnuaInputReplayer,
+        &mut TnuaController,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    for (mut replayer, mut controller, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let Some(frame) = replayer.recording.frames.get(replayer.frame) else {
+            continue;
+        };
+        for feed in frame.iter() {
+            feed(&mut controller);
+        }
+        replayer.frame += 1;
+    }
+}
@@ -0,0 +1,133 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float, Quaternion, Vector3};
+
+use crate::controller::TnuaController;
+use crate::{TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaSystemSet};
+
+pub struct TnuaInterpolatedOutputsPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaInterpolatedOutputsPlugin {
+    /// The `schedule` is the fixed schedule Tnua runs in. The interpolated outputs are always
+    /// updated in [`Update`].
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+/// Unlike most Tnua plugins, this one defaults to [`FixedUpdate`] - because there is no point in
+/// interpolating the outputs when Tnua runs in [`Update`].
+impl Default for TnuaInterpolatedOutputsPlugin {
+    fn default() -> Self {
+        Self::new(FixedUpdate)
+    }
+}
+
+/// A plugin required for making [`TnuaInterpolatedOutputs`] work.
+impl Plugin for TnuaInterpolatedOutputsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            record_outputs_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Logic),
+        );
+        app.add_systems(Update, interpolate_outputs_system);
+    }
+}
+
+/// Outputs of the controller, interpolated between the fixed updates for smooth rendering.
+///
+/// When Tnua runs in [`FixedUpdate`] and the game renders at a higher rate, animations and facing
+/// that are driven directly from the controller change only on the frames where the fixed update
+/// runs, which makes them stutter. Placing this component on a character entity (one with a
+/// [`TnuaController`]) will make Tnua record these outputs on every fixed update, and update the
+/// fields of this component on every [`Update`] by interpolating between the last two recorded
+/// states according to [`Time<Fixed>::overstep_fraction`].
+///
+/// Note that, as with any interpolation, the values lag behind the simulation by up to one fixed
+/// timestep.
+///
+/// Requires the [`TnuaInterpolatedOutputsPlugin`].
+#[derive(Component, Default)]
+pub struct TnuaInterpolatedOutputs {
+    /// The velocity of the character relative to what it stands on, without its vertical
+    /// component.
+    pub running_velocity: Vector3,
+
+    /// The velocity of the character along the up direction, relative to what it stands on.
+    pub vertical_velocity: Float,
+
+    /// The rotation of the character, which determines its facing.
+    pub rotation: Quaternion,
+
+    previous: Option<OutputsSnapshot>,
+    current: Option<OutputsSnapshot>,
+}
+
+#[derive(Clone, Copy)]
+struct OutputsSnapshot {
+    running_velocity: Vector3,
+    vertical_velocity: Float,
+    rotation: Quaternion,
+}
+
+impl OutputsSnapshot {
+    fn interpolate(&self, other: &Self, factor: Float) -> Self {
+        Self {
+            running_velocity: self.running_velocity.lerp(other.running_velocity, factor),
+            vertical_velocity: self.vertical_velocity.lerp(other.vertical_velocity, factor),
+            rotation: self.rotation.slerp(other.rotation, factor),
+        }
+    }
+}
+
+fn record_outputs_system(
+    mut query: Query<(
+        &mut TnuaInterpolatedOutputs,
+        &TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+    )>,
+) {
+    for (mut outputs, controller, tracker, sensor) in query.iter_mut() {
+        let (running_velocity, vertical_velocity) = if let Some(basis) = controller.dynamic_basis()
+        {
+            let up = -sensor.cast_direction.adjust_precision();
+            let effective_velocity = basis.effective_velocity();
+            (
+                effective_velocity.reject_from(up),
+                basis.vertical_velocity(),
+            )
+        } else {
+            (Vector3::ZERO, 0.0)
+        };
+        outputs.previous = outputs.current;
+        outputs.current = Some(OutputsSnapshot {
+            running_velocity,
+            vertical_velocity,
+            rotation: tracker.rotation,
+        });
+    }
+}
+
+fn interpolate_outputs_system(
+    time: Res<Time<Fixed>>,
+    mut query: Query<&mut TnuaInterpolatedOutputs>,
+) {
+    let factor = time.overstep_fraction().adjust_precision();
+    for mut outputs in query.iter_mut() {
+        let snapshot = match (outputs.previous, outputs.current) {
+            (Some(previous), Some(current)) => previous.interpolate(&current, factor),
+            (None, Some(current)) => current,
+            (_, None) => continue,
+        };
+        outputs.running_velocity = snapshot.running_velocity;
+        outputs.vertical_velocity = snapshot.vertical_velocity;
+        outputs.rotation = snapshot.rotation;
+    }
+}
@@ -0,0 +1,197 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float, Vector3};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaToggle};
+
+pub struct TnuaLandingPredictionPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaLandingPredictionPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaLandingPredictionPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaLandingPrediction`] work.
+impl Plugin for TnuaLandingPredictionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_landing_prediction_system.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Predicts when and where an airborne character will land.
+///
+/// This will create a child entity with a long-range proximity sensor pointed downward (like
+/// [`TnuaCeilingSensor`](crate::control_helpers::TnuaCeilingSensor) does), and combine the
+/// distance to the ground it detects with the velocity and the gravity of the character to
+/// calculate the [time to land](Self::time_to_land) and the [landing
+/// position](Self::landing_position). This can be used for playing a landing-anticipation
+/// animation, or by AI deciding whether to perform an air action.
+///
+/// The prediction assumes that the ground at the landing position is at the same height as the
+/// ground currently below the character, and that the character will not be affected by anything
+/// other than the gravity until it lands.
+///
+/// Requires the [`TnuaLandingPredictionPlugin`].
+#[derive(Component)]
+pub struct TnuaLandingPrediction {
+    sensor_entity: Option<Entity>,
+    range: Float,
+    modify_sensor: Box<dyn Send + Sync + Fn(&mut EntityCommands)>,
+
+    /// The distance from the origin of the character's proximity sensor to the ground at which
+    /// the character is considered to have landed.
+    ///
+    /// When `None`, the [`float_height`](TnuaBuiltinWalk::float_height) of the walk basis is used
+    /// (or zero, if the basis is not [`TnuaBuiltinWalk`]).
+    pub landing_distance: Option<Float>,
+
+    prediction: Option<(Float, Vector3)>,
+}
+
+impl TnuaLandingPrediction {
+    /// Create a new landing prediction, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `range` - the maximal distance below the character at which ground is detected. Landings
+    ///   further down than that are not predicted.
+    /// * `modify_sensor` - a function called with the command that creates the sensor. This
+    ///   function has the opportunity to add things to the sensor entity - mostly cast-shape
+    ///   components.
+    pub fn new(
+        range: Float,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        Self {
+            sensor_entity: None,
+            range,
+            modify_sensor: Box::new(modify_sensor),
+            landing_distance: None,
+            prediction: None,
+        }
+    }
+
+    /// The time, in seconds, until the character lands.
+    ///
+    /// This is (close to) zero when the character is on the ground, and `None` if no landing was
+    /// predicted (e.g. there is no ground within the range, or the character is moving away from
+    /// it).
+    pub fn time_to_land(&self) -> Option<Float> {
+        Some(self.prediction?.0)
+    }
+
+    /// Where the character (its position, not the ground under it) is predicted to be when it
+    /// lands.
+    pub fn landing_position(&self) -> Option<Vector3> {
+        Some(self.prediction?.1)
+    }
+
+    /// Check if the character is predicted to land within the given number of seconds.
+    pub fn is_landing_within(&self, seconds: Float) -> bool {
+        self.time_to_land()
+            .is_some_and(|time_to_land| time_to_land <= seconds)
+    }
+}
+
+/// The time it takes to fall `distance`, starting at `velocity` with `gravity` (both positive
+/// when pointing down).
+fn time_to_fall(distance: Float, velocity: Float, gravity: Float) -> Option<Float> {
+    if distance <= 0.0 {
+        return Some(0.0);
+    }
+    if gravity == 0.0 {
+        return (0.0 < velocity).then(|| distance / velocity);
+    }
+    let discriminant = velocity.powi(2) + 2.0 * gravity * distance;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let time = (-velocity + discriminant.sqrt()) / gravity;
+    (0.0 <= time).then_some(time)
+}
+
+#[allow(clippy::type_complexity)]
+fn update_landing_prediction_system(
+    mut query: Query<(
+        Entity,
+        &mut TnuaLandingPrediction,
+        &TnuaRigidBodyTracker,
+        Option<&TnuaController>,
+        Option<&TnuaToggle>,
+    )>,
+    mut sensors_query: Query<(&mut TnuaProximitySensor, Has<TnuaSubservientSensor>)>,
+    mut commands: Commands,
+) {
+    for (owner_entity, mut landing_prediction, tracker, controller, tnua_toggle) in query.iter_mut()
+    {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let Ok((main_sensor, _)) = sensors_query.get(owner_entity) else {
+            continue;
+        };
+        let cast_origin = main_sensor.cast_origin;
+        let cast_direction = main_sensor.cast_direction;
+
+        if let Some((mut subservient_sensor, true)) = landing_prediction
+            .sensor_entity
+            .and_then(|entity| sensors_query.get_mut(entity).ok())
+        {
+            let landing_distance = landing_prediction.landing_distance.unwrap_or_else(|| {
+                controller
+                    .and_then(|controller| controller.concrete_basis::<TnuaBuiltinWalk>())
+                    .map_or(0.0, |(walk, _)| walk.float_height)
+            });
+            let down = cast_direction.adjust_precision();
+            landing_prediction.prediction = subservient_sensor.output.as_ref().and_then(|output| {
+                let time_to_land = time_to_fall(
+                    output.proximity - landing_distance,
+                    tracker.velocity.dot(down),
+                    tracker.gravity.dot(down),
+                )?;
+                let landing_position = tracker.translation
+                    + time_to_land * tracker.velocity
+                    + 0.5 * time_to_land.powi(2) * tracker.gravity;
+                Some((time_to_land, landing_position))
+            });
+            subservient_sensor.cast_origin = cast_origin;
+            subservient_sensor.cast_direction = cast_direction;
+            subservient_sensor.cast_range = landing_prediction.range;
+        } else {
+            landing_prediction.prediction = None;
+            let mut cmd = commands.spawn((
+                Transform::default(),
+                TnuaSubservientSensor { owner_entity },
+                TnuaProximitySensor {
+                    cast_origin,
+                    cast_direction,
+                    cast_range: landing_prediction.range,
+                    ..Default::default()
+                },
+            ));
+            cmd.set_parent(owner_entity);
+            (landing_prediction.modify_sensor)(&mut cmd);
+            landing_prediction.sensor_entity = Some(cmd.id());
+        }
+    }
+}
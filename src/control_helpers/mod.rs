@@ -7,9 +7,49 @@
 //! be complex to use. This module provides helpers that allow using these features in an easier
 //! although less flexible way.
 mod air_actions_tracking;
+mod bunny_hop;
+mod camera_follow;
+mod ceiling_sensor;
+mod combo_tracking;
 mod crouch_enforcer;
+mod crowd_separation;
+mod edge_detection;
+mod gravity_transition;
+mod idle_sleep;
+mod input_recording;
+mod interpolated_outputs;
+mod landing_prediction;
+mod plane_lock;
+mod ragdoll_handoff;
+mod sensor_history;
+mod sensor_lod;
+mod sensor_normal_smoothing;
+mod side_scroll_facing;
 mod simple_fall_through_platforms;
+mod stats_tracker;
+mod trajectory_simulation;
+mod walk_extrapolation;
 
 pub use air_actions_tracking::*;
+pub use bunny_hop::*;
+pub use camera_follow::*;
+pub use ceiling_sensor::*;
+pub use combo_tracking::*;
 pub use crouch_enforcer::*;
+pub use crowd_separation::*;
+pub use edge_detection::*;
+pub use gravity_transition::*;
+pub use idle_sleep::*;
+pub use input_recording::*;
+pub use interpolated_outputs::*;
+pub use landing_prediction::*;
+pub use plane_lock::*;
+pub use ragdoll_handoff::*;
+pub use sensor_history::*;
+pub use sensor_lod::*;
+pub use sensor_normal_smoothing::*;
+pub use side_scroll_facing::*;
 pub use simple_fall_through_platforms::*;
+pub use stats_tracker::*;
+pub use trajectory_simulation::*;
+pub use walk_extrapolation::*;
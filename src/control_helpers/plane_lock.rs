@@ -0,0 +1,203 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::{
+    TnuaMotor, TnuaPipelineStages, TnuaRigidBodyTracker, TnuaSystemSet, TnuaToggle,
+    TnuaUserControlsSystemSet,
+};
+
+pub struct TnuaPlaneLockPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaPlaneLockPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaPlaneLockPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaPlaneLock`] work.
+impl Plugin for TnuaPlaneLockPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            project_desired_velocity_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaUserControlsSystemSet)
+                .before(TnuaPipelineStages::Logic),
+        );
+        app.add_systems(
+            self.schedule,
+            lock_motor_to_plane_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Logic)
+                .before(TnuaPipelineStages::Motors),
+        );
+    }
+}
+
+/// Constrains a character to a plane, for 2.5D games.
+///
+/// Placing this component on a character entity (one with a [`TnuaController`]) will make Tnua:
+///
+/// * Project the [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) and the
+///   [`desired_forward`](TnuaBuiltinWalk::desired_forward) of its [`TnuaBuiltinWalk`] basis onto
+///   the plane, so that the basis does not waste its acceleration on movement it cannot perform.
+/// * Remove from the motor everything that moves the character along the plane's
+///   [`normal`](Self::normal), and instead make it move the character back to the plane if
+///   something (e.g. a collision, or an action) pushed it away.
+///
+/// The plane is defined by its normal and its [`offset`](Self::offset) - the distance from the
+/// world origin along the normal. Use [`switch_lane_to`](Self::switch_lane_to) to move the plane
+/// (and the character with it) smoothly to a new offset, at the
+/// [`lane_switch_speed`](Self::lane_switch_speed).
+///
+/// This still uses 3D physics, so the rigid body should lock its rotations (like with any other
+/// Tnua character) and the level geometry should extend along the normal enough to support the
+/// character in all the lanes.
+///
+/// Requires the [`TnuaPlaneLockPlugin`].
+#[derive(Component, Debug, Clone)]
+pub struct TnuaPlaneLock {
+    /// The normal of the plane the character is locked to.
+    pub normal: Dir3,
+
+    /// The speed at which the character moves to a new lane, and at which it is brought back to
+    /// the plane when pushed away from it.
+    pub lane_switch_speed: Float,
+
+    offset: Float,
+    target_offset: Float,
+}
+
+impl TnuaPlaneLock {
+    /// Lock the character to the plane with the given `normal`, `offset` units from the world
+    /// origin along it.
+    pub fn new(normal: Dir3, offset: Float) -> Self {
+        Self {
+            normal,
+            lane_switch_speed: 10.0,
+            offset,
+            target_offset: offset,
+        }
+    }
+
+    /// The current distance of the plane from the world origin, along the
+    /// [`normal`](Self::normal).
+    ///
+    /// During a lane switch, this is somewhere between the old lane and the new one.
+    pub fn offset(&self) -> Float {
+        self.offset
+    }
+
+    /// The offset of the lane the plane is moving to (or the current offset, when there is no
+    /// lane switch).
+    pub fn target_offset(&self) -> Float {
+        self.target_offset
+    }
+
+    /// Check if the plane is in the middle of a lane switch.
+    pub fn is_switching_lanes(&self) -> bool {
+        self.offset != self.target_offset
+    }
+
+    /// Start moving the plane smoothly to the given offset.
+    ///
+    /// Calling this in the middle of a lane switch starts the new switch from wherever the plane
+    /// currently is.
+    pub fn switch_lane_to(&mut self, offset: Float) {
+        self.target_offset = offset;
+    }
+
+    /// Move the plane to the given offset immediately.
+    ///
+    /// The character will still be moved to the plane at the
+    /// [`lane_switch_speed`](Self::lane_switch_speed).
+    pub fn set_offset(&mut self, offset: Float) {
+        self.offset = offset;
+        self.target_offset = offset;
+    }
+}
+
+fn project_desired_velocity_system(
+    mut query: Query<(&TnuaPlaneLock, &mut TnuaController, Option<&TnuaToggle>)>,
+) {
+    for (plane_lock, mut controller, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly => continue,
+            TnuaToggle::Enabled => {}
+        }
+        let Some(basis_name) = controller.basis_name() else {
+            continue;
+        };
+        let Some((walk, _)) = controller.concrete_basis::<TnuaBuiltinWalk>() else {
+            continue;
+        };
+        let normal = plane_lock.normal.adjust_precision();
+        if walk.desired_velocity.dot(normal) == 0.0
+            && walk
+                .desired_forward
+                .is_none_or(|forward| forward.adjust_precision().dot(normal) == 0.0)
+        {
+            continue;
+        }
+
+        let mut walk = walk.clone();
+        walk.desired_velocity = walk.desired_velocity.reject_from(normal);
+        walk.desired_forward = walk.desired_forward.and_then(|forward| {
+            Dir3::new(forward.adjust_precision().reject_from(normal).f32()).ok()
+        });
+        controller.named_basis(basis_name, walk);
+    }
+}
+
+fn lock_motor_to_plane_system(
+    time: Res<Time>,
+    mut query: Query<(
+        &mut TnuaPlaneLock,
+        &TnuaRigidBodyTracker,
+        &mut TnuaMotor,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
+    if frame_duration == 0.0 {
+        return;
+    }
+    for (mut plane_lock, tracker, mut motor, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly => continue,
+            TnuaToggle::Enabled => {}
+        }
+        let plane_lock = plane_lock.as_mut();
+        let max_change = plane_lock.lane_switch_speed * frame_duration;
+        let remaining = plane_lock.target_offset - plane_lock.offset;
+        if remaining.abs() <= max_change {
+            plane_lock.offset = plane_lock.target_offset;
+        } else {
+            plane_lock.offset += max_change.copysign(remaining);
+        }
+
+        let normal = plane_lock.normal.adjust_precision();
+        let distance_to_plane = plane_lock.offset - tracker.translation.dot(normal);
+        let desired_normal_velocity = (distance_to_plane / frame_duration)
+            .clamp(-plane_lock.lane_switch_speed, plane_lock.lane_switch_speed);
+
+        motor.lin.cancel_on_axis(normal);
+        motor.lin.boost += (desired_normal_velocity - tracker.velocity.dot(normal)) * normal;
+        // The physics backend applies the gravity on its own, so whatever part of it goes along
+        // the normal must be cancelled out.
+        motor.lin.acceleration -= tracker.gravity.dot(normal) * normal;
+    }
+}
@@ -0,0 +1,204 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Float;
+
+use crate::controller::TnuaController;
+use crate::{TnuaMotor, TnuaPipelineStages, TnuaSystemSet, TnuaToggle};
+
+pub struct TnuaRagdollHandoffPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaRagdollHandoffPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaRagdollHandoffPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaRagdollHandoff`] work.
+impl Plugin for TnuaRagdollHandoffPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            start_getting_up_system
+                .in_set(TnuaSystemSet)
+                .before(TnuaPipelineStages::Sensors),
+        );
+        app.add_systems(
+            self.schedule,
+            ease_get_up_motor_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Logic)
+                .before(TnuaPipelineStages::Motors),
+        );
+        app.add_systems(
+            self.schedule,
+            handoff_to_ragdoll_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Motors),
+        );
+    }
+}
+
+/// Hands the control over a character to the physics engine (as a ragdoll) and back.
+///
+/// Place this component on the character entity (the one with the [`TnuaController`]), and use:
+///
+/// * [`ragdoll`](Self::ragdoll) to turn the character into a ragdoll. The motor is applied one
+///   last time in the frame of the handoff - so the velocity the controller intended to give the
+///   character is carried over into the rigid body - and then [`TnuaToggle::Disabled`] is set on
+///   the entity.
+/// * [`get_up`](Self::get_up) to give the control back to Tnua. [`TnuaToggle::Enabled`] is set on
+///   the entity, the [memory of the basis is reset](TnuaController::reset_basis_state) so that it
+///   starts over from the pose the ragdoll ended in, and the forces of the motor are eased in
+///   over the get up duration so that the character does not jerk back to its feet.
+///
+/// Since Tnua cannot lock or unlock the rotation axes of the rigid body by itself (these are
+/// backend specific), the component is created with two functions that are called with the
+/// commands of the character entity - `on_ragdoll` when the character becomes a ragdoll (e.g. to
+/// insert an unlocked `LockedAxes`) and `on_recover` when it finishes getting up (e.g. to lock
+/// the rotation axes again).
+///
+/// Requires the [`TnuaRagdollHandoffPlugin`].
+#[derive(Component)]
+pub struct TnuaRagdollHandoff {
+    on_ragdoll: Box<dyn Send + Sync + Fn(&mut EntityCommands)>,
+    on_recover: Box<dyn Send + Sync + Fn(&mut EntityCommands)>,
+    request: Option<RagdollRequest>,
+    status: TnuaRagdollStatus,
+}
+
+enum RagdollRequest {
+    Ragdoll,
+    GetUp { duration: Float },
+}
+
+/// The status of a [`TnuaRagdollHandoff`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TnuaRagdollStatus {
+    /// The character is controlled by Tnua.
+    Controlled,
+    /// The character is a ragdoll, controlled only by the physics engine.
+    Ragdoll,
+    /// The character is getting up, and the forces of the motor are eased in.
+    GettingUp {
+        /// The time, in seconds, since the character started getting up.
+        elapsed: Float,
+        /// The total duration, in seconds, of getting up.
+        duration: Float,
+    },
+}
+
+impl TnuaRagdollHandoff {
+    /// Create the component, to be added to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `on_ragdoll` - called with the commands of the character entity when it becomes a
+    ///   ragdoll.
+    /// * `on_recover` - called with the commands of the character entity when it finishes getting
+    ///   up.
+    pub fn new(
+        on_ragdoll: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+        on_recover: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        Self {
+            on_ragdoll: Box::new(on_ragdoll),
+            on_recover: Box::new(on_recover),
+            request: None,
+            status: TnuaRagdollStatus::Controlled,
+        }
+    }
+
+    /// Turn the character into a ragdoll at the end of the next run of the controller.
+    pub fn ragdoll(&mut self) {
+        self.request = Some(RagdollRequest::Ragdoll);
+    }
+
+    /// Give the control back to Tnua, easing in the motor over `duration` seconds.
+    pub fn get_up(&mut self, duration: Float) {
+        self.request = Some(RagdollRequest::GetUp { duration });
+    }
+
+    /// Whether the character is controlled by Tnua, a ragdoll, or getting up.
+    pub fn status(&self) -> TnuaRagdollStatus {
+        self.status
+    }
+
+    /// Check if the character is currently a ragdoll.
+    pub fn is_ragdoll(&self) -> bool {
+        self.status == TnuaRagdollStatus::Ragdoll
+    }
+}
+
+fn start_getting_up_system(
+    mut query: Query<(Entity, &mut TnuaRagdollHandoff, &mut TnuaController)>,
+    mut commands: Commands,
+) {
+    for (entity, mut handoff, mut controller) in query.iter_mut() {
+        let Some(RagdollRequest::GetUp { duration }) = handoff.request else {
+            continue;
+        };
+        handoff.request = None;
+        if handoff.status != TnuaRagdollStatus::Ragdoll {
+            continue;
+        }
+        commands.entity(entity).insert(TnuaToggle::Enabled);
+        controller.reset_basis_state();
+        handoff.status = TnuaRagdollStatus::GettingUp {
+            elapsed: 0.0,
+            duration,
+        };
+    }
+}
+
+fn ease_get_up_motor_system(mut query: Query<(&TnuaRagdollHandoff, &mut TnuaMotor)>) {
+    for (handoff, mut motor) in query.iter_mut() {
+        let TnuaRagdollStatus::GettingUp { elapsed, duration } = handoff.status else {
+            continue;
+        };
+        if duration <= elapsed {
+            continue;
+        }
+        let factor = elapsed / duration;
+        motor.lin.boost *= factor;
+        motor.lin.acceleration *= factor;
+        motor.ang.boost *= factor;
+        motor.ang.acceleration *= factor;
+    }
+}
+
+fn handoff_to_ragdoll_system(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut TnuaRagdollHandoff)>,
+    mut commands: Commands,
+) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
+    for (entity, mut handoff) in query.iter_mut() {
+        let handoff = handoff.as_mut();
+        if let Some(RagdollRequest::Ragdoll) = handoff.request {
+            handoff.request = None;
+            if handoff.status != TnuaRagdollStatus::Ragdoll {
+                let mut cmd = commands.entity(entity);
+                cmd.insert(TnuaToggle::Disabled);
+                (handoff.on_ragdoll)(&mut cmd);
+                handoff.status = TnuaRagdollStatus::Ragdoll;
+            }
+        } else if let TnuaRagdollStatus::GettingUp { elapsed, duration } = &mut handoff.status {
+            *elapsed += frame_duration;
+            if *duration <= *elapsed {
+                (handoff.on_recover)(&mut commands.entity(entity));
+                handoff.status = TnuaRagdollStatus::Controlled;
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::VecDeque;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Float;
+
+use crate::{
+    TnuaPipelineStages, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaSystemSet, TnuaToggle,
+};
+
+pub struct TnuaSensorHistoryPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaSensorHistoryPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaSensorHistoryPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaSensorHistory`] work.
+impl Plugin for TnuaSensorHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            record_sensor_history_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::SubservientSensors)
+                .before(TnuaPipelineStages::Logic),
+        );
+    }
+}
+
+/// Records the outputs of the proximity sensor in the last few frames.
+///
+/// Placing this component on an entity with a [`TnuaProximitySensor`] (usually the character
+/// entity) will make Tnua record, every frame, the output of the sensor - as seen by the basis
+/// and the actions, after the subservient sensors (e.g.
+/// [`TnuaSensorNormalSmoothing`](crate::control_helpers::TnuaSensorNormalSmoothing)) modified it.
+/// Only the last [`capacity`](Self::capacity) frames are kept.
+///
+/// This can be used for logic that needs a short history of the ground - like detecting changes
+/// in the slope - or for debugging.
+///
+/// Requires the [`TnuaSensorHistoryPlugin`].
+#[derive(Component, Debug, Clone)]
+pub struct TnuaSensorHistory {
+    capacity: usize,
+    entries: VecDeque<TnuaSensorHistoryEntry>,
+}
+
+/// A single frame recorded by [`TnuaSensorHistory`].
+#[derive(Debug, Clone)]
+pub struct TnuaSensorHistoryEntry {
+    /// The elapsed time, in seconds, of the schedule's [`Time`] at the frame.
+    pub timestamp: Float,
+    /// The output of the sensor at the frame. `None` if it did not detect anything.
+    pub output: Option<TnuaProximitySensorOutput>,
+}
+
+impl TnuaSensorHistory {
+    /// Create a history that keeps the last `capacity` frames.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// The maximum number of frames kept in the history.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of frames currently in the history.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if no frames have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the recorded frames, from the oldest to the latest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &TnuaSensorHistoryEntry> {
+        self.entries.iter()
+    }
+
+    /// The latest recorded frame.
+    pub fn latest(&self) -> Option<&TnuaSensorHistoryEntry> {
+        self.entries.back()
+    }
+
+    /// The frame recorded `frames_ago` frames before the latest one (`0` is the latest).
+    pub fn get(&self, frames_ago: usize) -> Option<&TnuaSensorHistoryEntry> {
+        let index = self.entries.len().checked_sub(frames_ago + 1)?;
+        self.entries.get(index)
+    }
+
+    /// Remove all the recorded frames.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn record(&mut self, entry: TnuaSensorHistoryEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.capacity <= self.entries.len() {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+fn record_sensor_history_system(
+    time: Res<Time>,
+    mut query: Query<(
+        &mut TnuaSensorHistory,
+        &TnuaProximitySensor,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    let timestamp = time.elapsed_secs_f64() as Float;
+    for (mut sensor_history, sensor, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        sensor_history.record(TnuaSensorHistoryEntry {
+            timestamp,
+            output: sensor.output.clone(),
+        });
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float};
+
+use crate::{TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaToggle};
+
+pub struct TnuaSensorLodPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaSensorLodPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaSensorLodPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaSensorLod`] work.
+impl Plugin for TnuaSensorLodPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_sensor_lod_system.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Reduces the frequency in which the proximity sensor is cast.
+///
+/// Casting the sensor is usually the most expensive part of Tnua's pipeline. Characters that are
+/// far from the camera do not need full precision, so placing this component on their entity
+/// (usually with a [`cast_interval`](Self::cast_interval) that the game updates based on the
+/// distance from the camera) will make the physics backend cast their sensor only once every few
+/// frames (see [`TnuaProximitySensor::skip_next_cast`]).
+///
+/// On the frames where the sensor is not cast, its output is estimated from the last one, using
+/// the velocity of the character relative to the entity it hit. Airborne characters (whose sensor
+/// did not hit anything) still have their sensor cast every frame, so that they would not miss
+/// their landing.
+///
+/// Requires the [`TnuaSensorLodPlugin`].
+#[derive(Component)]
+pub struct TnuaSensorLod {
+    /// Cast the sensor once every this many frames. `1` (or `0`) means casting every frame.
+    pub cast_interval: usize,
+    frames_since_cast: usize,
+    skipping: bool,
+}
+
+impl TnuaSensorLod {
+    pub fn new(cast_interval: usize) -> Self {
+        Self {
+            cast_interval,
+            frames_since_cast: 0,
+            skipping: false,
+        }
+    }
+}
+
+fn update_sensor_lod_system(
+    time: Res<Time>,
+    mut query: Query<(
+        &mut TnuaSensorLod,
+        &mut TnuaProximitySensor,
+        &TnuaRigidBodyTracker,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
+    for (mut lod, mut sensor, tracker, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+
+        if lod.skipping {
+            lod.frames_since_cast += 1;
+            let cast_direction = sensor.cast_direction.adjust_precision();
+            if let Some(output) = sensor.output.as_mut() {
+                let relative_velocity = tracker.velocity - output.entity_linvel;
+                output.proximity -= relative_velocity.dot(cast_direction) * frame_duration;
+            }
+        } else {
+            lod.frames_since_cast = 0;
+        }
+
+        lod.skipping = sensor.output.is_some() && lod.frames_since_cast + 1 < lod.cast_interval;
+        if lod.skipping {
+            sensor.skip_next_cast = true;
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AsF32, Float};
+
+use crate::{TnuaPipelineStages, TnuaProximitySensor, TnuaToggle};
+
+pub struct TnuaSensorNormalSmoothingPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaSensorNormalSmoothingPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaSensorNormalSmoothingPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaSensorNormalSmoothing`] work.
+impl Plugin for TnuaSensorNormalSmoothingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            smooth_sensor_normals_system.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Smooths the [`normal`](crate::TnuaProximitySensorOutput::normal) detected by the proximity
+/// sensor across frames.
+///
+/// When moving over seams between the triangles of a mesh collider, the sensed normal may flicker
+/// between the normals of the different triangles, causing the character to jitter. Placing this
+/// component on an entity with a [`TnuaProximitySensor`] (usually the character entity) will
+/// replace the sensed normal with an exponentially smoothed one, so that the basis and the
+/// actions will see a stable normal.
+///
+/// Requires the [`TnuaSensorNormalSmoothingPlugin`].
+#[derive(Component)]
+pub struct TnuaSensorNormalSmoothing {
+    /// The time constant, in seconds, of the exponential smoothing.
+    ///
+    /// After this much time, the smoothed normal covers about 63% of the angle towards a newly
+    /// sensed normal. Higher values mean smoother, but less responsive, normals.
+    pub time_constant: Float,
+    smoothed_normal: Option<Dir3>,
+}
+
+impl TnuaSensorNormalSmoothing {
+    pub fn new(time_constant: Float) -> Self {
+        Self {
+            time_constant,
+            smoothed_normal: None,
+        }
+    }
+}
+
+fn smooth_sensor_normals_system(
+    time: Res<Time>,
+    mut query: Query<(
+        &mut TnuaProximitySensor,
+        &mut TnuaSensorNormalSmoothing,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
+    for (mut sensor, mut smoothing, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+
+        let Some(sensor_output) = sensor.output.as_mut() else {
+            smoothing.smoothed_normal = None;
+            continue;
+        };
+        let smoothed_normal = if let Some(previous_normal) = smoothing.smoothed_normal {
+            let factor = if 0.0 < smoothing.time_constant {
+                1.0 - (-frame_duration / smoothing.time_constant).exp()
+            } else {
+                1.0
+            };
+            previous_normal.slerp(sensor_output.normal, factor.f32())
+        } else {
+            sensor_output.normal
+        };
+        smoothing.smoothed_normal = Some(smoothed_normal);
+        sensor_output.normal = smoothed_normal;
+    }
+}
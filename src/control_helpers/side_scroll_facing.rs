@@ -0,0 +1,185 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::{TnuaBasis, TnuaPipelineStages, TnuaSystemSet, TnuaToggle};
+
+pub struct TnuaSideScrollFacingPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaSideScrollFacingPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaSideScrollFacingPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaSideScrollFacing`] work.
+impl Plugin for TnuaSideScrollFacingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_side_scroll_facing_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Logic),
+        );
+    }
+}
+
+/// The side a side-scroller character is facing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaFacing {
+    /// Facing the negative direction of the [`axis`](TnuaSideScrollFacing::axis).
+    Left,
+    /// Facing the positive direction of the [`axis`](TnuaSideScrollFacing::axis).
+    Right,
+}
+
+impl TnuaFacing {
+    /// The sign of the facing direction along the axis - `-1.0` for left and `1.0` for right.
+    pub fn sign(&self) -> Float {
+        match self {
+            TnuaFacing::Left => -1.0,
+            TnuaFacing::Right => 1.0,
+        }
+    }
+
+    /// The other side.
+    pub fn opposite(&self) -> Self {
+        match self {
+            TnuaFacing::Left => TnuaFacing::Right,
+            TnuaFacing::Right => TnuaFacing::Left,
+        }
+    }
+}
+
+/// Tracks the side a side-scroller character is facing.
+///
+/// Side-scroller characters usually do not rotate - instead, their sprite is flipped to face the
+/// direction they walk in. Place this component on the character entity (the one with the
+/// [`TnuaController`]) and it will decide the facing from the
+/// [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) of the walk basis - so the character
+/// turns when the player wants to move the other way, even before it actually starts moving that
+/// way. Small inputs (below [`threshold`](Self::threshold)) do not change the facing, and the
+/// character only turns after the player wants to face the other side for
+/// [`turn_time`](Self::turn_time) seconds, which can be used to play a turn animation (see
+/// [`turning_to`](Self::turning_to)).
+///
+/// Requires the [`TnuaSideScrollFacingPlugin`].
+#[derive(Component)]
+pub struct TnuaSideScrollFacing {
+    /// The axis the character walks along. Facing [`Right`](TnuaFacing::Right) means facing the
+    /// positive direction of this axis.
+    pub axis: Dir3,
+
+    /// The minimal speed of the desired velocity (along the axis) required for changing the
+    /// facing.
+    pub threshold: Float,
+
+    /// The time, in seconds, it takes the character to turn around.
+    pub turn_time: Float,
+
+    /// Whether the character can turn around while airborne.
+    pub turn_in_air: bool,
+
+    facing: TnuaFacing,
+    turning_for: Option<Float>,
+}
+
+impl Default for TnuaSideScrollFacing {
+    fn default() -> Self {
+        Self {
+            axis: Dir3::X,
+            threshold: 0.1,
+            turn_time: 0.0,
+            turn_in_air: true,
+            facing: TnuaFacing::Right,
+            turning_for: None,
+        }
+    }
+}
+
+impl TnuaSideScrollFacing {
+    /// The side the character is facing.
+    pub fn facing(&self) -> TnuaFacing {
+        self.facing
+    }
+
+    /// Force the character to face a side immediately, cancelling any turn in progress.
+    pub fn set_facing(&mut self, facing: TnuaFacing) {
+        self.facing = facing;
+        self.turning_for = None;
+    }
+
+    /// The side the character is in the middle of turning to, if it is turning.
+    pub fn turning_to(&self) -> Option<TnuaFacing> {
+        self.turning_for.map(|_| self.facing.opposite())
+    }
+
+    /// How far, from 0.0 to 1.0, the character went in the current turn. `None` if it is not
+    /// turning.
+    pub fn turn_progress(&self) -> Option<Float> {
+        let turning_for = self.turning_for?;
+        Some(if 0.0 < self.turn_time {
+            (turning_for / self.turn_time).min(1.0)
+        } else {
+            1.0
+        })
+    }
+
+    /// Whether a sprite drawn facing right should be flipped (e.g. with `Sprite::flip_x`).
+    pub fn flip_x(&self) -> bool {
+        self.facing == TnuaFacing::Left
+    }
+}
+
+fn update_side_scroll_facing_system(
+    time: Res<Time>,
+    mut query: Query<(
+        &mut TnuaSideScrollFacing,
+        &TnuaController,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
+    for (mut side_scroll_facing, controller, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let Some((walk, walk_state)) = controller.concrete_basis::<TnuaBuiltinWalk>() else {
+            continue;
+        };
+        let side_scroll_facing = side_scroll_facing.as_mut();
+
+        let desired_speed = walk
+            .desired_velocity
+            .dot(side_scroll_facing.axis.adjust_precision());
+        let wants_to_turn = side_scroll_facing.threshold < desired_speed.abs()
+            && side_scroll_facing.facing.sign() * desired_speed < 0.0
+            && (side_scroll_facing.turn_in_air || !walk.is_airborne(walk_state));
+
+        if !wants_to_turn {
+            side_scroll_facing.turning_for = None;
+            continue;
+        }
+        let turning_for = side_scroll_facing.turning_for.get_or_insert(0.0);
+        if side_scroll_facing.turn_time <= *turning_for {
+            side_scroll_facing.facing = side_scroll_facing.facing.opposite();
+            side_scroll_facing.turning_for = None;
+        } else {
+            *turning_for += frame_duration;
+        }
+    }
+}
@@ -16,6 +16,14 @@ use crate::{TnuaGhostSensor, TnuaProximitySensor};
 /// these methods to determine which one to call.
 #[derive(Component, Default)]
 pub struct TnuaSimpleFallThroughPlatformsHelper {
+    /// Ghost platforms whose proximity is at most this much further than the nearest one are
+    /// considered part of the same layer, and the character will fall through all of them
+    /// together.
+    ///
+    /// This is useful when a single platform is made of multiple colliders. Platforms that are
+    /// stacked closer than that will be fallen through together.
+    pub layer_thickness: Float,
+
     currently_falling_through: HashSet<Entity>,
 }
 
@@ -73,7 +81,9 @@ impl TnuaHandleForSimpleFallThroughPlatformsHelper<'_> {
     /// Call this method to make the character drop through the platform.
     ///
     /// The character will fall through the first layer of ghost platforms detected since the last
-    /// time it was called with `just_pressed` being `true`. This means that:
+    /// time it was called with `just_pressed` being `true` - only the nearest platform (and the
+    /// platforms within [`layer_thickness`](TnuaSimpleFallThroughPlatformsHelper::layer_thickness)
+    /// from it), even if there are more platforms stacked closely below it. This means that:
     ///
     /// * To let the player fall through all the platforms by simply holding the button, call this
     ///   with `just_pressed = true` as long as the button is held.
@@ -99,8 +109,18 @@ impl TnuaHandleForSimpleFallThroughPlatformsHelper<'_> {
             return true;
         }
         self.parent.currently_falling_through.clear();
+        let Some(nearest_proximity) = self
+            .ghost_sensor
+            .iter()
+            .map(|ghost_platform| ghost_platform.proximity)
+            .filter(|proximity| self.min_proximity <= *proximity)
+            .min_by(|a, b| a.total_cmp(b))
+        else {
+            return false;
+        };
+        let max_proximity = nearest_proximity + self.parent.layer_thickness;
         for ghost_platform in self.ghost_sensor.iter() {
-            if self.min_proximity <= ghost_platform.proximity {
+            if (self.min_proximity..=max_proximity).contains(&ghost_platform.proximity) {
                 self.parent
                     .currently_falling_through
                     .insert(ghost_platform.entity);
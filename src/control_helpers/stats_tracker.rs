@@ -0,0 +1,138 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float};
+
+use crate::builtins::{TnuaBuiltinDash, TnuaBuiltinJump};
+use crate::controller::TnuaController;
+use crate::{TnuaAction, TnuaPipelineStages, TnuaSystemSet, TnuaToggle};
+
+pub struct TnuaStatsTrackerPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaStatsTrackerPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaStatsTrackerPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaStatsTracker`] work.
+impl Plugin for TnuaStatsTrackerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_stats_tracker_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Logic),
+        );
+    }
+}
+
+/// Accumulates gameplay statistics of a character - for achievements, analytics, etc.
+///
+/// Place this component on the character entity (the one with the [`TnuaController`]) to track
+/// the distance it walked, the time it spent in the air, the number of actions it performed and
+/// the fastest fall it landed from. The statistics are accumulated until [`reset`](Self::reset)
+/// is called.
+///
+/// The distance and the fall speed are measured relative to the ground (so riding a moving
+/// platform does not count as walking). Actions are counted by their names - see
+/// [`action_count`](Self::action_count).
+///
+/// Requires the [`TnuaStatsTrackerPlugin`].
+#[derive(Component, Default, Debug, Clone)]
+pub struct TnuaStatsTracker {
+    distance_walked: Float,
+    time_airborne: Float,
+    action_counts: HashMap<&'static str, usize>,
+    max_fall_speed_survived: Float,
+    current_fall_speed: Float,
+}
+
+impl TnuaStatsTracker {
+    /// The total distance the character moved while on the ground.
+    pub fn distance_walked(&self) -> Float {
+        self.distance_walked
+    }
+
+    /// The total time, in seconds, the character spent in the air.
+    pub fn time_airborne(&self) -> Float {
+        self.time_airborne
+    }
+
+    /// The number of times the action with the given name was started.
+    ///
+    /// This is [`TnuaAction::NAME`] for actions fed with [`TnuaController::action`], or the
+    /// custom name for actions fed with [`TnuaController::named_action`].
+    pub fn action_count(&self, action_name: &str) -> usize {
+        self.action_counts.get(action_name).copied().unwrap_or(0)
+    }
+
+    /// The number of times [`TnuaBuiltinJump`] was started.
+    pub fn jumps(&self) -> usize {
+        self.action_count(TnuaBuiltinJump::NAME)
+    }
+
+    /// The number of times [`TnuaBuiltinDash`] was started.
+    pub fn dashes(&self) -> usize {
+        self.action_count(TnuaBuiltinDash::NAME)
+    }
+
+    /// The highest downward speed the character had when landing.
+    pub fn max_fall_speed_survived(&self) -> Float {
+        self.max_fall_speed_survived
+    }
+
+    /// Clear all the statistics.
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+fn update_stats_tracker_system(
+    time: Res<Time>,
+    mut query: Query<(&mut TnuaStatsTracker, &TnuaController, Option<&TnuaToggle>)>,
+) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
+    for (mut stats_tracker, controller, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly => continue,
+            TnuaToggle::Enabled => {}
+        }
+        let stats_tracker = stats_tracker.as_mut();
+
+        if let Some(action_name) = controller.action_flow_status().just_starting() {
+            *stats_tracker.action_counts.entry(action_name).or_default() += 1;
+        }
+
+        let (Some(basis), Some(up_direction)) =
+            (controller.dynamic_basis(), controller.up_direction())
+        else {
+            continue;
+        };
+        let up = up_direction.adjust_precision();
+        let velocity = basis.effective_velocity();
+
+        if basis.is_airborne() {
+            stats_tracker.time_airborne += frame_duration;
+            stats_tracker.current_fall_speed =
+                stats_tracker.current_fall_speed.max(-velocity.dot(up));
+        } else {
+            stats_tracker.distance_walked += velocity.reject_from(up).length() * frame_duration;
+            // The fall ends when the character lands.
+            stats_tracker.max_fall_speed_survived = stats_tracker
+                .max_fall_speed_survived
+                .max(stats_tracker.current_fall_speed);
+            stats_tracker.current_fall_speed = 0.0;
+        }
+    }
+}
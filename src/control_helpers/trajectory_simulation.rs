@@ -0,0 +1,253 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+
+use crate::basis_action_traits::{BoxableAction, BoxableBasis};
+use crate::{
+    DynamicAction, DynamicBasis, TnuaAction, TnuaActionContext, TnuaActionEventQueue,
+    TnuaActionInitiationDirective, TnuaActionLifecycleDirective, TnuaActionLifecycleStatus,
+    TnuaActionSensors, TnuaBasis, TnuaBasisContext, TnuaMotor, TnuaProximitySensor,
+    TnuaProximitySensorOutput, TnuaRigidBodyTracker,
+};
+
+type GroundProbe<'a> = Box<dyn FnMut(Vector3, Dir3, Float) -> Option<(Float, Dir3)> + 'a>;
+
+/// Predicts the motion of a character without touching its actual state.
+///
+/// This is meant for AI decision making - e.g. "if I dashed now, would I land on that platform?".
+/// The simulation runs the math of a basis (and optionally an action) forward, starting from the
+/// character's current [`TnuaRigidBodyTracker`] and [`TnuaProximitySensor`], and integrates the
+/// resulting motor commands together with the gravity.
+///
+/// Since the physics engine is not involved, the simulation is only an approximation:
+///
+/// * The ground is modeled by a probe closure (see [`with_ground`](Self::with_ground)). By
+///   default, it is a plane through the point the proximity sensor currently hits, or no ground at
+///   all if the sensor does not hit anything.
+/// * Collisions with other objects are ignored, and the rotation of the character is not
+///   simulated.
+/// * The basis and the action start from their default states, and the action (if present) is
+///   considered to be fed throughout the simulation.
+/// * The action's [sensors](crate::TnuaAction::sensors) are not simulated, and never detect
+///   anything.
+///
+/// ```no_run
+/// # use bevy_tnua::control_helpers::TnuaTrajectorySimulation;
+/// # use bevy_tnua::builtins::{TnuaBuiltinWalk, TnuaBuiltinDash};
+/// # use bevy_tnua::{TnuaRigidBodyTracker, TnuaProximitySensor};
+/// # use bevy_tnua::math::Vector3;
+/// # let tracker = TnuaRigidBodyTracker::default();
+/// # let sensor = TnuaProximitySensor::default();
+/// # let walk = TnuaBuiltinWalk::default();
+/// # let dash = TnuaBuiltinDash::default();
+/// let trajectory = TnuaTrajectorySimulation::new(walk, &tracker, &sensor)
+///     .with_action(dash)
+///     .run(1.0 / 60.0, 120);
+/// let landing_position = trajectory.last().map(|frame| frame.translation);
+/// ```
+pub struct TnuaTrajectorySimulation<'a> {
+    basis: Box<dyn DynamicBasis>,
+    action: Option<Box<dyn DynamicAction>>,
+    tracker: TnuaRigidBodyTracker,
+    sensor: TnuaProximitySensor,
+    ground: GroundProbe<'a>,
+}
+
+/// The state of the character in one frame of a [`TnuaTrajectorySimulation`].
+#[derive(Debug, Clone)]
+pub struct TnuaSimulatedFrame {
+    /// The position of the character at the end of the frame.
+    pub translation: Vector3,
+
+    /// The velocity of the character at the end of the frame.
+    pub velocity: Vector3,
+
+    /// Whether the basis considered the character to be airborne during the frame.
+    pub airborne: bool,
+
+    /// Whether the action was active during the frame.
+    pub action_active: bool,
+}
+
+impl<'a> TnuaTrajectorySimulation<'a> {
+    /// Prepare a simulation of the character with the given basis.
+    ///
+    /// The `tracker` and the `sensor` are usually the components of the character entity.
+    pub fn new<B: TnuaBasis>(
+        basis: B,
+        tracker: &TnuaRigidBodyTracker,
+        sensor: &TnuaProximitySensor,
+    ) -> Self {
+        let ground: GroundProbe = if let Some(output) = sensor.output.as_ref() {
+            let cast_origin = tracker.translation + tracker.rotation.mul_vec3(sensor.cast_origin);
+            let plane_point =
+                cast_origin + output.proximity * sensor.cast_direction.adjust_precision();
+            let plane_normal = output.normal;
+            Box::new(move |origin, direction, range| {
+                let normal = plane_normal.adjust_precision();
+                let denominator = direction.adjust_precision().dot(normal);
+                if 0.0 <= denominator {
+                    return None;
+                }
+                let proximity = ((plane_point - origin).dot(normal) / denominator).max(0.0);
+                (proximity <= range).then_some((proximity, plane_normal))
+            })
+        } else {
+            Box::new(|_, _, _| None)
+        };
+        Self {
+            basis: Box::new(BoxableBasis::new(basis)),
+            action: None,
+            tracker: TnuaRigidBodyTracker {
+                translation: tracker.translation,
+                rotation: tracker.rotation,
+                velocity: tracker.velocity,
+                angvel: tracker.angvel,
+                gravity: tracker.gravity,
+            },
+            sensor: TnuaProximitySensor {
+                cast_origin: sensor.cast_origin,
+                cast_direction: sensor.cast_direction,
+                cast_range: sensor.cast_range,
+                output: sensor.output.clone(),
+                ..Default::default()
+            },
+            ground,
+        }
+    }
+
+    /// Try to perform an action during the simulation.
+    pub fn with_action<A: TnuaAction>(mut self, action: A) -> Self {
+        self.action = Some(Box::new(BoxableAction::new(action)));
+        self
+    }
+
+    /// Replace the ground model.
+    ///
+    /// The probe receives the cast origin, direction and range (like a [`TnuaProximitySensor`])
+    /// and should return the proximity and the normal of the ground it hits - or `None` if it does
+    /// not hit anything. It can be used, for example, to perform ray casts with the physics engine.
+    pub fn with_ground(
+        mut self,
+        probe: impl FnMut(Vector3, Dir3, Float) -> Option<(Float, Dir3)> + 'a,
+    ) -> Self {
+        self.ground = Box::new(probe);
+        self
+    }
+
+    /// Run the simulation for `steps` frames of `frame_duration` seconds each.
+    pub fn run(mut self, frame_duration: Float, steps: usize) -> Vec<TnuaSimulatedFrame> {
+        let up_direction = -self.sensor.cast_direction;
+        let mut action_status: Option<TnuaActionLifecycleStatus> = None;
+        let mut being_fed_for = Stopwatch::new();
+        let mut frames = Vec::with_capacity(steps);
+        // Action sensors are not simulated.
+        let no_action_sensors = TnuaActionSensors::default();
+        // Events emitted by the action are ignored.
+        let ignored_action_events = TnuaActionEventQueue::default();
+
+        for _ in 0..steps {
+            let cast_range = self.basis.proximity_sensor_cast_range().max(
+                self.action
+                    .as_ref()
+                    .map_or(0.0, |action| action.proximity_sensor_cast_range()),
+            );
+            let cast_origin =
+                self.tracker.translation + self.tracker.rotation.mul_vec3(self.sensor.cast_origin);
+            self.sensor.cast_range = cast_range;
+            self.sensor.output = (self.ground)(cast_origin, self.sensor.cast_direction, cast_range)
+                .map(|(proximity, normal)| TnuaProximitySensorOutput {
+                    entity: Entity::PLACEHOLDER,
+                    proximity,
+                    normal,
+                    entity_linvel: Vector3::ZERO,
+                    entity_angvel: Vector3::ZERO,
+                });
+
+            let mut motor = TnuaMotor::default();
+            self.basis.apply(
+                TnuaBasisContext {
+                    frame_duration,
+                    tracker: &self.tracker,
+                    proximity_sensor: &self.sensor,
+                    up_direction,
+                    walkable_filter: None,
+                },
+                &mut motor,
+            );
+
+            if let Some(action) = self.action.as_mut() {
+                let ctx = TnuaActionContext {
+                    frame_duration,
+                    tracker: &self.tracker,
+                    proximity_sensor: &self.sensor,
+                    up_direction,
+                    walkable_filter: None,
+                    basis: self.basis.as_ref(),
+                    sensors: &no_action_sensors,
+                    charge_duration: None,
+                    events: &ignored_action_events,
+                };
+                if action_status.is_none() {
+                    match action.initiation_decision(ctx, &being_fed_for) {
+                        TnuaActionInitiationDirective::Reject => {
+                            self.action = None;
+                        }
+                        TnuaActionInitiationDirective::Delay
+                        | TnuaActionInitiationDirective::Charge => {}
+                        TnuaActionInitiationDirective::Allow => {
+                            action_status = Some(TnuaActionLifecycleStatus::Initiated);
+                            if action.violates_coyote_time() {
+                                self.basis.violate_coyote_time();
+                            }
+                        }
+                    }
+                    being_fed_for.tick(Duration::from_secs_f32(frame_duration.f32()));
+                }
+            }
+
+            let mut action_active = false;
+            if let (Some(action), Some(status)) = (self.action.as_mut(), action_status) {
+                action_active = true;
+                let directive = action.apply(
+                    TnuaActionContext {
+                        frame_duration,
+                        tracker: &self.tracker,
+                        proximity_sensor: &self.sensor,
+                        up_direction,
+                        walkable_filter: None,
+                        basis: self.basis.as_ref(),
+                        sensors: &no_action_sensors,
+                        charge_duration: None,
+                        events: &ignored_action_events,
+                    },
+                    status,
+                    &mut motor,
+                );
+                match directive {
+                    TnuaActionLifecycleDirective::StillActive => {
+                        action_status = Some(TnuaActionLifecycleStatus::StillFed);
+                    }
+                    TnuaActionLifecycleDirective::Finished
+                    | TnuaActionLifecycleDirective::Reschedule { .. } => {
+                        self.action = None;
+                    }
+                }
+            }
+
+            self.tracker.velocity +=
+                motor.lin.boost + (motor.lin.acceleration + self.tracker.gravity) * frame_duration;
+            self.tracker.translation += self.tracker.velocity * frame_duration;
+
+            frames.push(TnuaSimulatedFrame {
+                translation: self.tracker.translation,
+                velocity: self.tracker.velocity,
+                airborne: self.basis.is_airborne(),
+                action_active,
+            });
+        }
+        frames
+    }
+}
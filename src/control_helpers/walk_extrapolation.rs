@@ -0,0 +1,152 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::{TnuaBasis, TnuaPipelineStages, TnuaSystemSet, TnuaToggle, TnuaUserControlsSystemSet};
+
+pub struct TnuaWalkExtrapolationPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaWalkExtrapolationPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaWalkExtrapolationPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaWalkExtrapolation`] work.
+impl Plugin for TnuaWalkExtrapolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            apply_walk_extrapolation_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaUserControlsSystemSet)
+                .before(TnuaPipelineStages::Logic),
+        );
+    }
+}
+
+/// Keeps feeding the walk basis of a remote character when its input stops arriving.
+///
+/// Characters driven by inputs received from the network freeze (or float in midair, if they were
+/// jumping) when the inputs stall, because nothing is fed to their controller. With this component
+/// on the character entity (one with a [`TnuaController`]), the walk basis is fed through the
+/// component's [`basis`](Self::basis) method instead of through the controller. On frames where
+/// no basis was fed, the last basis is extrapolated - with its
+/// [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) decaying linearly over
+/// [`max_frames`](Self::max_frames) frames, after which the character just stands in place. When
+/// the inputs resume, the desired velocity is blended from the extrapolated one into the real one
+/// over [`blend_frames`](Self::blend_frames) frames.
+///
+/// Since the character keeps being fed a basis, it keeps floating and its animations keep
+/// working. Actions are not extrapolated.
+///
+/// Requires the [`TnuaWalkExtrapolationPlugin`].
+#[derive(Component)]
+pub struct TnuaWalkExtrapolation {
+    /// The number of frames over which the extrapolated desired velocity decays to zero.
+    pub max_frames: usize,
+
+    /// The number of frames over which the desired velocity is blended back to the real one when
+    /// the inputs resume.
+    pub blend_frames: usize,
+
+    incoming: Option<(&'static str, TnuaBuiltinWalk)>,
+    last: Option<(&'static str, TnuaBuiltinWalk)>,
+    stalled_frames: usize,
+    blend: Option<(Vector3, usize)>,
+    last_desired_velocity: Vector3,
+}
+
+impl Default for TnuaWalkExtrapolation {
+    fn default() -> Self {
+        Self {
+            max_frames: 10,
+            blend_frames: 5,
+            incoming: None,
+            last: None,
+            stalled_frames: 0,
+            blend: None,
+            last_desired_velocity: Vector3::ZERO,
+        }
+    }
+}
+
+impl TnuaWalkExtrapolation {
+    /// Feed the walk basis received for this frame. See [`TnuaController::basis`].
+    pub fn basis(&mut self, basis: TnuaBuiltinWalk) {
+        self.named_basis(TnuaBuiltinWalk::NAME, basis);
+    }
+
+    /// Feed the walk basis received for this frame with a custom name. See
+    /// [`TnuaController::named_basis`].
+    pub fn named_basis(&mut self, name: &'static str, basis: TnuaBuiltinWalk) {
+        self.incoming = Some((name, basis));
+    }
+
+    /// The number of consecutive frames in which no basis was fed, and the last one was
+    /// extrapolated instead.
+    pub fn stalled_frames(&self) -> usize {
+        self.stalled_frames
+    }
+}
+
+fn apply_walk_extrapolation_system(
+    mut query: Query<(
+        &mut TnuaWalkExtrapolation,
+        &mut TnuaController,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    for (mut extrapolation, mut controller, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly => continue,
+            TnuaToggle::Enabled => {}
+        }
+        let extrapolation = extrapolation.as_mut();
+
+        let (name, walk) = if let Some((name, mut walk)) = extrapolation.incoming.take() {
+            if 0 < extrapolation.stalled_frames {
+                extrapolation.stalled_frames = 0;
+                extrapolation.blend = Some((extrapolation.last_desired_velocity, 0));
+            }
+            extrapolation.last = Some((name, walk.clone()));
+            if let Some((blend_from, blended_frames)) = extrapolation.blend.as_mut() {
+                *blended_frames += 1;
+                if extrapolation.blend_frames < *blended_frames {
+                    extrapolation.blend = None;
+                } else {
+                    walk.desired_velocity = blend_from.lerp(
+                        walk.desired_velocity,
+                        *blended_frames as Float / (extrapolation.blend_frames + 1) as Float,
+                    );
+                }
+            }
+            (name, walk)
+        } else if let Some((name, walk)) = extrapolation.last.as_ref() {
+            extrapolation.stalled_frames += 1;
+            extrapolation.blend = None;
+            let mut walk = walk.clone();
+            walk.desired_velocity *= (1.0
+                - extrapolation.stalled_frames as Float / (extrapolation.max_frames + 1) as Float)
+                .max(0.0);
+            (*name, walk)
+        } else {
+            continue;
+        };
+
+        extrapolation.last_desired_velocity = walk.desired_velocity;
+        controller.named_basis(name, walk);
+    }
+}
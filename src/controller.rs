@@ -1,23 +1,36 @@
+use std::time::Duration;
+
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
 use bevy::prelude::*;
 use bevy::time::Stopwatch;
-use bevy::utils::{Entry, HashMap};
-use bevy_tnua_physics_integration_layer::math::{AsF32, Float};
+use bevy::utils::{Entry, HashMap, HashSet};
+use bevy_tnua_physics_integration_layer::math::{
+    float_consts, AdjustPrecision, AsF32, Float, Quaternion, Vector3,
+};
 
+use crate::action_sensors::{
+    read_action_sensors_system, update_action_sensors_system, TnuaActionSensors,
+};
 use crate::basis_action_traits::{
     BoxableAction, BoxableBasis, DynamicAction, DynamicBasis, TnuaAction, TnuaActionContext,
-    TnuaActionInitiationDirective, TnuaActionLifecycleDirective, TnuaActionLifecycleStatus,
-    TnuaBasisContext,
+    TnuaActionEventKind, TnuaActionEventQueue, TnuaActionInitiationDirective,
+    TnuaActionLifecycleDirective, TnuaActionLifecycleStatus, TnuaBasisContext,
 };
 use crate::{
     TnuaBasis, TnuaMotor, TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker,
-    TnuaSystemSet, TnuaToggle, TnuaUserControlsSystemSet,
+    TnuaSystemSet, TnuaToggle, TnuaUserControlsSystemSet, TnuaVelChange,
 };
 
 /// The main for supporting Tnua character controller.
 ///
 /// Will not work without a physics backend plugin (like `TnuaRapier2dPlugin` or
 /// `TnuaRapier3dPlugin`)
+///
+/// The timers of the controller (action buffering, rescheduling, transition rules) tick by the
+/// [`Time`] of the schedule the plugin runs in - virtual time in [`Update`], fixed time in
+/// [`FixedUpdate`] - and only when [`TnuaSystemSet`] runs. Since the physics backend plugins
+/// prevent that system set from running while the physics is paused, the timers freeze together
+/// with the physics. To freeze them while the physics keeps running, use [`TnuaPause`].
 pub struct TnuaControllerPlugin {
     schedule: InternedScheduleLabel,
 }
@@ -38,6 +51,7 @@ impl Default for TnuaControllerPlugin {
 
 impl Plugin for TnuaControllerPlugin {
     fn build(&self, app: &mut App) {
+        app.add_event::<TnuaActionEvent>();
         app.configure_sets(
             self.schedule,
             (
@@ -52,7 +66,20 @@ impl Plugin for TnuaControllerPlugin {
         );
         app.add_systems(
             self.schedule,
-            apply_controller_system.in_set(TnuaPipelineStages::Logic),
+            read_action_sensors_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::SubservientSensors)
+                .before(TnuaPipelineStages::Logic),
+        );
+        app.add_systems(
+            self.schedule,
+            (
+                apply_controller_system,
+                apply_mounts_system,
+                update_action_sensors_system,
+            )
+                .chain()
+                .in_set(TnuaPipelineStages::Logic),
         );
     }
 }
@@ -85,13 +112,31 @@ struct FedEntry {
 ///
 /// Without [`TnuaControllerPlugin`] this component will not do anything.
 #[derive(Component, Default)]
-#[require(TnuaMotor, TnuaRigidBodyTracker, TnuaProximitySensor)]
+#[require(
+    TnuaMotor,
+    TnuaRigidBodyTracker,
+    TnuaProximitySensor,
+    TnuaActionSensors
+)]
 pub struct TnuaController {
     current_basis: Option<(&'static str, Box<dyn DynamicBasis>)>,
     actions_being_fed: HashMap<&'static str, FedEntry>,
     current_action: Option<(&'static str, Box<dyn DynamicAction>)>,
-    contender_action: Option<(&'static str, Box<dyn DynamicAction>, Stopwatch)>,
+    /// The last element is the charge duration of a charged contender action that was released
+    /// but could not start yet.
+    #[allow(clippy::type_complexity)]
+    contender_action: Option<(
+        &'static str,
+        Box<dyn DynamicAction>,
+        Stopwatch,
+        Option<Float>,
+    )>,
     action_flow_status: TnuaActionFlowStatus,
+    current_action_active_for: Stopwatch,
+    current_action_charge_duration: Option<Float>,
+    contender_buffer_time: Float,
+    up_direction: Option<Dir3>,
+    pending_teleport: Option<(Vector3, Option<Vector3>)>,
 }
 
 impl TnuaController {
@@ -130,6 +175,46 @@ impl TnuaController {
         }
     }
 
+    /// Make the basis forget everything it remembers from previous frames.
+    ///
+    /// Use this when the character was moved or rotated by something other than Tnua (e.g. after
+    /// it was a ragdoll) and the basis should start over from the current state of the rigid body
+    /// instead of trying to continue from where it left off. The inputs fed to the basis are not
+    /// affected.
+    pub fn reset_basis_state(&mut self) {
+        if let Some((_, basis)) = self.current_basis.as_mut() {
+            basis.reset_state();
+        }
+    }
+
+    /// Bring the character to a neutral state - e.g. when the game opens a menu.
+    ///
+    /// This [neutralizes the basis](Self::neutralize_basis), drops the contender action (an action
+    /// that was fed but did not start yet) and forgets all the actions fed so far. The current
+    /// action, if there is one, is not cut off - it will see that it is no longer fed and will
+    /// terminate gracefully (e.g. a jump will stop going up), so that only the passive floating of
+    /// the basis remains.
+    ///
+    /// Actions fed after calling this method (in the same frame) will not be forgotten.
+    pub fn neutralize_all(&mut self) {
+        self.neutralize_basis();
+        self.contender_action = None;
+        self.actions_being_fed.clear();
+    }
+
+    /// Move the character to `destination` in the next run of the controller.
+    ///
+    /// Unlike writing to the character's [`Transform`], this moves the rigid body through the
+    /// physics backend and resets the memory of the basis (e.g. the entity the character stands on
+    /// and the coyote time), so that the character will not try to float back toward where it was
+    /// or to move along with a platform it no longer stands on. The current action is not stopped.
+    ///
+    /// If `velocity` is `Some`, the character's velocity will be set to it (use
+    /// [`Vector3::ZERO`] to stop the character). If it is `None`, the velocity is preserved.
+    pub fn teleport(&mut self, destination: Vector3, velocity: Option<Vector3>) {
+        self.pending_teleport = Some((destination, velocity));
+    }
+
     /// The name of the currently running basis.
     ///
     /// When using the basis with it's default name, prefer to match this against
@@ -167,6 +252,45 @@ impl TnuaController {
     /// allow, for example, different animations. Otherwise prefer to use the default name with
     /// [`action`](Self::action).
     pub fn named_action<A: TnuaAction>(&mut self, name: &'static str, action: A) {
+        self.feed_action(name, action, 0.0);
+    }
+
+    /// Feed an action with [its default name](TnuaAction::NAME), buffering it for `buffer_time`
+    /// seconds.
+    ///
+    /// See [`named_buffered_action`](Self::named_buffered_action).
+    pub fn buffered_action<A: TnuaAction>(&mut self, action: A, buffer_time: Float) {
+        self.named_buffered_action(A::NAME, action, buffer_time);
+    }
+
+    /// Feed an action with a custom name, buffering it for `buffer_time` seconds.
+    ///
+    /// A buffered action remains a candidate for starting during the first `buffer_time` seconds
+    /// since it was first fed - even if the input is no longer fed, and even if its
+    /// [`initiation_decision`](TnuaAction::initiation_decision) returns
+    /// [`TnuaActionInitiationDirective::Reject`] (which is treated as
+    /// [`Delay`](TnuaActionInitiationDirective::Delay) during that time). This allows the player
+    /// to press the button slightly before the action becomes possible (e.g. dashing right before
+    /// landing) and still have the action performed.
+    ///
+    /// Note that if the action starts after the input was released, it will get
+    /// [`NoLongerFed`](TnuaActionLifecycleStatus::NoLongerFed) from its second frame - so, for
+    /// example, a buffered jump that starts after the button was released will be as short as
+    /// possible.
+    ///
+    /// Coyote time does not need this mechanism - it is handled by the basis (see
+    /// [`TnuaBuiltinWalk::coyote_time`](crate::builtins::TnuaBuiltinWalk::coyote_time)), and
+    /// applies to all the actions that check [`is_airborne`](TnuaBasis::is_airborne).
+    pub fn named_buffered_action<A: TnuaAction>(
+        &mut self,
+        name: &'static str,
+        action: A,
+        buffer_time: Float,
+    ) {
+        self.feed_action(name, action, buffer_time);
+    }
+
+    fn feed_action<A: TnuaAction>(&mut self, name: &'static str, action: A, buffer_time: Float) {
         match self.actions_being_fed.entry(name) {
             Entry::Occupied(mut entry) => {
                 entry.get_mut().fed_this_frame = true;
@@ -192,8 +316,13 @@ impl TnuaController {
                 {
                     // no action is running - but this action is rescheduled and there is no
                     // already-existing contender that would have taken priority
-                    self.contender_action =
-                        Some((name, Box::new(BoxableAction::new(action)), Stopwatch::new()));
+                    self.contender_action = Some((
+                        name,
+                        Box::new(BoxableAction::new(action)),
+                        Stopwatch::new(),
+                        None,
+                    ));
+                    self.contender_buffer_time = buffer_time;
                 } else {
                     // no action is running - will not set because button was already pressed.
                 }
@@ -204,7 +333,7 @@ impl TnuaController {
                     rescheduled_in: None,
                 });
                 if let Some(contender_action) = self.contender_action.as_mut().and_then(
-                    |(contender_name, contender_action, _)| {
+                    |(contender_name, contender_action, _, _)| {
                         if *contender_name == name {
                             let Some(contender_action) = contender_action
                                 .as_mut_any()
@@ -219,9 +348,15 @@ impl TnuaController {
                     },
                 ) {
                     contender_action.input = action;
+                    self.contender_buffer_time = buffer_time;
                 } else {
-                    self.contender_action =
-                        Some((name, Box::new(BoxableAction::new(action)), Stopwatch::new()));
+                    self.contender_action = Some((
+                        name,
+                        Box::new(BoxableAction::new(action)),
+                        Stopwatch::new(),
+                        None,
+                    ));
+                    self.contender_buffer_time = buffer_time;
                 }
             }
         }
@@ -253,6 +388,19 @@ impl TnuaController {
         Some((&boxable_action.input, &boxable_action.state))
     }
 
+    /// Mutable access to the currently running action, together with its state.
+    ///
+    /// This allows modifying a running action without feeding it again - which, for some actions,
+    /// would have restarted them (e.g.
+    /// [`TnuaBuiltinKnockbackState::refresh`](crate::builtins::TnuaBuiltinKnockbackState::refresh)).
+    /// Note that the input of an action that keeps getting fed is overwritten every frame it is
+    /// fed.
+    pub fn concrete_action_mut<A: TnuaAction>(&mut self) -> Option<(&mut A, &mut A::State)> {
+        let (_, action) = self.current_action.as_mut()?;
+        let boxable_action: &mut BoxableAction<A> = action.as_mut_any().downcast_mut()?;
+        Some((&mut boxable_action.input, &mut boxable_action.state))
+    }
+
     /// Indicator for the state and flow of movement actions.
     ///
     /// Query this every frame to keep track of the actions. For air actions,
@@ -280,12 +428,504 @@ impl TnuaController {
             None => Err(TnuaControllerHasNoBasis),
         }
     }
+
+    /// The up direction the basis and the actions used in the last frame.
+    ///
+    /// This is the opposite of the gravity direction - unless the entity has a
+    /// [`TnuaUpDirectionSmoothing`] component, in which case it may still be turning toward it.
+    pub fn up_direction(&self) -> Option<Dir3> {
+        self.up_direction
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
 #[error("The Tnua controller does not have any basis set")]
 pub struct TnuaControllerHasNoBasis;
 
+/// Restrictions on which actions are allowed to cancel which other actions.
+///
+/// By default, any action that gets [allowed to initiate](TnuaAction::initiation_decision) will
+/// cancel the currently running action. Add this component to the character entity (the one with
+/// the [`TnuaController`]) to forbid specific cancellations - e.g. "a dash cannot be cancelled
+/// into a jump during its first 0.2 seconds":
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::builtins::TnuaBuiltinDash;
+/// # use bevy_tnua::controller::TnuaActionTransitionRules;
+/// # let mut commands: Commands = panic!();
+/// # let mut cmd = commands.spawn_empty();
+/// cmd.insert(
+///     TnuaActionTransitionRules::default()
+///         .forbid_during(TnuaBuiltinDash::NAME, TnuaBuiltinJump::NAME, 0.2),
+/// );
+/// ```
+///
+/// A forbidden contender action is not rejected - it is delayed, the same way as if its
+/// [`initiation_decision`](TnuaAction::initiation_decision) returned
+/// [`TnuaActionInitiationDirective::Delay`]. If it is still fed when the restriction no longer
+/// applies (or when the running action finishes) it will start normally.
+///
+/// The rules use the action names - [`TnuaAction::NAME`] for actions fed with
+/// [`TnuaController::action`], or the custom name for actions fed with
+/// [`TnuaController::named_action`].
+#[derive(Component, Default, Debug, Clone)]
+pub struct TnuaActionTransitionRules {
+    forbidden: HashMap<(&'static str, &'static str), Float>,
+}
+
+impl TnuaActionTransitionRules {
+    /// Never allow the `into` action to cancel the `from` action.
+    pub fn forbid(self, from: &'static str, into: &'static str) -> Self {
+        self.forbid_during(from, into, Float::INFINITY)
+    }
+
+    /// Do not allow the `into` action to cancel the `from` action during the first `seconds` of
+    /// the `from` action.
+    pub fn forbid_during(mut self, from: &'static str, into: &'static str, seconds: Float) -> Self {
+        self.forbidden.insert((from, into), seconds);
+        self
+    }
+
+    /// Remove a restriction set by [`forbid`](Self::forbid) or
+    /// [`forbid_during`](Self::forbid_during).
+    pub fn allow(&mut self, from: &'static str, into: &'static str) {
+        self.forbidden.remove(&(from, into));
+    }
+
+    /// Check if the `into` action may cancel the `from` action after the latter has been active
+    /// for `from_active_for` seconds.
+    pub fn is_allowed(&self, from: &str, into: &str, from_active_for: Float) -> bool {
+        self.forbidden
+            .get(&(from, into))
+            .is_none_or(|forbidden_for| *forbidden_for <= from_active_for)
+    }
+}
+
+/// Makes the controller trigger [observer](Observer) hooks on the character entity.
+///
+/// Add this component to the character entity (the one with the [`TnuaController`]) to have the
+/// controller trigger, every frame, the [`TnuaBeforeBasisApply`], [`TnuaAfterActionApply`] and
+/// [`TnuaMotorFinalized`] events with the character entity as their target. Observe them with
+/// [`EntityCommands::observe`] (for a specific character) or [`App::add_observer`] (for all the
+/// characters):
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::controller::{TnuaControllerHooks, TnuaMotorFinalized};
+/// # let mut commands: Commands = panic!();
+/// # let mut cmd = commands.spawn_empty();
+/// cmd.insert(TnuaControllerHooks).observe(|trigger: Trigger<TnuaMotorFinalized>| {
+///     info!("{} motor: {:?}", trigger.entity(), trigger.event().lin);
+/// });
+/// ```
+///
+/// Since the controller runs the characters in parallel, the observers cannot run in the middle of
+/// it. The events are triggered right after the controller system, and each of them carries a
+/// snapshot of the relevant data from the point in the controller it describes.
+#[derive(Component, Default)]
+pub struct TnuaControllerHooks;
+
+/// Triggered by the controller before applying the basis. See [`TnuaControllerHooks`].
+#[derive(Event, Debug, Clone)]
+pub struct TnuaBeforeBasisApply {
+    /// The name of the basis.
+    pub basis_name: &'static str,
+    /// The up direction the basis is applied with.
+    pub up_direction: Dir3,
+}
+
+/// Triggered by the controller after applying an action. See [`TnuaControllerHooks`].
+///
+/// When an action is cancelled into another action, this is triggered for both.
+#[derive(Event, Debug, Clone)]
+pub struct TnuaAfterActionApply {
+    /// The name of the action.
+    pub action_name: &'static str,
+    /// The lifecycle status the action was applied with.
+    pub lifecycle_status: TnuaActionLifecycleStatus,
+    /// The directive the action returned.
+    pub directive: TnuaActionLifecycleDirective,
+    /// The linear part of the motor, after the action was applied.
+    pub lin: TnuaVelChange,
+    /// The angular part of the motor, after the action was applied.
+    pub ang: TnuaVelChange,
+}
+
+/// Triggered by the controller after it has finished setting the motor. See
+/// [`TnuaControllerHooks`].
+#[derive(Event, Debug, Clone)]
+pub struct TnuaMotorFinalized {
+    /// The linear part of the motor.
+    pub lin: TnuaVelChange,
+    /// The angular part of the motor.
+    pub ang: TnuaVelChange,
+}
+
+/// Sent by the controller for each event an action [emitted](TnuaActionContext::emit).
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::TnuaActionEventKind;
+/// # use bevy_tnua::controller::TnuaActionEvent;
+/// fn play_sounds(mut action_events: EventReader<TnuaActionEvent>) {
+///     for action_event in action_events.read() {
+///         match action_event.kind {
+///             TnuaActionEventKind::JumpTakeoff => {
+///                 // Play the jump sound
+///             }
+///             TnuaActionEventKind::LandImpact { velocity } => {
+///                 // Play the landing sound, louder for harder landings
+///             }
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
+#[derive(Event, Debug, Clone)]
+pub struct TnuaActionEvent {
+    /// The character entity.
+    pub entity: Entity,
+    /// The name of the action that emitted the event.
+    pub action_name: &'static str,
+    /// The event itself.
+    pub kind: TnuaActionEventKind,
+}
+
+/// What the controller decided to do with the character in the current frame.
+///
+/// Add this component to the character entity (the one with the [`TnuaController`]) and the
+/// controller will fill it at the end of its run, in [`TnuaPipelineStages::Logic`]. Systems that
+/// need to know the decision in the same frame, before the physics runs (e.g. for moving a camera
+/// ahead of the character), should read it in a system that runs
+/// `.after(TnuaPipelineStages::Logic).before(TnuaPipelineStages::Motors)`.
+///
+/// This is a read-only report - changing it has no effect. Note that systems running between the
+/// `Logic` and `Motors` stages may still modify the [`TnuaMotor`], and that a
+/// [`TnuaMotorFilter`](crate::TnuaMotorFilter) is only applied by the physics backend.
+///
+/// The report is not updated while [`TnuaToggle::Disabled`] is set.
+#[derive(Component, Default, Debug, Clone)]
+pub struct TnuaMotorReport {
+    /// The name of the basis that was applied.
+    pub basis_name: Option<&'static str>,
+    /// The name of the action that was applied (if any).
+    pub action_name: Option<&'static str>,
+    /// The linear part of the motor.
+    pub lin: TnuaVelChange,
+    /// The angular part of the motor.
+    pub ang: TnuaVelChange,
+    /// Where the character will be teleported to (if at all).
+    pub teleport: Option<Vector3>,
+    /// The duration of the frame the decision was made for.
+    pub frame_duration: Float,
+}
+
+impl TnuaMotorReport {
+    /// The total change to the linear velocity in this frame, including the acceleration.
+    pub fn lin_vel_change(&self) -> Vector3 {
+        self.lin.calc_boost(self.frame_duration)
+    }
+
+    /// The total change to the angular velocity in this frame, including the acceleration.
+    pub fn ang_vel_change(&self) -> Vector3 {
+        self.ang.calc_boost(self.frame_duration)
+    }
+}
+
+enum PendingHook {
+    BeforeBasisApply(TnuaBeforeBasisApply),
+    AfterActionApply(TnuaAfterActionApply),
+    MotorFinalized(TnuaMotorFinalized),
+}
+
+/// Suppresses the initiation of new actions for a short time after the character lands.
+///
+/// Add this component to the character entity (the one with the [`TnuaController`]) to give
+/// landings a brief recovery period. During the lag, contender actions are delayed - the same way
+/// as if their [`initiation_decision`](TnuaAction::initiation_decision) returned
+/// [`TnuaActionInitiationDirective::Delay`] - so an action that is still fed when the lag is over
+/// (or one that was buffered with [`TnuaController::buffered_action`]) will start normally. The
+/// basis is not affected, so the character can still walk during the lag.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::controller::TnuaLandingLag;
+/// # let mut commands: Commands = panic!();
+/// # let mut cmd = commands.spawn_empty();
+/// cmd.insert(
+///     TnuaLandingLag::new(0.1)
+///         // Short hops should not cause landing lag.
+///         .after_airborne_for(0.5)
+///         // Allow jumping right after landing.
+///         .exempt(TnuaBuiltinJump::NAME),
+/// );
+/// ```
+///
+/// Use [`remaining`](Self::remaining) to drive a landing animation.
+#[derive(Component, Debug, Clone)]
+pub struct TnuaLandingLag {
+    /// The duration, in seconds, of the lag.
+    pub duration: Float,
+
+    /// Landings only cause lag if the character was airborne for at least this many seconds.
+    pub min_airborne_time: Float,
+
+    exempt_actions: HashSet<&'static str>,
+    airborne_for: Option<Float>,
+    remaining: Float,
+}
+
+impl TnuaLandingLag {
+    /// Suppress new actions for `duration` seconds after every landing.
+    ///
+    /// Use [`after_airborne_for`](Self::after_airborne_for) to only cause lag after longer
+    /// falls, and [`exempt`](Self::exempt) to allow specific actions during the lag.
+    pub fn new(duration: Float) -> Self {
+        Self {
+            duration,
+            min_airborne_time: 0.0,
+            exempt_actions: Default::default(),
+            airborne_for: None,
+            remaining: 0.0,
+        }
+    }
+
+    /// Only cause lag when landing after being airborne for at least `seconds`.
+    pub fn after_airborne_for(self, seconds: Float) -> Self {
+        Self {
+            min_airborne_time: seconds,
+            ..self
+        }
+    }
+
+    /// Allow the action to start during the lag.
+    ///
+    /// The action is identified by its name - [`TnuaAction::NAME`] for actions fed with
+    /// [`TnuaController::action`], or the custom name for actions fed with
+    /// [`TnuaController::named_action`].
+    pub fn exempt(mut self, action_name: &'static str) -> Self {
+        self.exempt_actions.insert(action_name);
+        self
+    }
+
+    /// The time, in seconds, until the lag is over. Zero when there is no lag.
+    pub fn remaining(&self) -> Float {
+        self.remaining
+    }
+
+    /// Check if the character is currently in landing lag.
+    pub fn is_lagging(&self) -> bool {
+        0.0 < self.remaining
+    }
+
+    /// Check if the action is allowed to start now.
+    pub fn is_allowed(&self, action_name: &str) -> bool {
+        !self.is_lagging() || self.exempt_actions.contains(action_name)
+    }
+
+    fn update(&mut self, is_airborne: bool, delta: Float) {
+        if is_airborne {
+            self.remaining = 0.0;
+            *self.airborne_for.get_or_insert(0.0) += delta;
+        } else if let Some(airborne_for) = self.airborne_for.take() {
+            if self.min_airborne_time <= airborne_for {
+                self.remaining = self.duration;
+            }
+        } else {
+            self.remaining = (self.remaining - delta).max(0.0);
+        }
+    }
+}
+
+/// Additional restrictions on which surfaces the character can stand on.
+///
+/// By default, the walk basis considers any surface that is not steeper than
+/// [`max_slope`](crate::builtins::TnuaBuiltinWalk::max_slope) as walkable. Add this component to
+/// the character entity (the one with the [`TnuaController`]) to reject surfaces by other
+/// criteria - e.g. by the entity the character stands on. Surfaces rejected by the filter are
+/// treated like slopes that are too steep - the character will slip off them and will be
+/// considered airborne.
+///
+/// The predicate receives the entity detected by the proximity sensor and the normal of the
+/// surface at the contact point. Since it cannot access the ECS world, information about the
+/// entities must be captured in the closure - which means the component may need to be replaced
+/// when that information changes.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::controller::TnuaWalkableFilter;
+/// # let mut commands: Commands = panic!();
+/// # let mut cmd = commands.spawn_empty();
+/// # let lava_entity: Entity = panic!();
+/// cmd.insert(TnuaWalkableFilter::new(move |entity, _normal| {
+///     entity != lava_entity
+/// }));
+/// ```
+#[derive(Component)]
+pub struct TnuaWalkableFilter {
+    predicate: Box<dyn Fn(Entity, Dir3) -> bool + Send + Sync>,
+}
+
+impl TnuaWalkableFilter {
+    pub fn new(predicate: impl Fn(Entity, Dir3) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            predicate: Box::new(predicate),
+        }
+    }
+
+    /// Checks if the character can stand on the given entity at a point with the given normal.
+    pub fn is_walkable(&self, entity: Entity, normal: Dir3) -> bool {
+        (self.predicate)(entity, normal)
+    }
+}
+
+/// Limits the speed in which the up direction of the character can change.
+///
+/// Tnua uses the opposite of the gravity direction as the up direction for the basis and the
+/// actions. Without this component, when the gravity changes (e.g. when walking around a small
+/// planet, or when entering a gravity-flip zone) the up direction changes immediately, which makes
+/// the character snap to its new orientation. With this component on the character entity (the
+/// one with the [`TnuaController`]), the up direction will turn toward the new one at a limited
+/// angular speed - and since all the builtins use that up direction, the character will smoothly
+/// reorient.
+#[derive(Component)]
+pub struct TnuaUpDirectionSmoothing {
+    /// The maximum angular speed, in radians per second, in which the up direction can turn.
+    pub max_angvel: Float,
+}
+
+/// Makes the character stick to the surface it stands on, regardless of the gravity ("magnet
+/// boots").
+///
+/// With this component on the character entity (the one with the [`TnuaController`]), whenever the
+/// proximity sensor detects a surface, the gravity is replaced by a pull toward that surface, and
+/// the up direction used by the basis and the actions turns toward the surface's normal. This
+/// allows the character to walk up walls, on ceilings and around pipes.
+///
+/// When the character is airborne, the actual gravity is restored and the up direction turns back
+/// to be its opposite.
+///
+/// Note that the basis still limits the slopes the character can stand on (e.g.
+/// [`max_slope`](crate::builtins::TnuaBuiltinWalk::max_slope)), and since the slope is measured
+/// relative to the up direction, the [`max_angvel`](Self::max_angvel) determines how sharp a
+/// corner the character can walk around.
+#[derive(Component)]
+pub struct TnuaSurfaceAdhesion {
+    /// The acceleration pulling the character toward the surface, replacing the gravity.
+    pub strength: Float,
+
+    /// The maximum angular speed, in radians per second, in which the up direction can turn
+    /// toward the surface's normal (or back toward the gravity).
+    ///
+    /// When this component is present, this overrides
+    /// [`TnuaUpDirectionSmoothing::max_angvel`].
+    pub max_angvel: Float,
+}
+
+/// Applies the basis in multiple substeps every frame.
+///
+/// At low tick rates (e.g. a fixed timestep of 20-30 Hz) the basis' math is applied over long
+/// frames, which makes the float spring overshoot - causing the character to penetrate the ground
+/// or bounce on it. With this component on the character entity (the one with the
+/// [`TnuaController`]), the basis is applied the given number of times per frame, each time with a
+/// fraction of the frame duration and against the position and velocity predicted from the
+/// previous substeps. The resulting motor commands are combined into a single motor command with
+/// the same total velocity change.
+///
+/// Only the basis is substepped - actions are still applied once per frame, on top of the combined
+/// result of the basis. The predictions do not involve the physics engine, so the sensor outputs
+/// are extrapolated from the character's velocity and the velocity of whatever the sensor hit.
+#[derive(Component)]
+pub struct TnuaControllerSubsteps(pub usize);
+
+/// Freezes the controller's timers.
+///
+/// While this component is on the character entity (the one with the [`TnuaController`]), the
+/// basis is still applied (so the character keeps floating), but actions do not progress - the
+/// current action is not applied, contender actions do not start, and the timers used for action
+/// buffering, rescheduling and [transition rules](TnuaActionTransitionRules) do not tick. When
+/// the component is removed, everything continues from where it was frozen.
+///
+/// This is useful when the game is paused but the app (and possibly the physics) keeps running.
+/// Consider combining it with [`TnuaController::neutralize_all`].
+#[derive(Component)]
+pub struct TnuaPause;
+
+/// Scales the frame duration the controller uses, for when the physics runs slower (or faster)
+/// than the schedule Tnua runs in.
+///
+/// Bullet-time effects that slow down only the physics (e.g. by changing the relative speed of
+/// Avian's `Time<Physics>`) while Tnua keeps running by the regular [`Time`] make the basis and the
+/// actions compute their accelerations and boosts for longer frames than the ones the physics
+/// actually simulates - which makes the character move and react too strongly. Insert this
+/// resource with the same factor by which the physics is slowed (e.g. `0.5` for half speed), and
+/// the controller math and timers will use the dilated frame duration, so that the character
+/// follows the same trajectory as at normal speed - only over more frames.
+///
+/// There is no need for this resource when the schedule's [`Time`] itself is slowed (e.g. with
+/// [`Time<Virtual>::set_relative_speed`]), since the controller already uses it.
+///
+/// Negative factors are treated as `0.0` (which freezes the controller), and non-finite factors
+/// are treated as `1.0`.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct TnuaTimeDilation(pub Float);
+
+impl TnuaTimeDilation {
+    /// The factor the frame duration is actually scaled by.
+    pub fn factor(&self) -> Float {
+        if self.0.is_finite() {
+            self.0.max(0.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+impl Default for TnuaTimeDilation {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Makes the character ride another entity (a mount or a vehicle).
+///
+/// While this component is on the character entity (the one with the [`TnuaController`]), the
+/// controller stops applying the basis and the actions - any action that was going on is ended,
+/// and actions fed to the controller are ignored. Instead, the motor keeps the character at the
+/// [`anchor`](Self::anchor) point on the [`mount`](Self::mount) entity, moving along with it.
+/// The sensors and the rigid body tracker keep getting updated.
+///
+/// To dismount, remove the component. Since the character was moved by the motor (and not
+/// teleported), it keeps the velocity it had on the mount and the basis takes over from there.
+///
+/// If the mount entity is despawned (or has no [`GlobalTransform`]), the character is dismounted
+/// automatically.
+///
+/// Note that the character's collider still participates in the physics simulation while it is
+/// mounted, so it is usually required to make it not collide with the mount (e.g. with collision
+/// layers).
+#[derive(Component)]
+pub struct TnuaMounted {
+    /// The entity the character rides.
+    pub mount: Entity,
+    /// The position of the character, in the mount's coord system.
+    pub anchor: Vector3,
+    previous_anchor: Option<(Vector3, Quaternion)>,
+}
+
+impl TnuaMounted {
+    pub fn new(mount: Entity, anchor: Vector3) -> Self {
+        Self {
+            mount,
+            anchor,
+            previous_anchor: None,
+        }
+    }
+}
+
 /// The result of [`TnuaController::action_flow_status()`].
 #[derive(Debug, Default, Clone)]
 pub enum TnuaActionFlowStatus {
@@ -345,261 +985,710 @@ impl TnuaActionFlowStatus {
     }
 }
 
+fn dilated_delta(time: &Time, time_dilation: Option<&TnuaTimeDilation>) -> (Duration, Float) {
+    let delta = match time_dilation {
+        // Even finite factors can be big enough to overflow the `Duration`.
+        #[allow(clippy::unnecessary_cast)]
+        Some(time_dilation) => {
+            Duration::try_from_secs_f64(time.delta().as_secs_f64() * time_dilation.factor() as f64)
+                .unwrap_or(Duration::MAX)
+        }
+        None => time.delta(),
+    };
+    (delta, delta.as_secs_f64() as Float)
+}
+
 #[allow(clippy::type_complexity)]
 fn apply_controller_system(
     time: Res<Time>,
+    time_dilation: Option<Res<TnuaTimeDilation>>,
     mut query: Query<(
         &mut TnuaController,
         &TnuaRigidBodyTracker,
         &mut TnuaProximitySensor,
         &mut TnuaMotor,
+        &mut TnuaActionSensors,
         Option<&TnuaToggle>,
+        Option<&TnuaActionTransitionRules>,
+        Option<&mut TnuaLandingLag>,
+        Option<&TnuaWalkableFilter>,
+        Has<TnuaMounted>,
+        Option<&TnuaUpDirectionSmoothing>,
+        Option<&TnuaSurfaceAdhesion>,
+        Option<&TnuaControllerSubsteps>,
+        Has<TnuaPause>,
+        (
+            Entity,
+            Has<TnuaControllerHooks>,
+            Option<&mut TnuaMotorReport>,
+        ),
     )>,
+    parallel_commands: ParallelCommands,
 ) {
-    let frame_duration = time.delta().as_secs_f64() as Float;
+    let (delta, frame_duration) = dilated_delta(&time, time_dilation.as_deref());
     if frame_duration == 0.0 {
         return;
     }
-    for (mut controller, tracker, mut sensor, mut motor, tnua_toggle) in query.iter_mut() {
-        match tnua_toggle.copied().unwrap_or_default() {
-            TnuaToggle::Disabled => continue,
-            TnuaToggle::SenseOnly => {}
-            TnuaToggle::Enabled => {}
-        }
+    query.par_iter_mut().for_each(
+        |(
+            mut controller,
+            tracker,
+            mut sensor,
+            mut motor,
+            mut action_sensors,
+            tnua_toggle,
+            transition_rules,
+            mut landing_lag,
+            walkable_filter,
+            is_mounted,
+            up_direction_smoothing,
+            surface_adhesion,
+            substeps,
+            is_paused,
+            (entity, has_hooks, motor_report),
+        )| {
+            match tnua_toggle.copied().unwrap_or_default() {
+                TnuaToggle::Disabled => return,
+                TnuaToggle::SenseOnly => {}
+                TnuaToggle::Enabled => {}
+            }
 
-        let controller = controller.as_mut();
+            let controller = controller.as_mut();
+            let timers_delta = if is_paused { Duration::ZERO } else { delta };
+            let mut pending_hooks = Vec::new();
+            let action_events = TnuaActionEventQueue::default();
+            let mut pending_action_events = Vec::new();
+            let mut collect_action_events = |action_name: &'static str| {
+                pending_action_events.extend(action_events.take().into_iter().map(|kind| {
+                    TnuaActionEvent {
+                        entity,
+                        action_name,
+                        kind,
+                    }
+                }));
+            };
+            motor.teleport = None;
 
-        match controller.action_flow_status {
-            TnuaActionFlowStatus::NoAction | TnuaActionFlowStatus::ActionOngoing(_) => {}
-            TnuaActionFlowStatus::ActionEnded(_) => {
-                controller.action_flow_status = TnuaActionFlowStatus::NoAction;
-            }
-            TnuaActionFlowStatus::ActionStarted(action_name)
-            | TnuaActionFlowStatus::Cancelled {
-                old: _,
-                new: action_name,
-            } => {
-                controller.action_flow_status = TnuaActionFlowStatus::ActionOngoing(action_name);
+            match controller.action_flow_status {
+                TnuaActionFlowStatus::NoAction | TnuaActionFlowStatus::ActionOngoing(_) => {}
+                TnuaActionFlowStatus::ActionEnded(_) => {
+                    controller.action_flow_status = TnuaActionFlowStatus::NoAction;
+                }
+                TnuaActionFlowStatus::ActionStarted(action_name)
+                | TnuaActionFlowStatus::Cancelled {
+                    old: _,
+                    new: action_name,
+                } => {
+                    controller.action_flow_status = TnuaActionFlowStatus::ActionOngoing(action_name);
+                }
             }
-        }
 
-        if let Some((_, basis)) = controller.current_basis.as_mut() {
-            let up_direction = Dir3::new(-tracker.gravity.f32()).unwrap_or(Dir3::Y);
-            let basis = basis.as_mut();
-            basis.apply(
-                TnuaBasisContext {
-                    frame_duration,
-                    tracker,
-                    proximity_sensor: sensor.as_ref(),
-                    up_direction,
-                },
-                motor.as_mut(),
-            );
-            let sensor_cast_range_for_basis = basis.proximity_sensor_cast_range();
-
-            // To streamline TnuaActionContext creation
-            let proximity_sensor = sensor.as_ref();
-
-            let has_valid_contender = if let Some((_, contender_action, being_fed_for)) =
-                &mut controller.contender_action
-            {
-                let initiation_decision = contender_action.initiation_decision(
-                    TnuaActionContext {
-                        frame_duration,
-                        tracker,
-                        proximity_sensor,
-                        basis,
-                        up_direction,
-                    },
-                    being_fed_for,
-                );
-                being_fed_for.tick(time.delta());
-                match initiation_decision {
-                    TnuaActionInitiationDirective::Reject => {
-                        controller.contender_action = None;
-                        false
+            if is_mounted {
+                if let Some((name, _)) = controller.current_action.take() {
+                    controller.action_flow_status = TnuaActionFlowStatus::ActionEnded(name);
+                }
+                controller.contender_action = None;
+                action_sensors.requested.clear();
+            } else if let Some((basis_name, basis)) = controller.current_basis.as_mut() {
+                let adhere_to = surface_adhesion
+                    .zip(sensor.output.as_ref())
+                    .map(|(surface_adhesion, sensor_output)| {
+                        (surface_adhesion, sensor_output.normal)
+                    });
+                let mut up_direction = if let Some((_, surface_normal)) = adhere_to {
+                    surface_normal
+                } else {
+                    Dir3::new(-tracker.gravity.f32()).unwrap_or(Dir3::Y)
+                };
+                let up_direction_max_angvel = if let Some(surface_adhesion) = surface_adhesion {
+                    Some(surface_adhesion.max_angvel)
+                } else {
+                    up_direction_smoothing.map(|smoothing| smoothing.max_angvel)
+                };
+                if let (Some(max_angvel), Some(previous_up_direction)) =
+                    (up_direction_max_angvel, controller.up_direction)
+                {
+                    let angle = previous_up_direction
+                        .angle_between(*up_direction)
+                        .adjust_precision();
+                    let max_angle = max_angvel * frame_duration;
+                    if max_angle < angle {
+                        up_direction =
+                            previous_up_direction.slerp(up_direction, (max_angle / angle).f32());
                     }
-                    TnuaActionInitiationDirective::Delay => false,
-                    TnuaActionInitiationDirective::Allow => true,
                 }
-            } else {
-                false
-            };
+                controller.up_direction = Some(up_direction);
 
-            if let Some((name, current_action)) = controller.current_action.as_mut() {
-                let lifecycle_status = if has_valid_contender {
-                    TnuaActionLifecycleStatus::CancelledInto
-                } else if controller
-                    .actions_being_fed
-                    .get(name)
-                    .map(|fed_entry| fed_entry.fed_this_frame)
-                    .unwrap_or(false)
-                {
-                    TnuaActionLifecycleStatus::StillFed
+                // When adhering to a surface, the basis and the actions should see the adhesion
+                // instead of the actual gravity. The actual gravity gets replaced by the adhesion
+                // in the motor after they are applied.
+                let adhesion_tracker;
+                let gravity_replacement;
+                let tracker = if let Some((surface_adhesion, _)) = adhere_to {
+                    let adhesion_gravity =
+                        -surface_adhesion.strength * up_direction.adjust_precision();
+                    gravity_replacement = Some(adhesion_gravity - tracker.gravity);
+                    adhesion_tracker = TnuaRigidBodyTracker {
+                        gravity: adhesion_gravity,
+                        ..*tracker
+                    };
+                    &adhesion_tracker
                 } else {
-                    TnuaActionLifecycleStatus::NoLongerFed
+                    gravity_replacement = None;
+                    tracker
                 };
-
-                let directive = current_action.apply(
-                    TnuaActionContext {
-                        frame_duration,
-                        tracker,
-                        proximity_sensor,
-                        basis,
+                if has_hooks {
+                    pending_hooks.push(PendingHook::BeforeBasisApply(TnuaBeforeBasisApply {
+                        basis_name,
                         up_direction,
-                    },
-                    lifecycle_status,
-                    motor.as_mut(),
-                );
-                if current_action.violates_coyote_time() {
-                    basis.violate_coyote_time();
+                    }));
+                }
+                let basis = basis.as_mut();
+                match substeps.map_or(1, |substeps| substeps.0) {
+                    0 | 1 => {
+                        basis.apply(
+                            TnuaBasisContext {
+                                frame_duration,
+                                tracker,
+                                proximity_sensor: sensor.as_ref(),
+                                up_direction,
+                                walkable_filter,
+                            },
+                            motor.as_mut(),
+                        );
+                    }
+                    substeps => {
+                        apply_basis_in_substeps(
+                            basis,
+                            substeps,
+                            TnuaBasisContext {
+                                frame_duration,
+                                tracker,
+                                proximity_sensor: sensor.as_ref(),
+                                up_direction,
+                                walkable_filter,
+                            },
+                            motor.as_mut(),
+                        );
+                    }
+                }
+                let sensor_cast_range_for_basis = basis.proximity_sensor_cast_range();
+
+                if let Some(landing_lag) = landing_lag.as_mut() {
+                    landing_lag.update(basis.is_airborne(), timers_delta.as_secs_f64() as Float);
                 }
-                let reschedule_action =
-                    |actions_being_fed: &mut HashMap<&'static str, FedEntry>,
-                     after_seconds: Float| {
-                        if let Some(fed_entry) = actions_being_fed.get_mut(name) {
-                            fed_entry.rescheduled_in =
-                                Some(Timer::from_seconds(after_seconds.f32(), TimerMode::Once));
+
+                // To streamline TnuaActionContext creation
+                let proximity_sensor = sensor.as_ref();
+
+                let mut contender_charge_duration = None;
+                let has_valid_contender = if is_paused {
+                    false
+                } else if let Some((name, contender_action, being_fed_for, released_charge)) =
+                    &mut controller.contender_action
+                {
+                    let name = *name;
+                    let initiation_decision = contender_action.initiation_decision(
+                        TnuaActionContext {
+                            frame_duration,
+                            tracker,
+                            proximity_sensor,
+                            basis,
+                            up_direction,
+                            walkable_filter,
+                            sensors: &action_sensors,
+                            charge_duration: None,
+                            events: &action_events,
+                        },
+                        being_fed_for,
+                    );
+                    collect_action_events(name);
+                    let being_fed_for_secs = being_fed_for.elapsed_secs() as Float;
+                    being_fed_for.tick(timers_delta);
+                    let ready_to_start = match initiation_decision {
+                        TnuaActionInitiationDirective::Reject => {
+                            if being_fed_for_secs >= controller.contender_buffer_time {
+                                controller.contender_action = None;
+                            }
+                            false
+                        }
+                        TnuaActionInitiationDirective::Delay => false,
+                        TnuaActionInitiationDirective::Allow => true,
+                        TnuaActionInitiationDirective::Charge => {
+                            let still_charging = controller
+                                .actions_being_fed
+                                .get(name)
+                                .map(|fed_entry| fed_entry.fed_this_frame)
+                                .unwrap_or(false);
+                            if still_charging {
+                                *released_charge = None;
+                            } else {
+                                // The charge ends when the action is released, even if something
+                                // else prevents it from starting right away.
+                                contender_charge_duration =
+                                    Some(*released_charge.get_or_insert(being_fed_for_secs));
+                            }
+                            !still_charging
                         }
                     };
-                match directive {
-                    TnuaActionLifecycleDirective::StillActive => {
-                        if !lifecycle_status.is_active()
-                            && matches!(
-                                controller.action_flow_status,
-                                TnuaActionFlowStatus::ActionOngoing(_)
-                            )
-                        {
-                            controller.action_flow_status = TnuaActionFlowStatus::ActionEnded(name);
+                    ready_to_start
+                        && landing_lag
+                            .as_ref()
+                            .is_none_or(|landing_lag| landing_lag.is_allowed(name))
+                        && match (&controller.current_action, transition_rules) {
+                            (Some((current_name, _)), Some(transition_rules)) => transition_rules
+                                .is_allowed(
+                                    current_name,
+                                    name,
+                                    controller.current_action_active_for.elapsed_secs() as Float,
+                                ),
+                            _ => true,
                         }
+                } else {
+                    false
+                };
+
+                if let Some((name, current_action)) = controller
+                    .current_action
+                    .as_mut()
+                    .filter(|_| !is_paused)
+                {
+                    let lifecycle_status = if has_valid_contender {
+                        TnuaActionLifecycleStatus::CancelledInto
+                    } else if controller
+                        .actions_being_fed
+                        .get(name)
+                        .map(|fed_entry| fed_entry.fed_this_frame)
+                        .unwrap_or(false)
+                    {
+                        TnuaActionLifecycleStatus::StillFed
+                    } else {
+                        TnuaActionLifecycleStatus::NoLongerFed
+                    };
+
+                    let directive = current_action.apply(
+                        TnuaActionContext {
+                            frame_duration,
+                            tracker,
+                            proximity_sensor,
+                            basis,
+                            up_direction,
+                            walkable_filter,
+                            sensors: &action_sensors,
+                            charge_duration: controller.current_action_charge_duration,
+                            events: &action_events,
+                        },
+                        lifecycle_status,
+                        motor.as_mut(),
+                    );
+                    collect_action_events(name);
+                    if has_hooks {
+                        pending_hooks.push(PendingHook::AfterActionApply(TnuaAfterActionApply {
+                            action_name: name,
+                            lifecycle_status,
+                            directive,
+                            lin: motor.lin.clone(),
+                            ang: motor.ang.clone(),
+                        }));
                     }
-                    TnuaActionLifecycleDirective::Finished
-                    | TnuaActionLifecycleDirective::Reschedule { .. } => {
-                        if let TnuaActionLifecycleDirective::Reschedule { after_seconds } =
-                            directive
-                        {
-                            reschedule_action(&mut controller.actions_being_fed, after_seconds);
-                        }
-                        controller.current_action = if has_valid_contender {
-                            let (contender_name, mut contender_action, _) = controller.contender_action.take().expect("has_valid_contender can only be true if contender_action is Some");
-                            if let Some(contender_fed_entry) =
-                                controller.actions_being_fed.get_mut(contender_name)
+                    if current_action.violates_coyote_time() {
+                        basis.violate_coyote_time();
+                    }
+                    let reschedule_action =
+                        |actions_being_fed: &mut HashMap<&'static str, FedEntry>,
+                         after_seconds: Float| {
+                            if let Some(fed_entry) = actions_being_fed.get_mut(name) {
+                                fed_entry.rescheduled_in =
+                                    Some(Timer::from_seconds(after_seconds.f32(), TimerMode::Once));
+                            }
+                        };
+                    match directive {
+                        TnuaActionLifecycleDirective::StillActive => {
+                            if !lifecycle_status.is_active()
+                                && matches!(
+                                    controller.action_flow_status,
+                                    TnuaActionFlowStatus::ActionOngoing(_)
+                                )
                             {
-                                contender_fed_entry.rescheduled_in = None;
+                                controller.action_flow_status = TnuaActionFlowStatus::ActionEnded(name);
                             }
-                            let contender_directive = contender_action.apply(
-                                TnuaActionContext {
-                                    frame_duration,
-                                    tracker,
-                                    proximity_sensor,
-                                    basis,
-                                    up_direction,
-                                },
-                                TnuaActionLifecycleStatus::CancelledFrom,
-                                motor.as_mut(),
-                            );
-                            if contender_action.violates_coyote_time() {
-                                basis.violate_coyote_time();
+                        }
+                        TnuaActionLifecycleDirective::Finished
+                        | TnuaActionLifecycleDirective::Reschedule { .. } => {
+                            if let TnuaActionLifecycleDirective::Reschedule { after_seconds } =
+                                directive
+                            {
+                                reschedule_action(&mut controller.actions_being_fed, after_seconds);
                             }
-                            match contender_directive {
-                                TnuaActionLifecycleDirective::StillActive => {
-                                    if matches!(
-                                        controller.action_flow_status,
-                                        TnuaActionFlowStatus::ActionOngoing(_)
-                                    ) {
-                                        controller.action_flow_status =
-                                            TnuaActionFlowStatus::Cancelled {
-                                                old: name,
-                                                new: contender_name,
-                                            };
-                                    } else {
-                                        controller.action_flow_status =
-                                            TnuaActionFlowStatus::ActionStarted(contender_name);
-                                    }
-                                    Some((contender_name, contender_action))
+                            controller.current_action = if has_valid_contender {
+                                let (contender_name, mut contender_action, _, _) = controller.contender_action.take().expect("has_valid_contender can only be true if contender_action is Some");
+                                if let Some(contender_fed_entry) =
+                                    controller.actions_being_fed.get_mut(contender_name)
+                                {
+                                    contender_fed_entry.rescheduled_in = None;
                                 }
-                                TnuaActionLifecycleDirective::Finished => {
-                                    if matches!(
-                                        controller.action_flow_status,
-                                        TnuaActionFlowStatus::ActionOngoing(_)
-                                    ) {
-                                        controller.action_flow_status =
-                                            TnuaActionFlowStatus::ActionEnded(name);
-                                    }
-                                    None
+                                let contender_directive = contender_action.apply(
+                                    TnuaActionContext {
+                                        frame_duration,
+                                        tracker,
+                                        proximity_sensor,
+                                        basis,
+                                        up_direction,
+                                        walkable_filter,
+                                        sensors: &action_sensors,
+                                        charge_duration: contender_charge_duration,
+                                        events: &action_events,
+                                    },
+                                    TnuaActionLifecycleStatus::CancelledFrom,
+                                    motor.as_mut(),
+                                );
+                                collect_action_events(contender_name);
+                                if has_hooks {
+                                    pending_hooks.push(PendingHook::AfterActionApply(
+                                        TnuaAfterActionApply {
+                                            action_name: contender_name,
+                                            lifecycle_status: TnuaActionLifecycleStatus::CancelledFrom,
+                                            directive: contender_directive,
+                                            lin: motor.lin.clone(),
+                                            ang: motor.ang.clone(),
+                                        },
+                                    ));
+                                }
+                                if contender_action.violates_coyote_time() {
+                                    basis.violate_coyote_time();
                                 }
-                                TnuaActionLifecycleDirective::Reschedule { after_seconds } => {
-                                    if matches!(
-                                        controller.action_flow_status,
-                                        TnuaActionFlowStatus::ActionOngoing(_)
-                                    ) {
-                                        controller.action_flow_status =
-                                            TnuaActionFlowStatus::ActionEnded(name);
+                                match contender_directive {
+                                    TnuaActionLifecycleDirective::StillActive => {
+                                        if matches!(
+                                            controller.action_flow_status,
+                                            TnuaActionFlowStatus::ActionOngoing(_)
+                                        ) {
+                                            controller.action_flow_status =
+                                                TnuaActionFlowStatus::Cancelled {
+                                                    old: name,
+                                                    new: contender_name,
+                                                };
+                                        } else {
+                                            controller.action_flow_status =
+                                                TnuaActionFlowStatus::ActionStarted(contender_name);
+                                        }
+                                        controller.current_action_active_for.reset();
+                                        controller.current_action_charge_duration =
+                                            contender_charge_duration;
+                                        Some((contender_name, contender_action))
+                                    }
+                                    TnuaActionLifecycleDirective::Finished => {
+                                        if matches!(
+                                            controller.action_flow_status,
+                                            TnuaActionFlowStatus::ActionOngoing(_)
+                                        ) {
+                                            controller.action_flow_status =
+                                                TnuaActionFlowStatus::ActionEnded(name);
+                                        }
+                                        None
+                                    }
+                                    TnuaActionLifecycleDirective::Reschedule { after_seconds } => {
+                                        if matches!(
+                                            controller.action_flow_status,
+                                            TnuaActionFlowStatus::ActionOngoing(_)
+                                        ) {
+                                            controller.action_flow_status =
+                                                TnuaActionFlowStatus::ActionEnded(name);
+                                        }
+                                        reschedule_action(
+                                            &mut controller.actions_being_fed,
+                                            after_seconds,
+                                        );
+                                        None
                                     }
-                                    reschedule_action(
-                                        &mut controller.actions_being_fed,
-                                        after_seconds,
-                                    );
-                                    None
                                 }
-                            }
-                        } else {
-                            controller.action_flow_status = TnuaActionFlowStatus::ActionEnded(name);
-                            None
-                        };
+                            } else {
+                                controller.action_flow_status = TnuaActionFlowStatus::ActionEnded(name);
+                                None
+                            };
+                        }
+                    }
+                } else if has_valid_contender {
+                    let (contender_name, mut contender_action, _, _) = controller
+                        .contender_action
+                        .take()
+                        .expect("has_valid_contender can only be true if contender_action is Some");
+                    let contender_directive = contender_action.apply(
+                        TnuaActionContext {
+                            frame_duration,
+                            tracker,
+                            proximity_sensor,
+                            basis,
+                            up_direction,
+                            walkable_filter,
+                            sensors: &action_sensors,
+                            charge_duration: contender_charge_duration,
+                            events: &action_events,
+                        },
+                        TnuaActionLifecycleStatus::Initiated,
+                        motor.as_mut(),
+                    );
+                    collect_action_events(contender_name);
+                    if has_hooks {
+                        pending_hooks.push(PendingHook::AfterActionApply(TnuaAfterActionApply {
+                            action_name: contender_name,
+                            lifecycle_status: TnuaActionLifecycleStatus::Initiated,
+                            directive: contender_directive,
+                            lin: motor.lin.clone(),
+                            ang: motor.ang.clone(),
+                        }));
+                    }
+                    if contender_action.violates_coyote_time() {
+                        basis.violate_coyote_time();
                     }
+                    controller.action_flow_status = TnuaActionFlowStatus::ActionStarted(contender_name);
+                    controller.current_action = Some((contender_name, contender_action));
+                    controller.current_action_active_for.reset();
+                    controller.current_action_charge_duration = contender_charge_duration;
                 }
-            } else if has_valid_contender {
-                let (contender_name, mut contender_action, _) = controller
-                    .contender_action
-                    .take()
-                    .expect("has_valid_contender can only be true if contender_action is Some");
-                contender_action.apply(
-                    TnuaActionContext {
-                        frame_duration,
-                        tracker,
-                        proximity_sensor,
-                        basis,
-                        up_direction,
-                    },
-                    TnuaActionLifecycleStatus::Initiated,
-                    motor.as_mut(),
-                );
-                if contender_action.violates_coyote_time() {
-                    basis.violate_coyote_time();
+
+                if controller.current_action.is_some() {
+                    controller.current_action_active_for.tick(timers_delta);
+                }
+
+                let sensor_case_range_for_action =
+                    if let Some((_, current_action)) = &controller.current_action {
+                        current_action.proximity_sensor_cast_range()
+                    } else {
+                        0.0
+                    };
+
+                let requested_action_sensors =
+                    if let Some((_, current_action)) = &controller.current_action {
+                        current_action.sensors(up_direction)
+                    } else {
+                        Vec::new()
+                    };
+                if !(requested_action_sensors.is_empty() && action_sensors.requested.is_empty()) {
+                    action_sensors.requested = requested_action_sensors;
+                }
+
+                sensor.cast_range = sensor_cast_range_for_basis.max(sensor_case_range_for_action);
+                sensor.cast_direction = -up_direction;
+
+                if let Some(gravity_replacement) = gravity_replacement {
+                    motor.lin.acceleration += gravity_replacement;
                 }
-                controller.action_flow_status = TnuaActionFlowStatus::ActionStarted(contender_name);
-                controller.current_action = Some((contender_name, contender_action));
             }
 
-            let sensor_case_range_for_action =
-                if let Some((_, current_action)) = &controller.current_action {
-                    current_action.proximity_sensor_cast_range()
-                } else {
-                    0.0
+            // With `SenseOnly` the backend does not apply the motor, so the teleport is kept until
+            // the controller is enabled again.
+            if let Some((destination, velocity)) = controller
+                .pending_teleport
+                .take_if(|_| tnua_toggle.copied().unwrap_or_default() == TnuaToggle::Enabled)
+            {
+                if let Some((_, basis)) = controller.current_basis.as_mut() {
+                    basis.reset_state();
+                }
+                motor.teleport = Some(destination);
+                motor.lin = match velocity {
+                    Some(velocity) => TnuaVelChange::boost(velocity - tracker.velocity),
+                    None => Default::default(),
                 };
+            }
 
-            sensor.cast_range = sensor_cast_range_for_basis.max(sensor_case_range_for_action);
-            sensor.cast_direction = -up_direction;
-        }
+            if let Some(mut motor_report) = motor_report {
+                *motor_report = TnuaMotorReport {
+                    basis_name: controller.basis_name(),
+                    action_name: controller.action_name(),
+                    lin: motor.lin.clone(),
+                    ang: motor.ang.clone(),
+                    teleport: motor.teleport,
+                    frame_duration,
+                };
+            }
 
-        // Cycle actions_being_fed
-        controller.actions_being_fed.retain(|_, fed_entry| {
-            if fed_entry.fed_this_frame {
-                fed_entry.fed_this_frame = false;
-                if let Some(rescheduled_in) = &mut fed_entry.rescheduled_in {
-                    rescheduled_in.tick(time.delta());
-                }
-                true
-            } else {
-                false
+            if has_hooks {
+                pending_hooks.push(PendingHook::MotorFinalized(TnuaMotorFinalized {
+                    lin: motor.lin.clone(),
+                    ang: motor.ang.clone(),
+                }));
+                parallel_commands.command_scope(|mut commands| {
+                    for hook in pending_hooks {
+                        match hook {
+                            PendingHook::BeforeBasisApply(event) => {
+                                commands.trigger_targets(event, entity);
+                            }
+                            PendingHook::AfterActionApply(event) => {
+                                commands.trigger_targets(event, entity);
+                            }
+                            PendingHook::MotorFinalized(event) => {
+                                commands.trigger_targets(event, entity);
+                            }
+                        }
+                    }
+                });
             }
-        });
 
-        if let Some((contender_name, ..)) = controller.contender_action {
-            if !controller.actions_being_fed.contains_key(contender_name) {
-                controller.contender_action = None;
+            if !pending_action_events.is_empty() {
+                parallel_commands.command_scope(|mut commands| {
+                    for action_event in pending_action_events {
+                        commands.send_event(action_event);
+                    }
+                });
             }
+
+            // Cycle actions_being_fed
+            controller.actions_being_fed.retain(|_, fed_entry| {
+                if fed_entry.fed_this_frame {
+                    fed_entry.fed_this_frame = false;
+                    if let Some(rescheduled_in) = &mut fed_entry.rescheduled_in {
+                        rescheduled_in.tick(timers_delta);
+                    }
+                    true
+                } else {
+                    false
+                }
+            });
+
+            if let Some((contender_name, _, being_fed_for, released_charge)) =
+                &controller.contender_action
+            {
+                // A released charged action is no longer fed, but it should wait until it is
+                // allowed to start - e.g. by the landing lag or the action transition rules.
+                if released_charge.is_none()
+                    && !controller.actions_being_fed.contains_key(contender_name)
+                    && controller.contender_buffer_time <= being_fed_for.elapsed_secs() as Float
+                {
+                    controller.contender_action = None;
+                }
+            }
+        },
+    );
+}
+
+fn apply_basis_in_substeps(
+    basis: &mut dyn DynamicBasis,
+    substeps: usize,
+    ctx: TnuaBasisContext,
+    motor: &mut TnuaMotor,
+) {
+    let substep_duration = ctx.frame_duration / substeps as Float;
+    let cast_direction = ctx.proximity_sensor.cast_direction.adjust_precision();
+    let mut predicted_tracker = TnuaRigidBodyTracker { ..*ctx.tracker };
+    let mut predicted_sensor = TnuaProximitySensor {
+        cast_origin: ctx.proximity_sensor.cast_origin,
+        cast_direction: ctx.proximity_sensor.cast_direction,
+        cast_range: ctx.proximity_sensor.cast_range,
+        output: ctx.proximity_sensor.output.clone(),
+        additional_outputs: ctx.proximity_sensor.additional_outputs.clone(),
+        ..Default::default()
+    };
+
+    let mut lin_acceleration_sum = Vector3::ZERO;
+    let mut lin_change_sum = Vector3::ZERO;
+    let mut ang_acceleration_sum = Vector3::ZERO;
+    let mut ang_change_sum = Vector3::ZERO;
+    for _ in 0..substeps {
+        let mut substep_motor = TnuaMotor::default();
+        basis.apply(
+            TnuaBasisContext {
+                frame_duration: substep_duration,
+                tracker: &predicted_tracker,
+                proximity_sensor: &predicted_sensor,
+                up_direction: ctx.up_direction,
+                walkable_filter: ctx.walkable_filter,
+            },
+            &mut substep_motor,
+        );
+
+        let lin_change =
+            substep_motor.lin.boost + substep_motor.lin.acceleration * substep_duration;
+        let ang_change =
+            substep_motor.ang.boost + substep_motor.ang.acceleration * substep_duration;
+        lin_acceleration_sum += substep_motor.lin.acceleration;
+        lin_change_sum += lin_change;
+        ang_acceleration_sum += substep_motor.ang.acceleration;
+        ang_change_sum += ang_change;
+
+        predicted_tracker.velocity += lin_change + predicted_tracker.gravity * substep_duration;
+        predicted_tracker.angvel += ang_change;
+        let displacement = predicted_tracker.velocity * substep_duration;
+        predicted_tracker.translation += displacement;
+        for output in predicted_sensor
+            .output
+            .iter_mut()
+            .chain(predicted_sensor.additional_outputs.iter_mut())
+        {
+            output.proximity -=
+                (displacement - output.entity_linvel * substep_duration).dot(cast_direction);
+        }
+    }
+
+    // Use the average acceleration, and put the rest of the velocity change in the boost, so
+    // that the total velocity change remains the same.
+    let substeps = substeps as Float;
+    motor.lin.acceleration = lin_acceleration_sum / substeps;
+    motor.lin.boost = lin_change_sum - motor.lin.acceleration * ctx.frame_duration;
+    motor.ang.acceleration = ang_acceleration_sum / substeps;
+    motor.ang.boost = ang_change_sum - motor.ang.acceleration * ctx.frame_duration;
+}
+
+fn apply_mounts_system(
+    time: Res<Time>,
+    time_dilation: Option<Res<TnuaTimeDilation>>,
+    mut query: Query<(
+        Entity,
+        &mut TnuaMounted,
+        &TnuaRigidBodyTracker,
+        &mut TnuaMotor,
+        Option<&TnuaToggle>,
+    )>,
+    mounts_query: Query<&GlobalTransform>,
+    mut commands: Commands,
+) {
+    let (_, frame_duration) = dilated_delta(&time, time_dilation.as_deref());
+    if frame_duration == 0.0 {
+        return;
+    }
+    for (entity, mut mounted, tracker, mut motor, tnua_toggle) in query.iter_mut() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
         }
+        let Ok(mount_transform) = mounts_query.get(mounted.mount) else {
+            // The controller does not touch the motor while the character is mounted, so the
+            // boost from the previous frame must not be left there to get applied again.
+            *motor = TnuaMotor::default();
+            mounted.previous_anchor = None;
+            commands.entity(entity).remove::<TnuaMounted>();
+            continue;
+        };
+        let (_, mount_rotation, mount_translation) =
+            mount_transform.to_scale_rotation_translation();
+        let mount_rotation = mount_rotation.adjust_precision();
+        let anchor_position =
+            mount_translation.adjust_precision() + mount_rotation * mounted.anchor;
+
+        let (anchor_velocity, anchor_angvel) = if let Some((previous_position, previous_rotation)) =
+            mounted.previous_anchor
+        {
+            let (axis, mut angle) = (mount_rotation * previous_rotation.inverse()).to_axis_angle();
+            if float_consts::PI < angle {
+                angle -= float_consts::TAU;
+            }
+            (
+                (anchor_position - previous_position) / frame_duration,
+                axis * angle / frame_duration,
+            )
+        } else {
+            (Vector3::ZERO, Vector3::ZERO)
+        };
+        mounted.previous_anchor = Some((anchor_position, mount_rotation));
+
+        // Cover the distance from the anchor within a single frame, in addition to following the
+        // anchor's movement.
+        let desired_velocity =
+            anchor_velocity + (anchor_position - tracker.translation) / frame_duration;
+        motor.lin.boost = desired_velocity - tracker.velocity;
+        motor.lin.acceleration = -tracker.gravity;
+        motor.ang.boost = anchor_angvel - tracker.angvel;
+        motor.ang.acceleration = Vector3::ZERO;
     }
 }
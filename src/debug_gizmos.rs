@@ -0,0 +1,120 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AsF32, Float};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::{TnuaMotor, TnuaPipelineStages, TnuaProximitySensor};
+
+/// Draws the data Tnua senses and uses with [gizmos](bevy::gizmos).
+///
+/// Only available with the `gizmos` feature. The gizmos are drawn in the [`TnuaGizmos`] gizmo
+/// config group, which can be used to configure them or turn them off via the
+/// [`GizmoConfigStore`].
+///
+/// The plugin draws, for every entity with a [`TnuaProximitySensor`]:
+///
+/// * The cast of the sensor (a line in the cast direction up to the cast range).
+/// * The point the sensor hit and the normal at that point.
+/// * If the entity has a [`TnuaController`] with a [`TnuaBuiltinWalk`] basis - the point the
+///   character tries to float above.
+///
+/// And for every entity with a [`TnuaMotor`], the boost and acceleration of the motor.
+pub struct TnuaDebugGizmosPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaDebugGizmosPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaDebugGizmosPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl Plugin for TnuaDebugGizmosPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<TnuaGizmos>();
+        app.add_systems(
+            self.schedule,
+            (draw_proximity_sensors_system, draw_motors_system).after(TnuaPipelineStages::Logic),
+        );
+    }
+}
+
+/// The gizmo config group used by [`TnuaDebugGizmosPlugin`].
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct TnuaGizmos;
+
+const SENSOR_MISS_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+const SENSOR_HIT_COLOR: Color = Color::srgb(0.0, 1.0, 0.0);
+const NORMAL_COLOR: Color = Color::srgb(0.0, 0.5, 1.0);
+const FLOAT_HEIGHT_COLOR: Color = Color::srgb(1.0, 1.0, 0.0);
+const BOOST_COLOR: Color = Color::srgb(1.0, 0.0, 1.0);
+const ACCELERATION_COLOR: Color = Color::srgb(1.0, 0.5, 0.0);
+
+/// The motor's acceleration is drawn scaled down by this factor, because it is usually much larger
+/// than the boost.
+const ACCELERATION_SCALE: Float = 0.1;
+
+fn draw_proximity_sensors_system(
+    mut gizmos: Gizmos<TnuaGizmos>,
+    query: Query<(
+        &GlobalTransform,
+        &TnuaProximitySensor,
+        Option<&TnuaController>,
+    )>,
+) {
+    for (transform, sensor, controller) in query.iter() {
+        let cast_origin = transform.transform_point(sensor.cast_origin.f32());
+        let cast_direction = *sensor.cast_direction;
+        let cast_end = cast_origin + sensor.cast_range.f32() * cast_direction;
+        if let Some(sensor_output) = &sensor.output {
+            let hit_point = cast_origin + sensor_output.proximity.f32() * cast_direction;
+            gizmos.line(cast_origin, hit_point, SENSOR_HIT_COLOR);
+            gizmos.line(hit_point, cast_end, SENSOR_MISS_COLOR);
+            gizmos.sphere(
+                Isometry3d::from_translation(hit_point),
+                0.05,
+                SENSOR_HIT_COLOR,
+            );
+            gizmos.arrow(hit_point, hit_point + *sensor_output.normal, NORMAL_COLOR);
+        } else {
+            gizmos.line(cast_origin, cast_end, SENSOR_MISS_COLOR);
+        }
+
+        if let Some((walk, _)) =
+            controller.and_then(|controller| controller.concrete_basis::<TnuaBuiltinWalk>())
+        {
+            let float_at = cast_origin + walk.float_height.f32() * cast_direction;
+            gizmos.circle(
+                Isometry3d::new(float_at, Quat::from_rotation_arc(Vec3::Z, -cast_direction)),
+                0.25,
+                FLOAT_HEIGHT_COLOR,
+            );
+        }
+    }
+}
+
+fn draw_motors_system(
+    mut gizmos: Gizmos<TnuaGizmos>,
+    query: Query<(&GlobalTransform, &TnuaMotor)>,
+) {
+    for (transform, motor) in query.iter() {
+        let origin = transform.translation();
+        let boost = motor.lin.boost.f32();
+        if boost != Vec3::ZERO && boost.is_finite() {
+            gizmos.arrow(origin, origin + boost, BOOST_COLOR);
+        }
+        let acceleration = (ACCELERATION_SCALE * motor.lin.acceleration).f32();
+        if acceleration != Vec3::ZERO && acceleration.is_finite() {
+            gizmos.arrow(origin, origin + acceleration, ACCELERATION_COLOR);
+        }
+    }
+}
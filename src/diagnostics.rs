@@ -0,0 +1,159 @@
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticMeasurement, DiagnosticPath, Diagnostics, DiagnosticsStore,
+    RegisterDiagnostic,
+};
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::utils::{HashMap, Instant};
+
+use crate::controller::TnuaController;
+use crate::{TnuaPipelineStages, TnuaSystemSet, TnuaToggle};
+
+/// Registers [diagnostics](bevy::diagnostic) for tracking Tnua's health.
+///
+/// The diagnostics are:
+///
+/// * [`CONTROLLERS`](Self::CONTROLLERS) - the number of active controllers (ones that are not
+///   [disabled](TnuaToggle::Disabled)).
+/// * [`AIRBORNE`](Self::AIRBORNE) - the number of active controllers whose basis considers the
+///   character to be airborne.
+/// * [`SENSORS_DURATION`](Self::SENSORS_DURATION) - the time, in milliseconds, that
+///   [`TnuaPipelineStages::Sensors`] took (which is mostly the casting of the sensors by the
+///   physics backend).
+/// * `tnua/actions/<action name>` - the number of times the action was started in the frame. These
+///   diagnostics are registered the first time each action starts. Use
+///   [`action_path`](Self::action_path) to get their paths.
+///
+/// The diagnostics can be read from the [`DiagnosticsStore`], or logged with
+/// [`LogDiagnosticsPlugin`](bevy::diagnostic::LogDiagnosticsPlugin). Note that Bevy's
+/// [`DiagnosticsPlugin`](bevy::diagnostic::DiagnosticsPlugin) is also required.
+pub struct TnuaDiagnosticsPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaDiagnosticsPlugin {
+    pub const CONTROLLERS: DiagnosticPath = DiagnosticPath::const_new("tnua/controllers");
+    pub const AIRBORNE: DiagnosticPath = DiagnosticPath::const_new("tnua/airborne");
+    pub const SENSORS_DURATION: DiagnosticPath = DiagnosticPath::const_new("tnua/sensors_duration");
+
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+
+    /// The path of the diagnostic that counts the starts of the action with the given name.
+    pub fn action_path(action_name: &str) -> DiagnosticPath {
+        DiagnosticPath::from_components(["tnua", "actions", action_name])
+    }
+}
+
+impl Default for TnuaDiagnosticsPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl Plugin for TnuaDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(Self::CONTROLLERS));
+        app.register_diagnostic(Diagnostic::new(Self::AIRBORNE));
+        app.register_diagnostic(Diagnostic::new(Self::SENSORS_DURATION).with_suffix("ms"));
+        app.init_resource::<SensorsStageStart>();
+        app.add_systems(
+            self.schedule,
+            (
+                start_sensors_timing_system
+                    .in_set(TnuaSystemSet)
+                    .before(TnuaPipelineStages::Sensors),
+                finish_sensors_timing_system
+                    .in_set(TnuaSystemSet)
+                    .after(TnuaPipelineStages::Sensors)
+                    .before(TnuaPipelineStages::SubservientSensors),
+                (measure_controllers_system, measure_actions_system)
+                    .in_set(TnuaSystemSet)
+                    .after(TnuaPipelineStages::Logic),
+            ),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+struct SensorsStageStart(Option<Instant>);
+
+fn start_sensors_timing_system(mut sensors_stage_start: ResMut<SensorsStageStart>) {
+    sensors_stage_start.0 = Some(Instant::now());
+}
+
+fn finish_sensors_timing_system(
+    mut sensors_stage_start: ResMut<SensorsStageStart>,
+    mut diagnostics: Diagnostics,
+) {
+    if let Some(start) = sensors_stage_start.0.take() {
+        diagnostics.add_measurement(&TnuaDiagnosticsPlugin::SENSORS_DURATION, || {
+            start.elapsed().as_secs_f64() * 1000.0
+        });
+    }
+}
+
+fn measure_controllers_system(
+    query: Query<(&TnuaController, Option<&TnuaToggle>)>,
+    mut diagnostics: Diagnostics,
+) {
+    let mut controllers = 0;
+    let mut airborne = 0;
+    for (controller, tnua_toggle) in query.iter() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        controllers += 1;
+        if controller.is_airborne().unwrap_or(false) {
+            airborne += 1;
+        }
+    }
+    diagnostics.add_measurement(&TnuaDiagnosticsPlugin::CONTROLLERS, || controllers as f64);
+    diagnostics.add_measurement(&TnuaDiagnosticsPlugin::AIRBORNE, || airborne as f64);
+}
+
+fn measure_actions_system(
+    query: Query<(&TnuaController, Option<&TnuaToggle>)>,
+    mut diagnostics_store: ResMut<DiagnosticsStore>,
+    mut action_paths: Local<HashMap<&'static str, DiagnosticPath>>,
+) {
+    let mut counts = HashMap::<&'static str, usize>::default();
+    for (controller, tnua_toggle) in query.iter() {
+        match tnua_toggle.copied().unwrap_or_default() {
+            TnuaToggle::Disabled => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if let Some(action_name) = controller.action_flow_status().just_starting() {
+            *counts.entry(action_name).or_default() += 1;
+            action_paths.entry(action_name).or_insert_with(|| {
+                let path = TnuaDiagnosticsPlugin::action_path(action_name);
+                if diagnostics_store.get(&path).is_none() {
+                    diagnostics_store.add(Diagnostic::new(path.clone()));
+                }
+                path
+            });
+        }
+    }
+
+    // Measure all the known actions, including the ones that did not start this frame, so that
+    // the averages will be correct.
+    let time = Instant::now();
+    for (action_name, path) in action_paths.iter() {
+        let Some(diagnostic) = diagnostics_store.get_mut(path) else {
+            continue;
+        };
+        if !diagnostic.is_enabled {
+            continue;
+        }
+        diagnostic.add_measurement(DiagnosticMeasurement {
+            time,
+            value: counts.get(action_name).copied().unwrap_or(0) as f64,
+        });
+    }
+}
@@ -0,0 +1,144 @@
+//! Integration with [leafwing-input-manager](leafwing_input_manager).
+//!
+//! Only available with the `leafwing` feature.
+//!
+//! Instead of writing a controls system that reads the [`ActionState`] and feeds the
+//! [`TnuaController`], implement [`TnuaLeafwingScheme`] on a component that describes how the
+//! actions map to the basis and the actions of Tnua, add [`TnuaLeafwingPlugin`] for that component
+//! type, and place that component on the character entity (together with the [`ActionState`]).
+use std::marker::PhantomData;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use leafwing_input_manager::plugin::InputManagerSystem;
+use leafwing_input_manager::prelude::*;
+
+use crate::controller::TnuaController;
+use crate::TnuaUserControlsSystemSet;
+
+/// A mapping of leafwing-input-manager actions to Tnua inputs.
+///
+/// Implement this on a component that holds the configuration of the character's controls (e.g.
+/// speeds, jump height), and place that component on the character entity.
+pub trait TnuaLeafwingScheme: Component {
+    /// The leafwing-input-manager action type.
+    type Action: Actionlike;
+
+    /// Feed the controller according to the input.
+    ///
+    /// This runs in [`TnuaUserControlsSystemSet`] on every run of the schedule of the
+    /// [`TnuaLeafwingPlugin`].
+    fn feed(&self, input: &TnuaLeafwingInput<Self::Action>, controller: &mut TnuaController);
+}
+
+/// Must be added once for every scheme type, with the same schedule as the
+/// [`TnuaControllerPlugin`](crate::controller::TnuaControllerPlugin).
+pub struct TnuaLeafwingPlugin<S: TnuaLeafwingScheme> {
+    schedule: InternedScheduleLabel,
+    _phantom: PhantomData<fn() -> S>,
+}
+
+impl<S: TnuaLeafwingScheme> TnuaLeafwingPlugin<S> {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: TnuaLeafwingScheme> Default for TnuaLeafwingPlugin<S> {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making a [`TnuaLeafwingScheme`] work.
+impl<S: TnuaLeafwingScheme> Plugin for TnuaLeafwingPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.register_required_components::<S, TnuaLeafwingJustPressedCache<S::Action>>();
+        app.add_systems(
+            PreUpdate,
+            cache_just_pressed_system::<S::Action>.after(InputManagerSystem::Update),
+        );
+        app.add_systems(
+            self.schedule,
+            feed_scheme_system::<S>.in_set(TnuaUserControlsSystemSet),
+        );
+    }
+}
+
+/// Remembers which actions were just pressed, until the controls are fed.
+///
+/// When Tnua runs in a fixed schedule (e.g. [`FixedUpdate`]) it may not run at all in a frame
+/// where an action was just pressed, which will make the controls miss the press. This component
+/// (which [`TnuaLeafwingPlugin`] adds automatically) keeps the presses until the next time the
+/// [`TnuaLeafwingScheme`] is fed.
+#[derive(Component)]
+pub struct TnuaLeafwingJustPressedCache<A: Actionlike> {
+    just_pressed: HashSet<A>,
+}
+
+impl<A: Actionlike> Default for TnuaLeafwingJustPressedCache<A> {
+    fn default() -> Self {
+        Self {
+            just_pressed: Default::default(),
+        }
+    }
+}
+
+/// The input passed to [`TnuaLeafwingScheme::feed`].
+pub struct TnuaLeafwingInput<'a, A: Actionlike> {
+    /// The action state of the character.
+    pub action_state: &'a ActionState<A>,
+    just_pressed: &'a HashSet<A>,
+}
+
+impl<A: Actionlike> TnuaLeafwingInput<'_, A> {
+    /// Check if the action is currently pressed.
+    pub fn pressed(&self, action: &A) -> bool {
+        self.action_state.pressed(action)
+    }
+
+    /// Check if the action was pressed since the last time the scheme was fed.
+    ///
+    /// Unlike [`ActionState::just_pressed`], this does not miss presses when Tnua runs in a fixed
+    /// schedule.
+    pub fn just_pressed(&self, action: &A) -> bool {
+        self.just_pressed.contains(action) || self.action_state.just_pressed(action)
+    }
+
+    /// The value of a dual-axis action (e.g. a joystick or WASD).
+    pub fn axis_pair(&self, action: &A) -> Vec2 {
+        self.action_state.axis_pair(action)
+    }
+}
+
+fn cache_just_pressed_system<A: Actionlike>(
+    mut query: Query<(&ActionState<A>, &mut TnuaLeafwingJustPressedCache<A>)>,
+) {
+    for (action_state, mut cache) in query.iter_mut() {
+        cache.just_pressed.extend(action_state.get_just_pressed());
+    }
+}
+
+fn feed_scheme_system<S: TnuaLeafwingScheme>(
+    mut query: Query<(
+        &S,
+        &ActionState<S::Action>,
+        &mut TnuaLeafwingJustPressedCache<S::Action>,
+        &mut TnuaController,
+    )>,
+) {
+    for (scheme, action_state, mut cache, mut controller) in query.iter_mut() {
+        scheme.feed(
+            &TnuaLeafwingInput {
+                action_state,
+                just_pressed: &cache.just_pressed,
+            },
+            &mut controller,
+        );
+        cache.just_pressed.clear();
+    }
+}
@@ -126,17 +126,32 @@
 //!
 //! [`TnuaController`](crate::prelude::TnuaController) can also be used to retreive data that can
 //! be used to decide which animation to play. A useful helper for that is [`TnuaAnimatingState`].
+mod action_sensors;
 mod animating_helper;
+#[cfg(feature = "animation")]
+pub mod animation;
 mod basis_action_traits;
 pub mod builtins;
 pub mod control_helpers;
 pub mod controller;
+#[cfg(feature = "gizmos")]
+mod debug_gizmos;
+mod diagnostics;
+#[cfg(feature = "leafwing")]
+pub mod leafwing;
 pub mod util;
+mod validation;
+pub use action_sensors::{TnuaActionSensorSpec, TnuaActionSensors, TnuaActionSensorsSetup};
 pub use animating_helper::{TnuaAnimatingState, TnuaAnimatingStateDirective};
 pub use basis_action_traits::{
-    DynamicAction, DynamicBasis, TnuaAction, TnuaActionContext, TnuaActionInitiationDirective,
-    TnuaActionLifecycleDirective, TnuaActionLifecycleStatus, TnuaBasis, TnuaBasisContext,
+    DynamicAction, DynamicBasis, TnuaAction, TnuaActionContext, TnuaActionEventKind,
+    TnuaActionEventQueue, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
+    TnuaActionLifecycleStatus, TnuaBasis, TnuaBasisContext, TnuaGroundDetails,
 };
+#[cfg(feature = "gizmos")]
+pub use debug_gizmos::{TnuaDebugGizmosPlugin, TnuaGizmos};
+pub use diagnostics::TnuaDiagnosticsPlugin;
+pub use validation::{TnuaConfigValidationPlugin, TnuaConfigWarning};
 
 pub mod prelude {
     pub use crate::builtins::{TnuaBuiltinJump, TnuaBuiltinWalk};
@@ -1,8 +1,10 @@
 use bevy::prelude::*;
 use bevy_tnua_physics_integration_layer::math::{
-    AdjustPrecision, Float, Quaternion, Vector2, Vector3,
+    float_consts, AdjustPrecision, Float, Quaternion, Vector2, Vector3,
 };
 
+use crate::builtins::{TnuaBuiltinJump, TnuaBuiltinWalk};
+
 /// Calculate the kinetic energy required to jump to a certain height when different gravity is
 /// applied in different segments of the jump.
 ///
@@ -128,6 +130,198 @@ impl SegmentedJumpInitialVelocityCalculator {
     }
 }
 
+/// The trajectory of a full [`TnuaBuiltinJump`], for level design tools and for AI deciding
+/// whether a jump is possible.
+///
+/// The profile assumes the player holds the jump button for the entire jump (so that it reaches
+/// its full [`height`](TnuaBuiltinJump::height), or fully charges it when it has a
+/// [`charge_height_curve`](TnuaBuiltinJump::charge_height_curve)), that the jump starts from flat
+/// ground, and that nothing but the jump itself affects the vertical velocity. Like the jump height, all the
+/// heights here are measured from the center of the character at its float height.
+///
+/// ```
+/// # use bevy_tnua::builtins::TnuaBuiltinJump;
+/// # use bevy_tnua::util::JumpProfile;
+/// let jump = TnuaBuiltinJump {
+///     height: 4.0,
+///     ..Default::default()
+/// };
+/// let profile = JumpProfile::from_jump(&jump, 9.81, false, None);
+/// // Can the character, running at 10.0, jump over a 15.0 wide gap onto a 2.0 high ledge?
+/// let can_make_it = profile
+///     .reach_to(10.0, 2.0)
+///     .is_some_and(|reach| 15.0 <= reach);
+/// ```
+#[derive(Debug, Clone)]
+pub struct JumpProfile {
+    height: Float,
+    takeoff_velocity: Float,
+    time_to_apex: Float,
+    gravity: Float,
+    fall_extra_gravity: Float,
+    fall_gravity_multiplier: Float,
+}
+
+impl JumpProfile {
+    /// Calculate the profile of a jump under the given gravity (as a positive number).
+    ///
+    /// Set `airborne` for an air jump, so that the jump's
+    /// [`air_overrides`](TnuaBuiltinJump::air_overrides) are used. Pass the walk basis the jump
+    /// is used with (if there is one) to account for its
+    /// [`fall_gravity_multiplier`](TnuaBuiltinWalk::fall_gravity_multiplier).
+    pub fn from_jump(
+        jump: &TnuaBuiltinJump,
+        gravity: Float,
+        airborne: bool,
+        walk: Option<&TnuaBuiltinWalk>,
+    ) -> Self {
+        let jump = if airborne {
+            &jump.with_air_overrides()
+        } else {
+            jump
+        };
+        let height = match &jump.charge_height_curve {
+            Some(charge_height_curve) => jump.height * charge_height_curve.max_factor,
+            None => jump.height,
+        };
+        let segments = [
+            (
+                gravity + jump.peak_prevention_extra_gravity,
+                jump.peak_prevention_at_upward_velocity,
+            ),
+            (gravity, jump.takeoff_above_velocity),
+            (gravity + jump.takeoff_extra_gravity, Float::INFINITY),
+        ];
+        let mut calculator = SegmentedJumpInitialVelocityCalculator::new(height);
+        for (segment_gravity, velocity_threshold) in segments {
+            calculator.add_segment(segment_gravity, velocity_threshold);
+        }
+        let takeoff_velocity = calculator
+            .add_final_segment(gravity + jump.takeoff_extra_gravity)
+            .required_initial_velocity()
+            .expect("`add_final_segment` should have covered remaining height");
+
+        // Like in the calculator, go over the segments from the top of the jump down.
+        let mut time_to_apex = 0.0;
+        let mut segment_top_velocity: Float = 0.0;
+        for (segment_gravity, velocity_threshold) in segments {
+            let segment_bottom_velocity = velocity_threshold.min(takeoff_velocity);
+            if segment_top_velocity < segment_bottom_velocity {
+                time_to_apex += (segment_bottom_velocity - segment_top_velocity) / segment_gravity;
+                segment_top_velocity = segment_bottom_velocity;
+            }
+        }
+
+        Self {
+            height,
+            takeoff_velocity,
+            time_to_apex,
+            gravity,
+            fall_extra_gravity: jump.fall_extra_gravity,
+            fall_gravity_multiplier: walk.map_or(1.0, |walk| walk.fall_gravity_multiplier),
+        }
+    }
+
+    /// The height of the apex of the jump.
+    pub fn height(&self) -> Float {
+        self.height
+    }
+
+    /// The upward velocity the character takes off with.
+    pub fn takeoff_velocity(&self) -> Float {
+        self.takeoff_velocity
+    }
+
+    /// The time, in seconds, from the takeoff to the apex of the jump.
+    pub fn time_to_apex(&self) -> Float {
+        self.time_to_apex
+    }
+
+    /// The gravity that pulls the character down after the apex of the jump.
+    pub fn fall_gravity(&self) -> Float {
+        self.gravity * self.fall_gravity_multiplier + self.fall_extra_gravity
+    }
+
+    /// The time, in seconds, it takes the character to fall from the apex of the jump down to the
+    /// given distance below it.
+    pub fn fall_time(&self, distance: Float) -> Float {
+        (2.0 * distance.max(0.0) / self.fall_gravity()).sqrt()
+    }
+
+    /// The total time, in seconds, the character spends in the air when landing at the same
+    /// height it jumped from.
+    pub fn airtime(&self) -> Float {
+        self.time_to_apex + self.fall_time(self.height)
+    }
+
+    /// The total time, in seconds, the character spends in the air when landing at the given
+    /// height relative to the one it jumped from (negative for landing lower).
+    ///
+    /// Returns `None` if the landing height is above the apex of the jump.
+    pub fn airtime_to(&self, landing_height: Float) -> Option<Float> {
+        if self.height < landing_height {
+            return None;
+        }
+        Some(self.time_to_apex + self.fall_time(self.height - landing_height))
+    }
+
+    /// The horizontal distance the character covers when landing at the same height it jumped
+    /// from, while moving horizontally at the given speed.
+    pub fn reach(&self, horizontal_speed: Float) -> Float {
+        horizontal_speed * self.airtime()
+    }
+
+    /// The horizontal distance the character covers when landing at the given height relative to
+    /// the one it jumped from, while moving horizontally at the given speed.
+    ///
+    /// Returns `None` if the landing height is above the apex of the jump.
+    pub fn reach_to(&self, horizontal_speed: Float, landing_height: Float) -> Option<Float> {
+        Some(horizontal_speed * self.airtime_to(landing_height)?)
+    }
+}
+
+/// Maps the time an action was charged for into a factor for one of its parameters.
+///
+/// Used by actions that support [charging](crate::TnuaActionInitiationDirective::Charge), like
+/// [`TnuaBuiltinJump::charge_height_curve`](crate::builtins::TnuaBuiltinJump::charge_height_curve)
+/// and [`TnuaBuiltinDash::charge_speed_curve`](crate::builtins::TnuaBuiltinDash::charge_speed_curve).
+/// The factor goes linearly from [`min_factor`](Self::min_factor) (when the action was released
+/// immediately) to [`max_factor`](Self::max_factor) (when it was charged for
+/// [`full_charge_time`](Self::full_charge_time) or longer).
+#[derive(Debug, Clone, Copy)]
+pub struct ChargeCurve {
+    /// The time, in seconds, it takes to fully charge the action.
+    pub full_charge_time: Float,
+
+    /// The factor when the action is released without charging.
+    pub min_factor: Float,
+
+    /// The factor when the action is fully charged.
+    pub max_factor: Float,
+}
+
+impl Default for ChargeCurve {
+    fn default() -> Self {
+        Self {
+            full_charge_time: 1.0,
+            min_factor: 0.5,
+            max_factor: 1.0,
+        }
+    }
+}
+
+impl ChargeCurve {
+    /// The factor for an action that was charged for `charge_duration` seconds.
+    pub fn factor(&self, charge_duration: Float) -> Float {
+        let progress = if 0.0 < self.full_charge_time {
+            (charge_duration / self.full_charge_time).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        self.min_factor + (self.max_factor - self.min_factor) * progress
+    }
+}
+
 /// Calculate the rotation around `around_axis` required to rotate the character from
 /// `current_forward` to `desired_forward`.
 pub fn rotation_arc_around_axis(
@@ -148,6 +342,25 @@ pub fn rotation_arc_around_axis(
     Some(rotation_to_set_forward.xyz().z)
 }
 
+/// Like [`Quaternion::from_rotation_arc`], but when `from` and `to` point in opposite directions
+/// the rotation is around `fallback_axis` instead of an arbitrary axis.
+///
+/// `from`, `to` and `fallback_axis` must be normalized, and `fallback_axis` should be
+/// perpendicular to `from`. This matters in 2D, where the only rotation the physics backend can
+/// apply is around the Z axis - an arbitrary axis may be perpendicular to it, and then the
+/// character will never be rotated.
+pub fn rotation_arc_with_fallback_axis(
+    from: Vector3,
+    to: Vector3,
+    fallback_axis: Vector3,
+) -> Quaternion {
+    if from.dot(to) < -1.0 + 1.0e-6 {
+        Quaternion::from_axis_angle(fallback_axis, float_consts::PI)
+    } else {
+        Quaternion::from_rotation_arc(from, to)
+    }
+}
+
 /// Temporary until we get an official release of the physics integration layer crate with
 /// `calc_boost` in it.
 pub(crate) fn calc_boost(
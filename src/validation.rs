@@ -0,0 +1,106 @@
+use std::fmt;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::builtins::{TnuaBuiltinJump, TnuaBuiltinWalk};
+use crate::controller::TnuaController;
+use crate::{TnuaColliderDimensions, TnuaPipelineStages, TnuaSystemSet};
+
+/// A field of a basis or an action that is set to a value known to cause trouble.
+///
+/// Returned by [`TnuaBuiltinWalk::validate`] and [`TnuaBuiltinJump::validate`], and logged by
+/// [`TnuaConfigValidationPlugin`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TnuaConfigWarning {
+    /// The name of the offending field.
+    pub field: &'static str,
+    /// What is wrong with it, and how to fix it.
+    pub message: String,
+}
+
+impl TnuaConfigWarning {
+    pub(crate) fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for TnuaConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: {}", self.field, self.message)
+    }
+}
+
+/// Logs a warning when a [`TnuaBuiltinWalk`] or a [`TnuaBuiltinJump`] is fed with a known-bad
+/// combination of parameters.
+///
+/// The bases and actions are validated when they are used by the controller, with the
+/// [`TnuaColliderDimensions`] of the character if it has them. Each warning is logged once per
+/// entity and field, with the entity and the name of the field, so that it does not flood the log
+/// when the same parameters are fed every frame.
+///
+/// This is meant for development - there is no need to add it in release builds.
+pub struct TnuaConfigValidationPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaConfigValidationPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaConfigValidationPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl Plugin for TnuaConfigValidationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            validate_config_system
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Logic),
+        );
+    }
+}
+
+fn validate_config_system(
+    query: Query<(Entity, &TnuaController, Option<&TnuaColliderDimensions>)>,
+    mut already_warned: Local<HashSet<(Entity, &'static str, &'static str)>>,
+) {
+    for (entity, controller, collider_dimensions) in query.iter() {
+        let walk = controller
+            .concrete_basis::<TnuaBuiltinWalk>()
+            .map(|(walk, _)| walk);
+        let mut warnings = Vec::new();
+        if let Some(walk) = walk {
+            warnings.extend(
+                walk.validate(collider_dimensions)
+                    .into_iter()
+                    .map(|warning| (controller.basis_name(), warning)),
+            );
+        }
+        if let Some((jump, _)) = controller.concrete_action::<TnuaBuiltinJump>() {
+            warnings.extend(
+                jump.validate(walk)
+                    .into_iter()
+                    .map(|warning| (controller.action_name(), warning)),
+            );
+        }
+        for (name, warning) in warnings {
+            let name = name.unwrap_or_default();
+            if already_warned.insert((entity, name, warning.field)) {
+                warn!("Bad configuration for {name} of {entity}: {warning}");
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! A headless harness for running a Tnua character in a real physics simulation.
+//!
+//! The harness steps the app manually, one fixed update per step, so that the results are
+//! deterministic and independent of the machine running the tests.
+
+use std::time::Duration;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy_tnua::prelude::*;
+use bevy_tnua_avian3d::*;
+
+/// The height the character's center floats at when standing on the ground.
+pub const FLOAT_HEIGHT: f32 = 1.5;
+
+/// The distance from the character's center to the bottom of its collider.
+pub const HALF_HEIGHT: f32 = 1.0;
+
+/// Exactly one step of the default fixed timestep (64Hz), so that every update runs exactly one
+/// fixed update.
+const FRAME_DURATION: Duration = Duration::from_micros(15_625);
+
+pub struct TestHarness {
+    pub app: App,
+    pub character: Entity,
+}
+
+impl TestHarness {
+    /// Create an app with a flat ground at Y=0 and a character standing on it.
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            AssetPlugin::default(),
+        ));
+        app.init_asset::<Mesh>();
+        app.add_plugins((
+            PhysicsPlugins::default(),
+            TnuaControllerPlugin::new(FixedUpdate),
+            TnuaAvian3dPlugin::new(FixedUpdate),
+        ));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_DURATION));
+
+        app.world_mut()
+            .spawn((RigidBody::Static, Collider::half_space(Vec3::Y)));
+        let character = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(0.0, FLOAT_HEIGHT, 0.0),
+                RigidBody::Dynamic,
+                Collider::capsule(0.5, 2.0 * HALF_HEIGHT - 1.0),
+                TnuaController::default(),
+                TnuaAvian3dSensorShape(Collider::cylinder(0.49, 0.0)),
+                LockedAxes::ROTATION_LOCKED,
+            ))
+            .id();
+
+        app.finish();
+        app.cleanup();
+
+        Self { app, character }
+    }
+
+    /// Feed the controller and run a single frame.
+    pub fn step(&mut self, feed: impl FnOnce(&mut TnuaController)) {
+        let mut controller = self
+            .app
+            .world_mut()
+            .get_mut::<TnuaController>(self.character)
+            .expect("character must have a controller");
+        feed(&mut controller);
+        self.app.update();
+    }
+
+    /// Run multiple frames, feeding the controller on each one, and assert that the character never
+    /// penetrates the floor.
+    pub fn run(&mut self, frames: usize, mut feed: impl FnMut(&mut TnuaController, usize)) {
+        for frame in 0..frames {
+            self.step(|controller| feed(controller, frame));
+            let position = self.position();
+            assert!(
+                HALF_HEIGHT * 0.9 <= position.y,
+                "character penetrated the floor on frame {frame} (position is {position})"
+            );
+        }
+    }
+
+    /// Run multiple frames with only the walk basis fed, until the character is at rest.
+    pub fn settle(&mut self) {
+        self.run(120, |controller, _| controller.basis(walk(Vec3::ZERO)));
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.app
+            .world()
+            .get::<Transform>(self.character)
+            .expect("character must have a transform")
+            .translation
+    }
+}
+
+/// The walk basis used by the tests.
+pub fn walk(desired_velocity: Vec3) -> TnuaBuiltinWalk {
+    TnuaBuiltinWalk {
+        desired_velocity,
+        float_height: FLOAT_HEIGHT,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,277 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_tnua::builtins::TnuaBuiltinDash;
+use bevy_tnua::controller::TnuaTimeDilation;
+use bevy_tnua::prelude::*;
+use bevy_tnua::util::ChargeCurve;
+
+use common::{walk, TestHarness, FLOAT_HEIGHT};
+
+#[test]
+fn character_floats_at_float_height() {
+    let mut harness = TestHarness::new();
+    harness.settle();
+    let position = harness.position();
+    assert!(
+        (position.y - FLOAT_HEIGHT).abs() < 0.05,
+        "character should float at {FLOAT_HEIGHT}, but is at {position}"
+    );
+}
+
+#[test]
+fn jump_reaches_its_height() {
+    const JUMP_HEIGHT: f32 = 4.0;
+
+    let mut harness = TestHarness::new();
+    harness.settle();
+    let mut max_height = f32::NEG_INFINITY;
+    for _ in 0..120 {
+        harness.step(|controller| {
+            controller.basis(walk(Vec3::ZERO));
+            controller.action(TnuaBuiltinJump {
+                height: JUMP_HEIGHT,
+                ..Default::default()
+            });
+        });
+        max_height = max_height.max(harness.position().y);
+    }
+    let reached = max_height - FLOAT_HEIGHT;
+    assert!(
+        (reached - JUMP_HEIGHT).abs() < 0.25,
+        "jump should reach {JUMP_HEIGHT} but reached {reached}"
+    );
+}
+
+#[test]
+fn character_stops_within_distance() {
+    let mut harness = TestHarness::new();
+    harness.settle();
+    harness.run(60, |controller, _| {
+        controller.basis(walk(10.0 * Vec3::X));
+    });
+    let stopped_at = harness.position();
+    harness.run(60, |controller, _| {
+        controller.basis(walk(Vec3::ZERO));
+    });
+    let distance = harness.position().x - stopped_at.x;
+    assert!(
+        (0.0..2.0).contains(&distance),
+        "character should stop within 2.0 units, but moved {distance} units"
+    );
+}
+
+#[test]
+fn time_dilation_keeps_the_trajectory() {
+    const FRAMES: usize = 60;
+
+    let feed = |controller: &mut TnuaController| {
+        controller.basis(walk(5.0 * Vec3::X));
+        controller.action(TnuaBuiltinJump {
+            height: 4.0,
+            ..Default::default()
+        });
+    };
+
+    let mut normal = TestHarness::new();
+    normal.settle();
+    let normal_start = normal.position();
+    let mut normal_trajectory = Vec::with_capacity(FRAMES);
+    for _ in 0..FRAMES {
+        normal.step(feed);
+        normal_trajectory.push(normal.position() - normal_start);
+    }
+
+    let mut dilated = TestHarness::new();
+    dilated.settle();
+    dilated
+        .app
+        .world_mut()
+        .resource_mut::<Time<Physics>>()
+        .set_relative_speed(0.5);
+    dilated.app.insert_resource(TnuaTimeDilation(0.5));
+    let dilated_start = dilated.position();
+    for (frame, expected) in normal_trajectory.into_iter().enumerate() {
+        dilated.step(feed);
+        dilated.step(feed);
+        let actual = dilated.position() - dilated_start;
+        assert!(
+            actual.distance(expected) < 0.1,
+            "on frame {frame} the character should be at {expected}, but is at {actual}"
+        );
+    }
+}
+
+#[test]
+fn fall_gravity_multiplier_does_not_affect_upward_launch() {
+    const LAUNCH_HEIGHT: f32 = 10.0;
+
+    let launch_apex = |fall_gravity_multiplier: f32| {
+        let mut harness = TestHarness::new();
+        harness.settle();
+        // Launch from above the ground, so that the float spring does not interfere.
+        harness.step(|controller| {
+            controller.basis(walk(Vec3::ZERO));
+            controller.teleport(LAUNCH_HEIGHT * Vec3::Y, Some(15.0 * Vec3::Y));
+        });
+        let mut apex = f32::NEG_INFINITY;
+        for _ in 0..120 {
+            harness.step(|controller| {
+                controller.basis(TnuaBuiltinWalk {
+                    fall_gravity_multiplier,
+                    ..walk(Vec3::ZERO)
+                });
+            });
+            apex = apex.max(harness.position().y);
+        }
+        apex
+    };
+
+    let normal_apex = launch_apex(1.0);
+    let multiplied_apex = launch_apex(3.0);
+    assert!(
+        LAUNCH_HEIGHT + 5.0 < normal_apex,
+        "the launch should take the character well above the ground, but the apex was {normal_apex}"
+    );
+    assert!(
+        (normal_apex - multiplied_apex).abs() < 0.1,
+        "the apex should be {normal_apex} regardless of the fall gravity multiplier, but was {multiplied_apex}"
+    );
+}
+
+#[test]
+fn backward_speed_multiplier_slows_walking_backward() {
+    let walking_speed = |desired_forward: Dir3| {
+        let mut harness = TestHarness::new();
+        harness.settle();
+        let start = harness.position();
+        harness.run(120, |controller, _| {
+            controller.basis(TnuaBuiltinWalk {
+                desired_forward: Some(desired_forward),
+                backward_speed_multiplier: 0.5,
+                ..walk(4.0 * Vec3::X)
+            });
+        });
+        (harness.position().x - start.x) / 120.0 * 64.0
+    };
+
+    let forward_speed = walking_speed(Dir3::X);
+    let backward_speed = walking_speed(Dir3::NEG_X);
+    assert!(
+        1.5 < backward_speed,
+        "walking backward should still move the character, but the speed was {backward_speed}"
+    );
+    assert!(
+        (backward_speed / forward_speed - 0.5).abs() < 0.1,
+        "walking backward should be at half the forward speed ({forward_speed}), but was {backward_speed}"
+    );
+}
+
+#[test]
+fn teleport_moves_the_character_and_stops_it() {
+    let mut harness = TestHarness::new();
+    harness.settle();
+    harness.run(30, |controller, _| controller.basis(walk(5.0 * Vec3::X)));
+    let destination = Vec3::new(-20.0, FLOAT_HEIGHT, 10.0);
+    harness.step(|controller| {
+        controller.basis(walk(Vec3::ZERO));
+        controller.teleport(destination, Some(Vec3::ZERO));
+    });
+    harness.settle();
+    let position = harness.position();
+    assert!(
+        position.distance(destination) < 0.1,
+        "character should stay at {destination}, but is at {position}"
+    );
+}
+
+#[test]
+fn charged_jump_height_depends_on_charge_time() {
+    const JUMP_HEIGHT: f32 = 4.0;
+
+    let charged_jump_height = |charge_frames: usize| {
+        let mut harness = TestHarness::new();
+        harness.settle();
+        let jump = TnuaBuiltinJump {
+            height: JUMP_HEIGHT,
+            charge_height_curve: Some(ChargeCurve {
+                full_charge_time: 0.5,
+                min_factor: 0.5,
+                max_factor: 1.0,
+            }),
+            ..Default::default()
+        };
+        harness.run(charge_frames, |controller, _| {
+            controller.basis(walk(Vec3::ZERO));
+            controller.action(jump.clone());
+        });
+        assert!(
+            (harness.position().y - FLOAT_HEIGHT).abs() < 0.05,
+            "the jump should not start while it is being charged"
+        );
+        let mut max_height = f32::NEG_INFINITY;
+        for _ in 0..120 {
+            harness.step(|controller| controller.basis(walk(Vec3::ZERO)));
+            max_height = max_height.max(harness.position().y);
+        }
+        max_height - FLOAT_HEIGHT
+    };
+
+    let tapped = charged_jump_height(1);
+    let fully_charged = charged_jump_height(64);
+    assert!(
+        (fully_charged - JUMP_HEIGHT).abs() < 0.25,
+        "a fully charged jump should reach {JUMP_HEIGHT} but reached {fully_charged}"
+    );
+    assert!(
+        (tapped - 0.5 * JUMP_HEIGHT).abs() < 0.25,
+        "a tapped jump should reach {} but reached {tapped}",
+        0.5 * JUMP_HEIGHT,
+    );
+}
+
+#[test]
+fn charged_dash_speed_depends_on_charge_time() {
+    const DASH_SPEED: f32 = 20.0;
+
+    let charged_dash_speed = |charge_frames: usize| {
+        let mut harness = TestHarness::new();
+        harness.settle();
+        let dash = TnuaBuiltinDash {
+            displacement: 10.0 * Vec3::X,
+            speed: DASH_SPEED,
+            charge_speed_curve: Some(ChargeCurve {
+                full_charge_time: 0.5,
+                min_factor: 0.5,
+                max_factor: 1.0,
+            }),
+            ..Default::default()
+        };
+        harness.run(charge_frames, |controller, _| {
+            controller.basis(walk(Vec3::ZERO));
+            controller.action(dash.clone());
+        });
+        let mut previous_x = harness.position().x;
+        let mut max_speed = f32::NEG_INFINITY;
+        for _ in 0..30 {
+            harness.step(|controller| controller.basis(walk(Vec3::ZERO)));
+            let x = harness.position().x;
+            max_speed = max_speed.max((x - previous_x) * 64.0);
+            previous_x = x;
+        }
+        max_speed
+    };
+
+    let tapped = charged_dash_speed(1);
+    let fully_charged = charged_dash_speed(64);
+    assert!(
+        (fully_charged - DASH_SPEED).abs() < 1.0,
+        "a fully charged dash should move at {DASH_SPEED} but moved at {fully_charged}"
+    );
+    assert!(
+        (tapped - 0.5 * DASH_SPEED).abs() < 1.0,
+        "a tapped dash should move at {} but moved at {tapped}",
+        0.5 * DASH_SPEED,
+    );
+}